@@ -1,3 +1,32 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
 fn main() {
+    embed_locales();
     tauri_build::build()
 }
+
+/// Embed every locale file the frontend has (`src/i18n/locales/*.json`)
+/// for `src/i18n.rs`, so adding a language needs no Rust changes.
+fn embed_locales() {
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let dir = manifest_dir.join("../src/i18n/locales");
+    println!("cargo:rerun-if-changed={}", dir.display());
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .expect("read src/i18n/locales")
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+
+    let mut out = String::from("&[\n");
+    for path in files {
+        let tag = path.file_stem().unwrap().to_string_lossy().into_owned();
+        let path = path.canonicalize().unwrap();
+        writeln!(out, "    ({:?}, include_str!({:?})),", tag, path.display().to_string()).unwrap();
+    }
+    out.push(']');
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    std::fs::write(out_dir.join("locales.rs"), out).unwrap();
+}
@@ -0,0 +1,105 @@
+//! Everything the user can trigger from outside a window's own controls:
+//! the tray, hotkeys, `floaty://` links, CLI commands, the control API and
+//! the `run_action` command all name an [`Action`], and `dispatch` in
+//! `lib.rs` is the one place that carries it out.
+//!
+//! Serialized as `{ "action": "setShape", "shape": "mickey" }`; the same
+//! JSON is the control API's request body and a tray item's menu id.
+
+use serde::{Deserialize, Serialize};
+
+use crate::settings::ShapePreset;
+use crate::RecordingRegion;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum Action {
+    ToggleVisibility,
+    SetCameraVisible {
+        visible: bool,
+    },
+    ToggleLock,
+    ToggleKeyboard,
+    SetKeyboardVisible {
+        visible: bool,
+    },
+    ToggleClickThrough,
+    ToggleAlwaysOnTop,
+    StartRecording,
+    StopRecording,
+    ToggleRecording,
+    /// Pause a running recording, or resume a paused one.
+    TogglePause,
+    /// Pause a running recording. During the countdown, the recording
+    /// starts paused.
+    PauseRecording,
+    /// Resume a paused recording, or start one if none is running.
+    ResumeRecording,
+    /// Record `region`; without one, open the interactive region picker.
+    SelectRegion {
+        #[serde(default)]
+        region: Option<RecordingRegion>,
+    },
+    ResetRegion,
+    SetShape {
+        shape: ShapePreset,
+    },
+    /// Switch to the next shape preset, wrapping around.
+    CycleShape,
+    SetScale {
+        scale: f64,
+    },
+    /// Switch the camera window to the device with this id.
+    SelectCamera {
+        id: String,
+    },
+    /// Open one of the recent recordings (see `RECENT_RECORDINGS_KEY`) in
+    /// the default player. Other paths are refused.
+    OpenRecording {
+        path: String,
+    },
+    /// Show one of the recent recordings in the file manager.
+    RevealRecording {
+        path: String,
+    },
+    OpenSettings,
+    ActivateProfile {
+        id: String,
+    },
+    /// Switch to the next profile in list order, wrapping around.
+    ActivateNextProfile,
+    GetStatus,
+    Quit,
+}
+
+impl Action {
+    /// The id of a menu item that runs this action.
+    pub fn menu_id(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// The action behind a menu item id from [`Action::menu_id`].
+    pub fn from_menu_id(id: &str) -> Option<Action> {
+        serde_json::from_str(id).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn menu_ids_round_trip() {
+        for action in [
+            Action::ToggleVisibility,
+            Action::ToggleRecording,
+            Action::SetShape { shape: ShapePreset::Mickey },
+            Action::ActivateProfile { id: "p1700000000000".into() },
+            Action::Quit,
+        ] {
+            assert_eq!(Action::from_menu_id(&action.menu_id()), Some(action));
+        }
+        assert_eq!(Action::ToggleLock.menu_id(), r#"{"action":"toggleLock"}"#);
+        assert_eq!(Action::from_menu_id("profile:p1"), None);
+    }
+}
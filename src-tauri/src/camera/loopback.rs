@@ -0,0 +1,108 @@
+//! The virtual camera's sink on Linux: a v4l2loopback device, fed with
+//! plain `write()`s of whole YUYV frames. Load the module with
+//! `exclusive_caps=1` so browsers and call apps list the device as a
+//! camera; without a `video_nr` it takes the next free `/dev/video*`.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+
+use super::output::FrameSink;
+use super::v4l2::{
+    text, xioctl, Capability, Dev, Format, V4L2_BUF_TYPE_VIDEO_OUTPUT, V4L2_CAP_VIDEO_OUTPUT,
+    V4L2_COLORSPACE_SRGB, V4L2_FIELD_NONE, VIDIOC_QUERYCAP, VIDIOC_S_FMT,
+};
+use super::DeviceTree;
+
+/// What v4l2loopback reports as its driver name.
+const DRIVER: &str = "v4l2 loopback";
+
+pub struct Loopback {
+    file: File,
+    path: String,
+    card: String,
+}
+
+impl Loopback {
+    /// Open `path`, or the first v4l2loopback device that takes output,
+    /// and set it to `width`×`height` YUYV.
+    pub fn open(path: Option<&str>, width: u32, height: u32) -> Result<Self, String> {
+        let ((file, card), path) = match path {
+            Some(path) => (open_output(path).map_err(|e| format!("{}: {}", path, e))?, path.to_string()),
+            None => Dev
+                .nodes()
+                .into_iter()
+                .find_map(|path| Some((open_output(&path).ok()?, path)))
+                .ok_or("no v4l2loopback device found (load it with `modprobe v4l2loopback exclusive_caps=1`)")?,
+        };
+        let mut format = Format {
+            kind: V4L2_BUF_TYPE_VIDEO_OUTPUT,
+            ..Default::default()
+        };
+        let pix = &mut format.fmt.pix;
+        pix.width = width;
+        pix.height = height;
+        pix.pixel_format = u32::from_le_bytes(*b"YUYV");
+        pix.field = V4L2_FIELD_NONE;
+        pix.bytes_per_line = width * 2;
+        pix.size_image = width * height * 2;
+        pix.colorspace = V4L2_COLORSPACE_SRGB;
+        xioctl(&file, VIDIOC_S_FMT, &mut format).map_err(|e| format!("{}: setting the format: {}", path, e))?;
+        Ok(Self { file, path, card })
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The device's name, which camera lists show as its label.
+    pub fn card(&self) -> &str {
+        &self.card
+    }
+}
+
+impl FrameSink for Loopback {
+    fn write_frame(&mut self, yuyv: &[u8]) -> io::Result<()> {
+        self.file.write_all(yuyv)
+    }
+}
+
+/// Open `path` for writing if it is a v4l2loopback device taking output,
+/// and return it with its name. With `exclusive_caps=1`, one that another
+/// app already feeds reports capture only.
+fn open_output(path: &str) -> io::Result<(File, String)> {
+    let file = OpenOptions::new().write(true).open(path)?;
+    let mut cap = Capability::default();
+    xioctl(&file, VIDIOC_QUERYCAP, &mut cap)?;
+    if text(&cap.driver) != DRIVER {
+        return Err(io::Error::other(format!("not a v4l2loopback device ({})", text(&cap.driver))));
+    }
+    if cap.caps() & V4L2_CAP_VIDEO_OUTPUT == 0 {
+        return Err(io::Error::other("already fed by another app"));
+    }
+    Ok((file, text(&cap.card)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::output::VirtualCamera;
+    use std::io::Read;
+
+    /// Needs `modprobe v4l2loopback exclusive_caps=1`; run with
+    /// `--ignored`. Publishes a frame and reads it back as a call app would.
+    #[test]
+    #[ignore]
+    fn publishes_to_a_loopback_device() {
+        let (width, height) = (640, 360);
+        let mut camera = VirtualCamera::open(None, width, height).unwrap();
+        let device = camera.device().to_string();
+        let gray = vec![128; (width * height * 4) as usize];
+        camera.push(width, height, &gray).unwrap();
+
+        let mut reader = File::open(&device).unwrap();
+        let mut frame = vec![0; (width * height * 2) as usize];
+        camera.push(width, height, &gray).unwrap();
+        reader.read_exact(&mut frame).unwrap();
+        assert_eq!(frame[..4], [126, 128, 126, 128]);
+    }
+}
@@ -0,0 +1,390 @@
+//! Native camera enumeration for `list_cameras`: every capture device with
+//! the modes (pixel format, size, frame rates) its driver offers, and
+//! whether another app is streaming from it. The webview can only list
+//! cameras by label, so this is what settings and diagnostics use to tell
+//! which modes are valid and why a camera fails before `getUserMedia` runs.
+//!
+//! Only Linux (V4L2, `v4l2.rs`) is implemented; elsewhere the list is
+//! empty. Probing goes through [`DeviceTree`], so the grouping and mode
+//! logic here is tested against a fixture instead of real hardware.
+//!
+//! [`watch`] reports cameras being plugged in and out; [`resolve`] picks
+//! the camera to use when the selected one is not connected. [`output`]
+//! goes the other way, publishing the styled picture as a virtual camera.
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "linux")]
+mod loopback;
+pub mod output;
+#[cfg(target_os = "linux")]
+mod v4l2;
+pub mod watch;
+
+/// A camera, as the webview reports it (`report_cameras`) or as found
+/// natively. The native fields are absent from webview reports.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraDevice {
+    pub device_id: String,
+    pub label: String,
+    pub group_id: Option<String>,
+    /// Device node, e.g. `/dev/video0`.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Kernel driver, e.g. `uvcvideo` or `v4l2 loopback`.
+    #[serde(default)]
+    pub driver: Option<String>,
+    /// Largest first within each pixel format.
+    #[serde(default)]
+    pub modes: Vec<CameraMode>,
+    /// Another process is streaming from the device.
+    #[serde(default)]
+    pub busy: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraMode {
+    /// FourCC, e.g. `YUYV` or `MJPG`.
+    pub pixel_format: String,
+    pub width: u32,
+    pub height: u32,
+    /// Highest first.
+    pub frame_rates: Vec<f64>,
+}
+
+/// One device node as its driver describes it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub path: String,
+    pub card: String,
+    pub driver: String,
+    pub bus_info: String,
+    /// Delivers video frames (as opposed to e.g. UVC metadata nodes).
+    pub capture: bool,
+    pub formats: Vec<NodeFormat>,
+    pub busy: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeFormat {
+    pub fourcc: u32,
+    pub sizes: Vec<NodeSize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeSize {
+    pub width: u32,
+    pub height: u32,
+    /// Frame intervals as `(numerator, denominator)` seconds.
+    pub intervals: Vec<(u32, u32)>,
+}
+
+/// Where nodes come from: `/dev` on a real system, a fixture in tests.
+pub trait DeviceTree {
+    /// Candidate node paths.
+    fn nodes(&self) -> Vec<String>;
+    fn probe(&self, path: &str) -> std::io::Result<Node>;
+}
+
+/// Every capture device on this machine.
+#[cfg(target_os = "linux")]
+pub fn list() -> Vec<CameraDevice> {
+    enumerate(&v4l2::Dev)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn list() -> Vec<CameraDevice> {
+    Vec::new()
+}
+
+/// Probe every node in `tree` and describe its capture devices. Nodes that
+/// cannot be opened (gone, no permission) are skipped.
+pub fn enumerate(tree: &impl DeviceTree) -> Vec<CameraDevice> {
+    tree.nodes()
+        .iter()
+        .filter_map(|path| match tree.probe(path) {
+            Ok(node) => Some(node),
+            Err(e) => {
+                eprintln!("Skipping camera {}: {}", path, e);
+                None
+            }
+        })
+        .filter(|node| node.capture)
+        .map(describe)
+        .collect()
+}
+
+fn describe(node: Node) -> CameraDevice {
+    let mut modes: Vec<CameraMode> = node
+        .formats
+        .iter()
+        .flat_map(|format| {
+            format.sizes.iter().map(|size| CameraMode {
+                pixel_format: fourcc(format.fourcc),
+                width: size.width,
+                height: size.height,
+                frame_rates: frame_rates(&size.intervals),
+            })
+        })
+        .collect();
+    // Stable, so formats keep the driver's order (its preference).
+    modes.sort_by_key(|m| std::cmp::Reverse(m.width as u64 * m.height as u64));
+    let order: Vec<String> = node.formats.iter().map(|f| fourcc(f.fourcc)).collect();
+    modes.sort_by_key(|m| order.iter().position(|f| *f == m.pixel_format));
+    CameraDevice {
+        device_id: node.path.clone(),
+        label: node.card,
+        // Nodes of one physical camera share the bus.
+        group_id: Some(node.bus_info).filter(|b| !b.is_empty()),
+        path: Some(node.path),
+        driver: Some(node.driver),
+        modes,
+        busy: node.busy,
+    }
+}
+
+/// `YUYV` for `v4l2_fourcc('Y', 'U', 'Y', 'V')`.
+pub fn fourcc(code: u32) -> String {
+    code.to_le_bytes().iter().map(|&b| b as char).collect::<String>().trim_end().to_string()
+}
+
+fn frame_rates(intervals: &[(u32, u32)]) -> Vec<f64> {
+    let mut rates: Vec<f64> = intervals
+        .iter()
+        .filter(|(num, den)| *num > 0 && *den > 0)
+        .map(|&(num, den)| (den as f64 / num as f64 * 100.0).round() / 100.0)
+        .collect();
+    rates.sort_by(|a, b| b.total_cmp(a));
+    rates.dedup();
+    rates
+}
+
+/// The common sizes a driver that accepts a range of sizes (stepwise or
+/// continuous) can produce, plus its largest.
+pub fn stepwise_sizes(min: (u32, u32), max: (u32, u32), step: (u32, u32)) -> Vec<(u32, u32)> {
+    const COMMON: [(u32, u32); 6] = [(320, 240), (640, 480), (1280, 720), (1920, 1080), (2560, 1440), (3840, 2160)];
+    let fits = |value: u32, lo: u32, hi: u32, step: u32| (lo..=hi).contains(&value) && (value - lo).is_multiple_of(step.max(1));
+    let mut sizes: Vec<(u32, u32)> = COMMON
+        .into_iter()
+        .filter(|&(w, h)| fits(w, min.0, max.0, step.0) && fits(h, min.1, max.1, step.1))
+        .collect();
+    if !sizes.contains(&max) {
+        sizes.push(max);
+    }
+    sizes
+}
+
+/// `/dev` entries that are V4L2 video nodes (`video0`, `video12`), in
+/// numeric order.
+pub fn video_nodes(names: impl Iterator<Item = String>) -> Vec<String> {
+    let mut numbered: Vec<(u32, String)> = names
+        .filter_map(|name| Some((name.strip_prefix("video")?.parse().ok()?, name)))
+        .collect();
+    numbered.sort();
+    numbered.into_iter().map(|(_, name)| format!("/dev/{}", name)).collect()
+}
+
+/// Cameras in `next` but not in `prev`, and the other way round, by
+/// device id.
+pub fn diff(prev: &[CameraDevice], next: &[CameraDevice]) -> (Vec<CameraDevice>, Vec<CameraDevice>) {
+    let missing_from = |list: &[CameraDevice], device: &CameraDevice| !list.iter().any(|d| d.device_id == device.device_id);
+    let added = next.iter().filter(|d| missing_from(prev, d)).cloned().collect();
+    let removed = prev.iter().filter(|d| missing_from(next, d)).cloned().collect();
+    (added, removed)
+}
+
+/// Whether `pattern` (a selected id, a `cameraPriority` entry or a camera
+/// override's `match`) names `device`: its id exactly, or its label
+/// ignoring case, where `*` stands for any run of characters (`Logitech*`).
+/// Mirrored by `matchesCamera` in `src/lib/camera.ts`.
+pub fn matches(pattern: &str, device: &CameraDevice) -> bool {
+    device.device_id == pattern || glob(&pattern.to_lowercase(), &device.label.to_lowercase())
+}
+
+fn glob(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = text.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+/// The camera to open out of `available`: the selected one if it is
+/// connected, else the first connected entry of `priority`, else the first
+/// camera. `None` when no camera is listed (yet), in which case the
+/// caller should try the selection as is.
+pub fn resolve<'a>(
+    available: &'a [CameraDevice],
+    selected: Option<&str>,
+    priority: &[String],
+) -> Option<&'a str> {
+    if available.is_empty() {
+        return None;
+    }
+    selected
+        .into_iter()
+        .chain(priority.iter().map(String::as_str))
+        .find_map(|entry| available.iter().find(|d| matches(entry, d)))
+        .or(available.first())
+        .map(|d| d.device_id.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YUYV: u32 = u32::from_le_bytes(*b"YUYV");
+    const MJPG: u32 = u32::from_le_bytes(*b"MJPG");
+
+    struct Fixture(Vec<Node>);
+
+    impl DeviceTree for Fixture {
+        fn nodes(&self) -> Vec<String> {
+            let mut paths: Vec<String> = self.0.iter().map(|n| n.path.clone()).collect();
+            paths.push("/dev/video9".into());
+            paths
+        }
+
+        fn probe(&self, path: &str) -> std::io::Result<Node> {
+            self.0
+                .iter()
+                .find(|n| n.path == path)
+                .cloned()
+                .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::PermissionDenied))
+        }
+    }
+
+    fn node(path: &str, capture: bool, formats: Vec<NodeFormat>) -> Node {
+        Node {
+            path: path.into(),
+            card: "Integrated Camera".into(),
+            driver: "uvcvideo".into(),
+            bus_info: "usb-0000:00:14.0-8".into(),
+            capture,
+            formats,
+            busy: false,
+        }
+    }
+
+    fn size(width: u32, height: u32, intervals: &[(u32, u32)]) -> NodeSize {
+        NodeSize { width, height, intervals: intervals.to_vec() }
+    }
+
+    #[test]
+    fn lists_capture_nodes_with_their_modes() {
+        let webcam = node(
+            "/dev/video0",
+            true,
+            vec![
+                NodeFormat { fourcc: MJPG, sizes: vec![size(640, 480, &[(1, 30)]), size(1920, 1080, &[(1, 30), (1, 15), (1, 30)])] },
+                NodeFormat { fourcc: YUYV, sizes: vec![size(640, 480, &[(1001, 30000), (1, 0)])] },
+            ],
+        );
+        let metadata = node("/dev/video1", false, Vec::new());
+        let loopback = Node {
+            path: "/dev/video2".into(),
+            card: "Dummy video device (0x0000)".into(),
+            driver: "v4l2 loopback".into(),
+            bus_info: String::new(),
+            busy: true,
+            ..node("", true, Vec::new())
+        };
+
+        let devices = enumerate(&Fixture(vec![webcam, metadata, loopback]));
+        assert_eq!(devices.len(), 2);
+        let cam = &devices[0];
+        assert_eq!((cam.device_id.as_str(), cam.group_id.as_deref()), ("/dev/video0", Some("usb-0000:00:14.0-8")));
+        let modes: Vec<_> = cam.modes.iter().map(|m| (m.pixel_format.as_str(), m.width, m.frame_rates.clone())).collect();
+        assert_eq!(
+            modes,
+            [("MJPG", 1920, vec![30.0, 15.0]), ("MJPG", 640, vec![30.0]), ("YUYV", 640, vec![29.97])]
+        );
+        assert!(!cam.busy);
+        assert!(devices[1].busy);
+        assert_eq!(devices[1].group_id, None);
+    }
+
+    #[test]
+    fn expands_size_ranges_to_common_sizes() {
+        assert_eq!(
+            stepwise_sizes((320, 240), (1920, 1080), (16, 8)),
+            [(320, 240), (640, 480), (1280, 720), (1920, 1080)]
+        );
+        assert_eq!(stepwise_sizes((2, 2), (1000, 1000), (2, 2)), [(320, 240), (640, 480), (1000, 1000)]);
+    }
+
+    fn camera(id: &str, label: &str) -> CameraDevice {
+        CameraDevice {
+            device_id: id.into(),
+            label: label.into(),
+            group_id: None,
+            path: None,
+            driver: None,
+            modes: Vec::new(),
+            busy: false,
+        }
+    }
+
+    #[test]
+    fn resolves_the_selected_camera_or_a_fallback() {
+        let builtin = camera("a1", "Integrated Camera");
+        let dock = camera("b2", "Logitech BRIO");
+        let both = [builtin.clone(), dock.clone()];
+        let priority = ["Logitech BRIO".to_string(), "a1".to_string()];
+
+        assert_eq!(resolve(&both, Some("a1"), &priority), Some("a1"));
+        // Selected camera unplugged: first connected priority entry.
+        assert_eq!(resolve(&both, Some("gone"), &priority), Some("b2"));
+        assert_eq!(resolve(std::slice::from_ref(&builtin), Some("gone"), &priority), Some("a1"));
+        assert_eq!(resolve(std::slice::from_ref(&builtin), Some("gone"), &[]), Some("a1"));
+        assert_eq!(resolve(std::slice::from_ref(&dock), None, &[]), Some("b2"));
+        assert_eq!(resolve(&[], Some("a1"), &priority), None);
+    }
+
+    #[test]
+    fn matches_ids_and_label_patterns() {
+        let brio = camera("b2", "Logitech BRIO (046d:085e)");
+        assert!(matches("b2", &brio));
+        assert!(matches("logitech brio (046d:085e)", &brio));
+        assert!(matches("Logitech*", &brio));
+        assert!(matches("*brio*", &brio));
+        assert!(matches("Logitech*(*)", &brio));
+        assert!(matches("*", &brio));
+        assert!(!matches("Logitech", &brio));
+        assert!(!matches("*C920*", &brio));
+        assert!(!matches("BRIO*", &brio));
+        assert!(!matches("Logitech*(*)x", &brio));
+
+        let dock = [camera("a1", "Integrated Camera"), brio.clone()];
+        assert_eq!(resolve(&dock, Some("gone"), &["*BRIO*".into()]), Some("b2"));
+    }
+
+    #[test]
+    fn diffs_by_device_id() {
+        let a = camera("/dev/video0", "Integrated Camera");
+        let b = camera("/dev/video2", "Logitech BRIO");
+        let (added, removed) = diff(std::slice::from_ref(&a), std::slice::from_ref(&b));
+        assert_eq!((added, removed), (vec![b.clone()], vec![a.clone()]));
+        assert_eq!(diff(&[a.clone(), b.clone()], &[b, a]), (Vec::new(), Vec::new()));
+    }
+
+    #[test]
+    fn finds_video_nodes() {
+        let names = ["video10", "video2", "vhci", "video-ir", "video0"].map(String::from);
+        assert_eq!(video_nodes(names.into_iter()), ["/dev/video0", "/dev/video2", "/dev/video10"]);
+        assert_eq!(fourcc(YUYV), "YUYV");
+        assert_eq!(fourcc(u32::from_le_bytes(*b"Y8  ")), "Y8");
+    }
+}
@@ -0,0 +1,185 @@
+//! The virtual camera: the camera window's styled picture (shape mask,
+//! mirror, beauty) published as a webcam that call apps can open. The
+//! window renders each frame to RGBA at the configured size and rate and
+//! passes it in; [`VirtualCamera`] converts it to YUYV, the format call
+//! apps accept most widely, and hands it to its [`FrameSink`]. Only Linux
+//! has a sink (v4l2loopback, `loopback.rs`).
+
+use std::io;
+
+use super::CameraDevice;
+
+/// Where converted frames go.
+pub trait FrameSink: Send {
+    fn write_frame(&mut self, yuyv: &[u8]) -> io::Result<()>;
+}
+
+pub struct VirtualCamera {
+    sink: Box<dyn FrameSink>,
+    /// Device node the sink writes to, e.g. `/dev/video10`.
+    device: String,
+    /// The device's name, as camera lists show it.
+    label: String,
+    /// Device as configured (`None` = the first loopback device found).
+    requested: Option<String>,
+    width: u32,
+    height: u32,
+    yuyv: Vec<u8>,
+}
+
+impl VirtualCamera {
+    pub fn new(
+        sink: Box<dyn FrameSink>,
+        device: String,
+        label: String,
+        requested: Option<String>,
+        width: u32,
+        height: u32,
+    ) -> Self {
+        Self {
+            sink,
+            device,
+            label,
+            requested,
+            width,
+            height,
+            yuyv: Vec::new(),
+        }
+    }
+
+    /// Open `device` (or the first v4l2loopback device) for `width`×`height`
+    /// frames.
+    #[cfg(target_os = "linux")]
+    pub fn open(device: Option<&str>, width: u32, height: u32) -> Result<Self, String> {
+        let sink = super::loopback::Loopback::open(device, width, height)?;
+        let (path, label) = (sink.path().to_string(), sink.card().to_string());
+        Ok(Self::new(Box::new(sink), path, label, device.map(String::from), width, height))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn open(_device: Option<&str>, _width: u32, _height: u32) -> Result<Self, String> {
+        Err("the virtual camera is only available on Linux (v4l2loopback)".into())
+    }
+
+    /// Whether this is already the output the settings ask for.
+    pub fn serves(&self, device: Option<&str>, width: u32, height: u32) -> bool {
+        self.requested.as_deref() == device && (self.width, self.height) == (width, height)
+    }
+
+    pub fn device(&self) -> &str {
+        &self.device
+    }
+
+    /// Frame size it takes, `(width, height)`.
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Whether `camera` is this output's own device. Once fed, a loopback
+    /// device reports capture and is listed as a camera like any other
+    /// (natively by path, by the webview under its name); opening it would
+    /// show the camera window its own picture.
+    pub fn is_output(&self, camera: &CameraDevice) -> bool {
+        camera.path.as_deref() == Some(self.device.as_str()) || camera.label == self.label
+    }
+
+    /// Publish one `width`×`height` RGBA frame. Frames of another size
+    /// (rendered before a resolution change) are rejected.
+    pub fn push(&mut self, width: u32, height: u32, rgba: &[u8]) -> Result<(), String> {
+        if (width, height) != (self.width, self.height) {
+            return Err(format!("frame is {}x{}, expected {}x{}", width, height, self.width, self.height));
+        }
+        let expected = width as usize * height as usize * 4;
+        if rgba.len() != expected {
+            return Err(format!(
+                "frame is {} bytes, expected {} for {}x{} RGBA",
+                rgba.len(),
+                expected,
+                width,
+                height
+            ));
+        }
+        rgba_to_yuyv(rgba, &mut self.yuyv);
+        self.sink.write_frame(&self.yuyv).map_err(|e| format!("{}: {}", self.device, e))
+    }
+}
+
+/// Convert RGBA to YUYV (4:2:2, BT.601 limited range): each pair of pixels
+/// becomes `Y0 U Y1 V`, with the pair's chroma averaged. Alpha is dropped;
+/// the window paints the area outside the shape opaque. Widths are even
+/// (see `settings::validate`).
+pub fn rgba_to_yuyv(rgba: &[u8], yuyv: &mut Vec<u8>) {
+    yuyv.clear();
+    yuyv.reserve(rgba.len() / 2);
+    for pair in rgba.chunks_exact(8) {
+        let (r0, g0, b0) = (pair[0] as i32, pair[1] as i32, pair[2] as i32);
+        let (r1, g1, b1) = (pair[4] as i32, pair[5] as i32, pair[6] as i32);
+        let luma = |r: i32, g: i32, b: i32| (((66 * r + 129 * g + 25 * b + 128) >> 8) + 16) as u8;
+        let (r, g, b) = ((r0 + r1) / 2, (g0 + g1) / 2, (b0 + b1) / 2);
+        let u = (((-38 * r - 74 * g + 112 * b + 128) >> 8) + 128) as u8;
+        let v = (((112 * r - 94 * g - 18 * b + 128) >> 8) + 128) as u8;
+        yuyv.extend_from_slice(&[luma(r0, g0, b0), u, luma(r1, g1, b1), v]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Frames(Arc<Mutex<Vec<Vec<u8>>>>);
+
+    impl FrameSink for Frames {
+        fn write_frame(&mut self, yuyv: &[u8]) -> io::Result<()> {
+            self.0.lock().unwrap().push(yuyv.to_vec());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn converts_to_limited_range_yuyv() {
+        // Black, white, then a red/blue pair.
+        let rgba = [0, 0, 0, 255, 255, 255, 255, 255, 255, 0, 0, 255, 0, 0, 255, 255];
+        let mut yuyv = Vec::new();
+        rgba_to_yuyv(&rgba, &mut yuyv);
+        assert_eq!(yuyv[..4], [16, 128, 235, 128]);
+        // Red and blue: Y 82 and 41; the shared chroma is magenta-ish.
+        assert_eq!(yuyv[4..], [82, 165, 41, 175]);
+    }
+
+    #[test]
+    fn pushes_frames_of_the_configured_size() {
+        let frames = Frames::default();
+        let mut camera = VirtualCamera::new(Box::new(frames.clone()), "/dev/video10".into(), "Floaty".into(), None, 4, 2);
+        assert!(camera.serves(None, 4, 2));
+        assert!(!camera.serves(Some("/dev/video10"), 4, 2));
+        assert!(!camera.serves(None, 8, 2));
+
+        camera.push(4, 2, &[128; 4 * 2 * 4]).unwrap();
+        let err = camera.push(8, 2, &[128; 8 * 2 * 4]).unwrap_err();
+        assert!(err.contains("expected 4x2"), "{}", err);
+        let err = camera.push(4, 2, &[128; 8 * 2 * 4]).unwrap_err();
+        assert!(err.contains("expected 32 for 4x2"), "{}", err);
+        let written = frames.0.lock().unwrap();
+        assert_eq!(written.len(), 1);
+        assert_eq!(written[0].len(), 4 * 2 * 2);
+    }
+
+    #[test]
+    fn recognizes_its_own_device() {
+        let camera = VirtualCamera::new(Box::new(Frames::default()), "/dev/video10".into(), "Floaty".into(), None, 4, 2);
+        let device = |label: &str, path: Option<&str>| CameraDevice {
+            device_id: "x".into(),
+            label: label.into(),
+            group_id: None,
+            path: path.map(String::from),
+            driver: None,
+            modes: Vec::new(),
+            busy: false,
+        };
+        assert!(camera.is_output(&device("Floaty", None)));
+        assert!(camera.is_output(&device("Loopback", Some("/dev/video10"))));
+        assert!(!camera.is_output(&device("FaceTime HD Camera", Some("/dev/video0"))));
+    }
+}
@@ -0,0 +1,328 @@
+//! Video4Linux2 probing: the `/dev/video*` nodes, read with the driver's
+//! enumeration ioctls (`videodev2.h`). Nodes are opened non-blocking and
+//! never streamed from. The ioctl plumbing is shared with the virtual
+//! camera's output side (`loopback.rs`).
+
+use std::ffi::{c_int, c_ulong};
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::OpenOptionsExt;
+
+use super::{stepwise_sizes, video_nodes, DeviceTree, Node, NodeFormat, NodeSize};
+
+extern "C" {
+    fn ioctl(fd: c_int, request: c_ulong, ...) -> c_int;
+}
+
+const O_NONBLOCK: i32 = 0o4000;
+const EBUSY: i32 = 16;
+const EINVAL: i32 = 22;
+
+const V4L2_CAP_VIDEO_CAPTURE: u32 = 0x0000_0001;
+pub(super) const V4L2_CAP_VIDEO_OUTPUT: u32 = 0x0000_0002;
+const V4L2_CAP_DEVICE_CAPS: u32 = 0x8000_0000;
+const V4L2_BUF_TYPE_VIDEO_CAPTURE: u32 = 1;
+pub(super) const V4L2_BUF_TYPE_VIDEO_OUTPUT: u32 = 2;
+pub(super) const V4L2_FIELD_NONE: u32 = 1;
+pub(super) const V4L2_COLORSPACE_SRGB: u32 = 8;
+const V4L2_MEMORY_MMAP: u32 = 1;
+const V4L2_FRMSIZE_TYPE_DISCRETE: u32 = 1;
+const V4L2_FRMIVAL_TYPE_DISCRETE: u32 = 1;
+
+// The ioctl structs mirror the kernel's layout, unread fields included.
+
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+pub(super) struct Capability {
+    pub driver: [u8; 16],
+    pub card: [u8; 32],
+    bus_info: [u8; 32],
+    version: u32,
+    capabilities: u32,
+    device_caps: u32,
+    reserved: [u32; 3],
+}
+
+impl Capability {
+    /// The node's own capabilities, where the driver reports them apart
+    /// from the whole device's.
+    pub fn caps(&self) -> u32 {
+        if self.capabilities & V4L2_CAP_DEVICE_CAPS != 0 {
+            self.device_caps
+        } else {
+            self.capabilities
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+struct FmtDesc {
+    index: u32,
+    kind: u32,
+    flags: u32,
+    description: [u8; 32],
+    pixel_format: u32,
+    mbus_code: u32,
+    reserved: [u32; 3],
+}
+
+/// `v4l2_frmsizeenum`; `sizes` is the discrete `(width, height)` or the
+/// stepwise `min_width, max_width, step_width, min_height, ...` union.
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+struct FrmSizeEnum {
+    index: u32,
+    pixel_format: u32,
+    kind: u32,
+    sizes: [u32; 6],
+    reserved: [u32; 2],
+}
+
+/// `v4l2_frmivalenum`; `intervals` is the discrete fraction or the
+/// stepwise `min, max, step` fractions.
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+struct FrmIvalEnum {
+    index: u32,
+    pixel_format: u32,
+    width: u32,
+    height: u32,
+    kind: u32,
+    intervals: [u32; 6],
+    reserved: [u32; 2],
+}
+
+/// `v4l2_pix_format`.
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+pub(super) struct PixFormat {
+    pub width: u32,
+    pub height: u32,
+    pub pixel_format: u32,
+    pub field: u32,
+    pub bytes_per_line: u32,
+    pub size_image: u32,
+    pub colorspace: u32,
+    private: u32,
+    flags: u32,
+    ycbcr_enc: u32,
+    quantization: u32,
+    xfer_func: u32,
+}
+
+/// `v4l2_format` with the `pix` member of its 200-byte union; the union
+/// holds pointers, hence the pointer alignment.
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+pub(super) struct Format {
+    pub kind: u32,
+    pub fmt: FormatUnion,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+pub(super) struct FormatUnion {
+    pub pix: PixFormat,
+    rest: [u8; 152],
+    align: [usize; 0],
+}
+
+impl Default for FormatUnion {
+    fn default() -> Self {
+        Self { pix: PixFormat::default(), rest: [0; 152], align: [] }
+    }
+}
+
+#[repr(C)]
+#[derive(Default)]
+#[allow(dead_code)]
+struct RequestBuffers {
+    count: u32,
+    kind: u32,
+    memory: u32,
+    capabilities: u32,
+    flags: u8,
+    reserved: [u8; 3],
+}
+
+/// `_IOR`/`_IOWR('V', nr, T)` on the common ioctl encoding.
+const fn request<T>(write: bool, nr: c_ulong) -> c_ulong {
+    let dir: c_ulong = if write { 3 } else { 2 };
+    (dir << 30) | ((std::mem::size_of::<T>() as c_ulong) << 16) | ((b'V' as c_ulong) << 8) | nr
+}
+
+pub(super) const VIDIOC_QUERYCAP: c_ulong = request::<Capability>(false, 0);
+const VIDIOC_ENUM_FMT: c_ulong = request::<FmtDesc>(true, 2);
+pub(super) const VIDIOC_S_FMT: c_ulong = request::<Format>(true, 5);
+const VIDIOC_REQBUFS: c_ulong = request::<RequestBuffers>(true, 8);
+const VIDIOC_ENUM_FRAMESIZES: c_ulong = request::<FrmSizeEnum>(true, 74);
+const VIDIOC_ENUM_FRAMEINTERVALS: c_ulong = request::<FrmIvalEnum>(true, 75);
+
+pub(super) fn xioctl<T>(file: &File, request: c_ulong, arg: &mut T) -> io::Result<()> {
+    // SAFETY: `arg` is the `#[repr(C)]` struct `request` is encoded for.
+    if unsafe { ioctl(file.as_raw_fd(), request, arg as *mut T) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Run an enumeration ioctl for index 0, 1, ... until the driver reports
+/// the end (`EINVAL`).
+fn enumerate<T: Default>(file: &File, request: c_ulong, init: impl Fn(u32) -> T) -> io::Result<Vec<T>> {
+    let mut items = Vec::new();
+    for index in 0.. {
+        let mut item = init(index);
+        match xioctl(file, request, &mut item) {
+            Ok(()) => items.push(item),
+            Err(e) if e.raw_os_error() == Some(EINVAL) => break,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(items)
+}
+
+pub(super) fn text(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+}
+
+/// The system's video nodes.
+pub struct Dev;
+
+impl DeviceTree for Dev {
+    fn nodes(&self) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir("/dev") else {
+            return Vec::new();
+        };
+        video_nodes(entries.filter_map(|e| e.ok()?.file_name().into_string().ok()))
+    }
+
+    fn probe(&self, path: &str) -> io::Result<Node> {
+        let file = OpenOptions::new().read(true).write(true).custom_flags(O_NONBLOCK).open(path)?;
+        let mut cap = Capability::default();
+        xioctl(&file, VIDIOC_QUERYCAP, &mut cap)?;
+        let capture = cap.caps() & V4L2_CAP_VIDEO_CAPTURE != 0;
+        // A driver that fails enumerating its formats still has a usable
+        // camera: list it without modes rather than not at all.
+        let formats = if capture { formats(&file).unwrap_or_default() } else { Vec::new() };
+        Ok(Node {
+            path: path.to_string(),
+            card: text(&cap.card),
+            driver: text(&cap.driver),
+            bus_info: text(&cap.bus_info),
+            capture,
+            formats,
+            busy: capture && busy(&file),
+        })
+    }
+}
+
+fn formats(file: &File) -> io::Result<Vec<NodeFormat>> {
+    let descs = enumerate(file, VIDIOC_ENUM_FMT, |index| FmtDesc {
+        index,
+        kind: V4L2_BUF_TYPE_VIDEO_CAPTURE,
+        ..Default::default()
+    })?;
+    descs
+        .into_iter()
+        .map(|desc| {
+            let fourcc = desc.pixel_format;
+            let sizes = frame_sizes(file, fourcc)?
+                .into_iter()
+                .map(|(width, height)| {
+                    Ok(NodeSize { width, height, intervals: frame_intervals(file, fourcc, width, height)? })
+                })
+                .collect::<io::Result<_>>()?;
+            Ok(NodeFormat { fourcc, sizes })
+        })
+        .collect()
+}
+
+fn frame_sizes(file: &File, fourcc: u32) -> io::Result<Vec<(u32, u32)>> {
+    let sizes = enumerate(file, VIDIOC_ENUM_FRAMESIZES, |index| FrmSizeEnum {
+        index,
+        pixel_format: fourcc,
+        ..Default::default()
+    })?;
+    Ok(match sizes.first() {
+        Some(first) if first.kind != V4L2_FRMSIZE_TYPE_DISCRETE => {
+            let [min_w, max_w, step_w, min_h, max_h, step_h] = first.sizes;
+            stepwise_sizes((min_w, min_h), (max_w, max_h), (step_w, step_h))
+        }
+        _ => sizes.iter().map(|s| (s.sizes[0], s.sizes[1])).collect(),
+    })
+}
+
+/// Discrete intervals as listed; for a range, its shortest and longest.
+fn frame_intervals(file: &File, fourcc: u32, width: u32, height: u32) -> io::Result<Vec<(u32, u32)>> {
+    let intervals = enumerate(file, VIDIOC_ENUM_FRAMEINTERVALS, |index| FrmIvalEnum {
+        index,
+        pixel_format: fourcc,
+        width,
+        height,
+        ..Default::default()
+    })?;
+    Ok(match intervals.first() {
+        Some(first) if first.kind != V4L2_FRMIVAL_TYPE_DISCRETE => {
+            let [min_num, min_den, max_num, max_den, ..] = first.intervals;
+            vec![(min_num, min_den), (max_num, max_den)]
+        }
+        _ => intervals.iter().map(|i| (i.intervals[0], i.intervals[1])).collect(),
+    })
+}
+
+/// Whether another process is streaming: the driver refuses buffers to a
+/// second user while one holds them. Any buffers granted are released.
+fn busy(file: &File) -> bool {
+    let mut req = RequestBuffers {
+        count: 1,
+        kind: V4L2_BUF_TYPE_VIDEO_CAPTURE,
+        memory: V4L2_MEMORY_MMAP,
+        ..Default::default()
+    };
+    match xioctl(file, VIDIOC_REQBUFS, &mut req) {
+        Ok(()) => {
+            req.count = 0;
+            let _ = xioctl(file, VIDIOC_REQBUFS, &mut req);
+            false
+        }
+        Err(e) => e.raw_os_error() == Some(EBUSY),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ioctl_requests_match_videodev2() {
+        assert_eq!(VIDIOC_QUERYCAP, 0x8068_5600);
+        assert_eq!(VIDIOC_ENUM_FMT, 0xc040_5602);
+        assert_eq!(VIDIOC_REQBUFS, 0xc014_5608);
+        assert_eq!(VIDIOC_ENUM_FRAMESIZES, 0xc02c_564a);
+        assert_eq!(VIDIOC_ENUM_FRAMEINTERVALS, 0xc034_564b);
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(VIDIOC_S_FMT, 0xc0d0_5605);
+        #[cfg(target_pointer_width = "32")]
+        assert_eq!(VIDIOC_S_FMT, 0xc0cc_5605);
+    }
+
+    /// Needs a camera or `modprobe v4l2loopback`; run with `--ignored`.
+    #[test]
+    #[ignore]
+    fn lists_this_machines_cameras() {
+        let devices = super::super::enumerate(&Dev);
+        assert!(!devices.is_empty(), "no capture devices under /dev");
+        for device in &devices {
+            println!("{} {:?} busy={} modes={}", device.label, device.path, device.busy, device.modes.len());
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Camera hot-plug: [`spawn`] calls back whenever a video node appears in
+//! or disappears from `/dev`, which is where udev publishes V4L2 devices.
+//! Watching the nodes rather than subscribing to udev's netlink socket
+//! needs no extra library and only fires once a node exists to be opened.
+//!
+//! Elsewhere there is no native watcher; the webview's `devicechange`
+//! event is all there is.
+
+#[cfg(target_os = "linux")]
+use std::sync::mpsc;
+#[cfg(target_os = "linux")]
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "linux")]
+use notify::{RecursiveMode, Watcher};
+
+/// udev creates the node, then sets its group and permissions; a camera
+/// also brings several nodes at once. Video node changes closer together
+/// than this are handled as one.
+#[cfg(target_os = "linux")]
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Watch `/dev` on a background thread and call `on_change` after each
+/// burst of video nodes being added or removed. Runs for the life of the
+/// process.
+#[cfg(target_os = "linux")]
+pub fn spawn(on_change: impl Fn() + Send + 'static) -> Result<(), String> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(|e| e.to_string())?;
+    watcher
+        .watch(std::path::Path::new("/dev"), RecursiveMode::NonRecursive)
+        .map_err(|e| e.to_string())?;
+    std::thread::spawn(move || {
+        // Dropping the watcher would end the watch.
+        let _watcher = watcher;
+        while let Ok(event) = rx.recv() {
+            if !touches_video_node(&event) {
+                continue;
+            }
+            settle(&rx);
+            on_change();
+        }
+    });
+    Ok(())
+}
+
+/// Wait until no video node has come or gone for [`DEBOUNCE`]. Other `/dev`
+/// churn (ttys, ptys) is drained without extending the wait, so it cannot
+/// hold the callback back.
+#[cfg(target_os = "linux")]
+fn settle(rx: &mpsc::Receiver<notify::Result<notify::Event>>) {
+    let mut deadline = Instant::now() + DEBOUNCE;
+    loop {
+        match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(event) if touches_video_node(&event) => deadline = Instant::now() + DEBOUNCE,
+            Ok(_) => {}
+            Err(_) => return,
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn spawn(_on_change: impl Fn() + Send + 'static) -> Result<(), String> {
+    Ok(())
+}
+
+/// Whether `event` created or removed a `/dev/video*` node.
+#[cfg(target_os = "linux")]
+fn touches_video_node(event: &notify::Result<notify::Event>) -> bool {
+    match event {
+        Ok(event) => {
+            (event.kind.is_create() || event.kind.is_remove())
+                && !super::video_nodes(
+                    event
+                        .paths
+                        .iter()
+                        .filter_map(|p| p.file_name()?.to_str().map(String::from)),
+                )
+                .is_empty()
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, EventKind, ModifyKind, RemoveKind};
+
+    fn event(kind: EventKind, path: &str) -> notify::Result<notify::Event> {
+        Ok(notify::Event::new(kind).add_path(path.into()))
+    }
+
+    #[test]
+    fn only_video_nodes_coming_and_going_count() {
+        assert!(touches_video_node(&event(EventKind::Create(CreateKind::File), "/dev/video2")));
+        assert!(touches_video_node(&event(EventKind::Remove(RemoveKind::Any), "/dev/video10")));
+        assert!(!touches_video_node(&event(EventKind::Modify(ModifyKind::Any), "/dev/video0")));
+        assert!(!touches_video_node(&event(EventKind::Create(CreateKind::File), "/dev/ttyUSB0")));
+        assert!(!touches_video_node(&event(EventKind::Create(CreateKind::File), "/dev/video-ir")));
+    }
+
+    #[test]
+    fn other_dev_churn_does_not_postpone_the_callback() {
+        let (tx, rx) = mpsc::channel();
+        let churn = std::thread::spawn(move || {
+            let until = Instant::now() + DEBOUNCE * 6;
+            while Instant::now() < until {
+                let _ = tx.send(event(EventKind::Create(CreateKind::File), "/dev/pts/7"));
+                std::thread::sleep(DEBOUNCE / 10);
+            }
+        });
+        let started = Instant::now();
+        settle(&rx);
+        assert!(started.elapsed() < DEBOUNCE * 3, "{:?}", started.elapsed());
+        churn.join().unwrap();
+    }
+}
@@ -0,0 +1,200 @@
+//! Command-line control of a running instance.
+//!
+//! `floaty record start`, `floaty shape set mickey`, ... are parsed here.
+//! A second launch hands its arguments to the running instance through the
+//! single-instance plugin and exits, so the commands act on the live app
+//! and reuse the same functions as the tray and the windows. If nothing is
+//! running, the launch becomes the app and runs the command once it has
+//! started. `help` and `settings get` only read, so they are answered by the
+//! launching process itself (the forwarding channel has no way back).
+
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::deeplink;
+use crate::settings::{self, ShapePreset};
+use crate::RecordingRegion;
+
+pub const USAGE: &str = "\
+Usage: floaty <command>
+
+  record start|stop|toggle      Start, stop or toggle screen recording
+  camera show|hide              Show or hide the camera window
+  region set <x>,<y>,<w>,<h>    Record this region (physical pixels)
+  region reset                  Record the full screen
+  shape set <preset>            circle, roundedSquare or mickey
+  settings get [key]            Print the settings (or one, by camelCase key)
+  settings set <key>=<value>... Change settings; values are JSON, or text
+  help                          Show this message
+
+Commands other than `help` and `settings get` act on the running instance.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordAction {
+    Start,
+    Stop,
+    Toggle,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    Help,
+    Record(RecordAction),
+    Camera { visible: bool },
+    RegionSet(RecordingRegion),
+    RegionReset,
+    ShapeSet(ShapePreset),
+    SettingsGet(Option<String>),
+    /// A settings patch, as taken by `patch_app_settings`.
+    SettingsSet(Map<String, Value>),
+}
+
+impl CliCommand {
+    /// Answered by the launching process; never forwarded.
+    pub fn is_local(&self) -> bool {
+        matches!(self, CliCommand::Help | CliCommand::SettingsGet(_))
+    }
+}
+
+/// Parse the arguments after the program name. `Ok(None)` is a plain
+/// launch: no arguments, only flags added by the OS or a launcher, or a
+/// `floaty://` link (run by the deep-link plugin, see `deeplink`).
+pub fn parse(args: &[String]) -> Result<Option<CliCommand>, String> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let command = match args.as_slice() {
+        [] => return Ok(None),
+        [first, ..] if first.starts_with('-') && !matches!(*first, "-h" | "--help") => return Ok(None),
+        [link] if deeplink::is_link(link) => return Ok(None),
+        ["help" | "-h" | "--help"] => CliCommand::Help,
+        ["record", action] => CliCommand::Record(match *action {
+            "start" => RecordAction::Start,
+            "stop" => RecordAction::Stop,
+            "toggle" => RecordAction::Toggle,
+            other => return Err(format!("unknown record action: {}", other)),
+        }),
+        ["camera", "show"] => CliCommand::Camera { visible: true },
+        ["camera", "hide"] => CliCommand::Camera { visible: false },
+        ["region", "set", spec] => CliCommand::RegionSet(parse_region(spec)?),
+        ["region", "reset"] => CliCommand::RegionReset,
+        ["shape", "set", preset] => CliCommand::ShapeSet(
+            serde_json::from_value(Value::from(*preset)).map_err(|_| format!("unknown shape: {}", preset))?,
+        ),
+        ["settings", "get"] => CliCommand::SettingsGet(None),
+        ["settings", "get", key] => CliCommand::SettingsGet(Some(key.to_string())),
+        ["settings", "set", pairs @ ..] if !pairs.is_empty() => CliCommand::SettingsSet(parse_pairs(pairs)?),
+        _ => return Err(format!("unknown command: {}", args.join(" "))),
+    };
+    Ok(Some(command))
+}
+
+fn parse_region(spec: &str) -> Result<RecordingRegion, String> {
+    let invalid = || format!("region must be x,y,width,height; got {}", spec);
+    let parts = spec
+        .split(',')
+        .map(|p| p.trim().parse::<i32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+    let [x, y, width, height] = parts[..] else {
+        return Err(invalid());
+    };
+    if width <= 0 || height <= 0 {
+        return Err(format!("region must have a positive size; got {}x{}", width, height));
+    }
+    Ok(RecordingRegion { x, y, width, height })
+}
+
+/// `key=value` pairs into a patch. A value that is not JSON is taken as
+/// text, so `theme=dark` and `theme="dark"` mean the same.
+fn parse_pairs(pairs: &[&str]) -> Result<Map<String, Value>, String> {
+    pairs
+        .iter()
+        .map(|pair| {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected key=value, got {}", pair))?;
+            let value = serde_json::from_str(value).unwrap_or_else(|_| Value::from(value));
+            Ok((key.trim().to_string(), value))
+        })
+        .collect()
+}
+
+/// `settings get`: read the stored settings straight from the store file,
+/// loaded the same way the app loads them.
+pub fn settings_get(store_path: &Path, settings_key: &str, key: Option<&str>) -> Result<String, String> {
+    let stored = match std::fs::read_to_string(store_path) {
+        Ok(text) => serde_json::from_str::<Value>(&text)
+            .map_err(|e| format!("{}: {}", store_path.display(), e))?
+            .get(settings_key)
+            .cloned(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.to_string()),
+    };
+    let loaded = match stored {
+        Some(value) => settings::migrate::load(&value).settings,
+        None => settings::AppSettings::default(),
+    };
+    let value = serde_json::to_value(loaded).map_err(|e| e.to_string())?;
+    let value = match key {
+        Some(key) => value.get(key).cloned().ok_or_else(|| format!("no setting named {}", key))?,
+        None => value,
+    };
+    serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(line: &str) -> Result<Option<CliCommand>, String> {
+        let args: Vec<String> = line.split_whitespace().map(String::from).collect();
+        parse(&args)
+    }
+
+    #[test]
+    fn parses_subcommands() {
+        assert_eq!(parse_str("").unwrap(), None);
+        assert_eq!(parse_str("-psn_0_12345").unwrap(), None);
+        assert_eq!(parse_str("floaty://record/start").unwrap(), None);
+        assert_eq!(parse_str("--help").unwrap(), Some(CliCommand::Help));
+        assert_eq!(parse_str("record toggle").unwrap(), Some(CliCommand::Record(RecordAction::Toggle)));
+        assert_eq!(parse_str("camera hide").unwrap(), Some(CliCommand::Camera { visible: false }));
+        assert_eq!(parse_str("shape set roundedSquare").unwrap(), Some(CliCommand::ShapeSet(ShapePreset::RoundedSquare)));
+        assert_eq!(parse_str("settings get scale").unwrap(), Some(CliCommand::SettingsGet(Some("scale".into()))));
+        match parse_str("region set 10,20,1280,720").unwrap() {
+            Some(CliCommand::RegionSet(r)) => assert_eq!((r.x, r.y, r.width, r.height), (10, 20, 1280, 720)),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        for line in ["record pause", "camera", "shape set hexagon", "region set 1,2,3", "region set 0,0,0,10", "settings set", "settings set theme", "frobnicate"] {
+            assert!(parse_str(line).is_err(), "{}", line);
+        }
+    }
+
+    #[test]
+    fn settings_values_are_json_or_text() {
+        let Some(CliCommand::SettingsSet(patch)) = parse_str("settings set theme=dark recordingFps=60 selectedCameraId=null").unwrap() else {
+            panic!("not a settings set");
+        };
+        assert_eq!(patch["theme"], "dark");
+        assert_eq!(patch["recordingFps"], 60);
+        assert_eq!(patch["selectedCameraId"], Value::Null);
+    }
+
+    #[test]
+    fn settings_get_reads_the_store_file() {
+        let dir = std::env::temp_dir().join(format!("floaty-cli-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("settings.json");
+        assert_eq!(settings_get(&path, "app_settings", Some("recordingFps")).unwrap(), "30");
+
+        std::fs::write(&path, r#"{ "app_settings": { "recordingFps": 60, "theme": "neon" } }"#).unwrap();
+        assert_eq!(settings_get(&path, "app_settings", Some("recordingFps")).unwrap(), "60");
+        assert_eq!(settings_get(&path, "app_settings", Some("theme")).unwrap(), "\"system\"");
+        assert!(settings_get(&path, "app_settings", Some("nope")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,537 @@
+//! Local control API, so stream decks, chat bots and scripts can drive the
+//! app without focusing it.
+//!
+//! Off unless `controlApiEnabled` is set. The server listens on
+//! `127.0.0.1:<controlApiPort>` only and every request must carry the
+//! token (see [`protocol::authorized`]), which is generated on first use and
+//! kept in the store outside the settings blob, so it never ends up in an
+//! exported settings file or a profile.
+//!
+//! - `POST /action` runs one [`protocol::Action`] and answers with its result.
+//! - `GET /status` is `getStatus`.
+//! - `GET /ws` upgrades to a WebSocket that takes the same requests as text
+//!   messages and streams the [`FORWARDED_EVENTS`] as they are emitted.
+//! - With `controlApiObsCompat`, a WebSocket at `/` speaks a subset of
+//!   obs-websocket v5 instead (see [`obs`]); it authenticates in-protocol.
+//!
+//! The server is blocking std networking on its own threads (one per
+//! connection), like the settings watcher; the actions themselves run
+//! through the handler the app installs, i.e. the same functions the tray
+//! and the windows call.
+
+pub mod obs;
+pub mod protocol;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tungstenite::handshake::derive_accept_key;
+use tungstenite::protocol::frame::coding::CloseCode;
+use tungstenite::protocol::frame::CloseFrame;
+use tungstenite::protocol::Role;
+use tungstenite::{Message, WebSocket};
+
+use crate::events::evt;
+use protocol::Action;
+
+/// Streamed to every WebSocket client.
+pub const FORWARDED_EVENTS: &[&str] = &[
+    evt::RECORDING_STATUS,
+    evt::RECORDING_UI,
+    evt::SETTINGS_UPDATED,
+    evt::PROFILES_UPDATED,
+    evt::REGION_SELECTED,
+    evt::HOTKEY_TRIGGERED,
+    evt::CAMERA_ADDED,
+    evt::CAMERA_REMOVED,
+];
+
+pub const DEFAULT_PORT: u16 = 7373;
+
+/// Longest request head (request line and headers) accepted.
+const MAX_HEAD: usize = 16 * 1024;
+/// Longest `POST /action` body accepted.
+const MAX_BODY: usize = 64 * 1024;
+/// A client has this long to send its request head.
+const HEAD_TIMEOUT: Duration = Duration::from_secs(5);
+/// How often a WebSocket connection checks for events to send.
+const POLL: Duration = Duration::from_millis(50);
+
+/// Runs one action for a client.
+pub type Handler = Arc<dyn Fn(Action) -> Result<Value, String> + Send + Sync>;
+
+struct Shared {
+    token: String,
+    obs_compat: bool,
+    handler: Handler,
+    shutdown: AtomicBool,
+    /// One per open WebSocket; receives `(event, payload JSON)`.
+    subscribers: Mutex<Vec<mpsc::Sender<(String, String)>>>,
+}
+
+/// A running server. Dropping it stops accepting connections and closes
+/// the open WebSockets.
+pub struct Server {
+    port: u16,
+    shared: Arc<Shared>,
+    accept: Option<JoinHandle<()>>,
+}
+
+impl Server {
+    /// Listen on `127.0.0.1:port` (`0` picks a free port). `obs_compat`
+    /// also serves the obs-websocket protocol at `/`.
+    pub fn start(port: u16, token: String, obs_compat: bool, handler: Handler) -> Result<Server, String> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .map_err(|e| format!("cannot listen on 127.0.0.1:{}: {}", port, e))?;
+        let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+        let shared = Arc::new(Shared {
+            token,
+            obs_compat,
+            handler,
+            shutdown: AtomicBool::new(false),
+            subscribers: Mutex::new(Vec::new()),
+        });
+
+        let accepting = shared.clone();
+        let accept = std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if accepting.shutdown.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(stream) = stream else { continue };
+                let shared = accepting.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = serve(stream, &shared) {
+                        eprintln!("Control API connection failed: {}", e);
+                    }
+                });
+            }
+        });
+        Ok(Server {
+            port,
+            shared,
+            accept: Some(accept),
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Whether this server already is the one the arguments ask for.
+    pub fn serves(&self, port: u16, token: &str, obs_compat: bool) -> bool {
+        self.port == port && self.shared.token == token && self.shared.obs_compat == obs_compat
+    }
+
+    /// Send an event to every connected WebSocket client. `payload` is the
+    /// event's JSON.
+    pub fn broadcast(&self, event: &str, payload: &str) {
+        if let Ok(mut subscribers) = self.shared.subscribers.lock() {
+            // A closed connection has dropped its receiver.
+            subscribers.retain(|tx| tx.send((event.to_string(), payload.to_string())).is_ok());
+        }
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees the flag, and wait for it to
+        // release the port: a restart binds the same port right after.
+        if TcpStream::connect((Ipv4Addr::LOCALHOST, self.port)).is_ok() {
+            if let Some(accept) = self.accept.take() {
+                let _ = accept.join();
+            }
+        }
+    }
+}
+
+/// A fresh random token (hex).
+pub fn new_token() -> Result<String, String> {
+    let mut bytes = [0u8; 24];
+    getrandom::fill(&mut bytes).map_err(|e| e.to_string())?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// The parts of a request head the server looks at.
+struct Head {
+    method: String,
+    path: String,
+    query: Option<String>,
+    authorization: Option<String>,
+    websocket_key: Option<String>,
+    /// Offered WebSocket subprotocols.
+    websocket_protocols: Vec<String>,
+    content_length: usize,
+}
+
+fn read_head(reader: &mut impl BufRead) -> Result<Head, String> {
+    let mut raw = Vec::new();
+    while !raw.ends_with(b"\r\n\r\n") {
+        let read = reader
+            .by_ref()
+            .take((MAX_HEAD - raw.len()) as u64)
+            .read_until(b'\n', &mut raw)
+            .map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Err("request head incomplete or too long".into());
+        }
+    }
+
+    let mut headers = [httparse::EMPTY_HEADER; 32];
+    let mut request = httparse::Request::new(&mut headers);
+    request.parse(&raw).map_err(|e| e.to_string())?;
+    let header = |name: &str| {
+        request
+            .headers
+            .iter()
+            .find(|h| h.name.eq_ignore_ascii_case(name))
+            .and_then(|h| std::str::from_utf8(h.value).ok())
+            .map(str::to_string)
+    };
+    let (path, query) = match request.path.unwrap_or("/").split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (request.path.unwrap_or("/").to_string(), None),
+    };
+    let upgrade = header("upgrade").is_some_and(|v| v.eq_ignore_ascii_case("websocket"));
+    Ok(Head {
+        method: request.method.unwrap_or("").to_string(),
+        path,
+        query,
+        authorization: header("authorization"),
+        websocket_key: header("sec-websocket-key").filter(|_| upgrade),
+        websocket_protocols: header("sec-websocket-protocol")
+            .map(|v| v.split(',').map(|p| p.trim().to_string()).collect())
+            .unwrap_or_default(),
+        content_length: header("content-length").and_then(|v| v.trim().parse().ok()).unwrap_or(0),
+    })
+}
+
+fn serve(stream: TcpStream, shared: &Shared) -> Result<(), String> {
+    stream.set_read_timeout(Some(HEAD_TIMEOUT)).map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+    let mut stream = stream;
+    let head = read_head(&mut reader)?;
+
+    // OBS clients send the password inside the protocol, not with the upgrade.
+    if shared.obs_compat && head.method == "GET" && head.path == "/" {
+        let Some(key) = &head.websocket_key else {
+            return reply(&mut stream, 400, &json!({ "ok": false, "error": "expected a WebSocket upgrade" }));
+        };
+        // Without a subprotocol, obs-websocket defaults to JSON too.
+        if !head.websocket_protocols.is_empty() && !head.websocket_protocols.iter().any(|p| p == obs::SUBPROTOCOL) {
+            return reply(&mut stream, 400, &json!({ "ok": false, "error": "only obswebsocket.json is supported" }));
+        }
+        let protocol = (!head.websocket_protocols.is_empty()).then_some(obs::SUBPROTOCOL);
+        let socket = upgrade(stream, &reader, key, protocol)?;
+        return run_obs(socket, shared);
+    }
+
+    if !protocol::authorized(&shared.token, head.authorization.as_deref(), head.query.as_deref()) {
+        return reply(&mut stream, 401, &json!({ "ok": false, "error": "missing or wrong token" }));
+    }
+    match (head.method.as_str(), head.path.as_str()) {
+        ("GET", "/ws") => {
+            let Some(key) = &head.websocket_key else {
+                return reply(&mut stream, 400, &json!({ "ok": false, "error": "expected a WebSocket upgrade" }));
+            };
+            let socket = upgrade(stream, &reader, key, None)?;
+            run_socket(socket, shared)
+        }
+        ("GET", "/status") => {
+            let result = (shared.handler)(Action::GetStatus);
+            reply(&mut stream, status_code(&Ok(()), &result), &protocol::response(&Value::Null, &result))
+        }
+        ("POST", "/action") => {
+            if head.content_length > MAX_BODY {
+                return reply(&mut stream, 413, &json!({ "ok": false, "error": "request body too large" }));
+            }
+            let mut body = vec![0; head.content_length];
+            reader.read_exact(&mut body).map_err(|e| e.to_string())?;
+            let (id, action) = protocol::parse_request(&String::from_utf8_lossy(&body));
+            let parsed = action.as_ref().map(|_| ()).map_err(String::clone);
+            let result = action.and_then(|action| (shared.handler)(action));
+            reply(&mut stream, status_code(&parsed, &result), &protocol::response(&id, &result))
+        }
+        (_, "/ws" | "/status" | "/action") => {
+            reply(&mut stream, 405, &json!({ "ok": false, "error": "method not allowed" }))
+        }
+        _ => reply(&mut stream, 404, &json!({ "ok": false, "error": "not found" })),
+    }
+}
+
+/// 400 for a request that did not parse, 500 for an action that failed.
+fn status_code(parsed: &Result<(), String>, result: &Result<Value, String>) -> u16 {
+    match (parsed, result) {
+        (Err(_), _) => 400,
+        (Ok(()), Err(_)) => 500,
+        _ => 200,
+    }
+}
+
+fn reply(stream: &mut TcpStream, status: u16, body: &Value) -> Result<(), String> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error",
+    };
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Complete the WebSocket handshake, agreeing on `protocol` if given.
+fn upgrade(
+    mut stream: TcpStream,
+    reader: &BufReader<TcpStream>,
+    key: &str,
+    protocol: Option<&str>,
+) -> Result<WebSocket<TcpStream>, String> {
+    let protocol = protocol
+        .map(|p| format!("Sec-WebSocket-Protocol: {}\r\n", p))
+        .unwrap_or_default();
+    write!(
+        stream,
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n{}\r\n",
+        derive_accept_key(key.as_bytes()),
+        protocol
+    )
+    .map_err(|e| e.to_string())?;
+    // Anything the client sent after its head was read into the buffer.
+    Ok(WebSocket::from_partially_read(stream, reader.buffer().to_vec(), Role::Server, None))
+}
+
+/// The native protocol: each text message is a request, answered in place.
+fn run_socket(socket: WebSocket<TcpStream>, shared: &Shared) -> Result<(), String> {
+    pump(
+        socket,
+        shared,
+        |text| {
+            let (id, action) = protocol::parse_request(text);
+            let result = action.and_then(|action| (shared.handler)(action));
+            Ok(vec![protocol::response(&id, &result).to_string()])
+        },
+        |event, payload| vec![protocol::event_message(event, payload)],
+    )
+}
+
+fn run_obs(mut socket: WebSocket<TcpStream>, shared: &Shared) -> Result<(), String> {
+    let session = std::cell::RefCell::new(obs::Session::new(&shared.token, shared.handler.clone())?);
+    socket
+        .send(Message::Text(session.borrow().hello()))
+        .map_err(|e| e.to_string())?;
+    pump(
+        socket,
+        shared,
+        |text| session.borrow_mut().receive(text),
+        |event, payload| session.borrow_mut().event(event, payload),
+    )
+}
+
+/// Answer messages and forward events until the client leaves, the server
+/// stops, or `on_text` asks to close. Reads time out every [`POLL`] to check
+/// for events to send.
+fn pump(
+    mut socket: WebSocket<TcpStream>,
+    shared: &Shared,
+    mut on_text: impl FnMut(&str) -> Result<Vec<String>, obs::Close>,
+    mut on_event: impl FnMut(&str, &str) -> Vec<String>,
+) -> Result<(), String> {
+    let (tx, events) = mpsc::channel();
+    shared.subscribers.lock().map_err(|e| e.to_string())?.push(tx);
+    socket.get_mut().set_read_timeout(Some(POLL)).map_err(|e| e.to_string())?;
+
+    let mut close = None;
+    while !shared.shutdown.load(Ordering::SeqCst) {
+        match socket.read() {
+            Ok(Message::Text(text)) => match on_text(&text) {
+                Ok(replies) => {
+                    for reply in replies {
+                        socket.send(Message::Text(reply)).map_err(|e| e.to_string())?;
+                    }
+                }
+                Err(reason) => {
+                    close = Some(CloseFrame {
+                        code: CloseCode::from(reason.code),
+                        reason: reason.reason.into(),
+                    });
+                    break;
+                }
+            },
+            // Pings are answered by tungstenite; binary frames are not ours.
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {}
+            Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => return Ok(()),
+            Err(e) => return Err(e.to_string()),
+        }
+        for (event, payload) in events.try_iter() {
+            for message in on_event(&event, &payload) {
+                socket.send(Message::Text(message)).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    let _ = socket.close(close);
+    let _ = socket.flush();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "test-token";
+
+    fn start() -> (Server, Arc<Mutex<Vec<Action>>>) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let handler: Handler = Arc::new(move |action| {
+            log.lock().unwrap().push(action.clone());
+            match action {
+                Action::GetStatus => Ok(json!({ "recording": false })),
+                Action::TogglePause => Err("not recording".into()),
+                _ => Ok(Value::Null),
+            }
+        });
+        (Server::start(0, TOKEN.into(), true, handler).unwrap(), seen)
+    }
+
+    fn http(server: &Server, request: &str) -> String {
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, server.port())).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut out = String::new();
+        stream.read_to_string(&mut out).unwrap();
+        out
+    }
+
+    fn post(server: &Server, auth: &str, body: &str) -> String {
+        http(
+            server,
+            &format!("POST /action HTTP/1.1\r\nHost: localhost\r\n{}Content-Length: {}\r\n\r\n{}", auth, body.len(), body),
+        )
+    }
+
+    #[test]
+    fn http_requests_need_the_token() {
+        let (server, seen) = start();
+        let bearer = format!("Authorization: Bearer {}\r\n", TOKEN);
+
+        assert!(post(&server, "", r#"{"action":"toggleLock"}"#).starts_with("HTTP/1.1 401"));
+        assert!(post(&server, "Authorization: Bearer nope\r\n", r#"{"action":"toggleLock"}"#).starts_with("HTTP/1.1 401"));
+        assert!(seen.lock().unwrap().is_empty());
+
+        let ok = post(&server, &bearer, r#"{"action":"toggleLock"}"#);
+        assert!(ok.starts_with("HTTP/1.1 200") && ok.contains(r#""ok":true"#), "{}", ok);
+        assert!(post(&server, &bearer, r#"{"action":"nope"}"#).starts_with("HTTP/1.1 400"));
+        assert!(post(&server, &bearer, r#"{"action":"togglePause"}"#).starts_with("HTTP/1.1 500"));
+
+        let status = http(&server, &format!("GET /status?token={} HTTP/1.1\r\n\r\n", TOKEN));
+        assert!(status.starts_with("HTTP/1.1 200") && status.contains(r#""recording":false"#), "{}", status);
+        assert!(http(&server, &format!("GET /nope HTTP/1.1\r\n{}\r\n", bearer)).starts_with("HTTP/1.1 404"));
+        assert!(http(&server, &format!("GET /action HTTP/1.1\r\n{}\r\n", bearer)).starts_with("HTTP/1.1 405"));
+
+        assert_eq!(*seen.lock().unwrap(), [Action::ToggleLock, Action::TogglePause, Action::GetStatus]);
+    }
+
+    #[test]
+    fn websocket_answers_requests_and_streams_events() {
+        let (server, seen) = start();
+        let url = format!("ws://127.0.0.1:{}/ws", server.port());
+        assert!(tungstenite::connect(&url).is_err());
+
+        let (mut client, _) = tungstenite::connect(format!("{}?token={}", url, TOKEN)).unwrap();
+        client.send(Message::Text(r#"{"id":1,"action":"setShape","shape":"circle"}"#.into())).unwrap();
+        let reply: Value = serde_json::from_str(client.read().unwrap().to_text().unwrap()).unwrap();
+        assert_eq!(reply, json!({ "type": "response", "id": 1, "ok": true, "result": null }));
+        assert_eq!(*seen.lock().unwrap(), [Action::SetShape { shape: crate::settings::ShapePreset::Circle }]);
+
+        // Subscribed once the first reply arrived.
+        server.broadcast(evt::RECORDING_STATUS, r#"{"active":true}"#);
+        let event: Value = serde_json::from_str(client.read().unwrap().to_text().unwrap()).unwrap();
+        assert_eq!(event, json!({ "type": "event", "event": evt::RECORDING_STATUS, "payload": { "active": true } }));
+
+        // Restarting on the same port works once the old server is gone.
+        let port = server.port();
+        drop(server);
+        let restarted = Server::start(port, TOKEN.into(), false, Arc::new(|_| Ok(Value::Null))).unwrap();
+        assert_eq!(restarted.port(), port);
+        loop {
+            match client.read() {
+                Ok(Message::Close(_)) | Err(_) => break,
+                Ok(_) => {}
+            }
+        }
+    }
+
+    fn read_json(client: &mut WebSocket<tungstenite::stream::MaybeTlsStream<TcpStream>>) -> Value {
+        serde_json::from_str(client.read().unwrap().to_text().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn obs_client_session() {
+        let (server, seen) = start();
+        let request = tungstenite::client::IntoClientRequest::into_client_request(format!("ws://127.0.0.1:{}/", server.port()))
+            .map(|mut r| {
+                r.headers_mut().insert("Sec-WebSocket-Protocol", obs::SUBPROTOCOL.parse().unwrap());
+                r
+            })
+            .unwrap();
+        let (mut client, response) = tungstenite::connect(request).unwrap();
+        assert_eq!(response.headers()["Sec-WebSocket-Protocol"], obs::SUBPROTOCOL);
+
+        let hello = read_json(&mut client);
+        assert_eq!(hello["op"], 0);
+        let auth = &hello["d"]["authentication"];
+        let secret = obs::auth_response(TOKEN, auth["salt"].as_str().unwrap(), auth["challenge"].as_str().unwrap());
+        let identify = json!({ "op": 1, "d": { "rpcVersion": 1, "authentication": secret } });
+        client.send(Message::Text(identify.to_string())).unwrap();
+        assert_eq!(read_json(&mut client), json!({ "op": 2, "d": { "negotiatedRpcVersion": 1 } }));
+
+        let request = json!({ "op": 6, "d": { "requestType": "ToggleRecord", "requestId": "t" } });
+        client.send(Message::Text(request.to_string())).unwrap();
+        let reply = read_json(&mut client);
+        assert_eq!(reply["op"], 7);
+        assert_eq!(reply["d"]["requestStatus"], json!({ "result": true, "code": 100 }));
+        assert!(seen.lock().unwrap().contains(&Action::ToggleRecording));
+
+        server.broadcast(evt::RECORDING_UI, r#"{"status":"recording","elapsed":0}"#);
+        let event = read_json(&mut client);
+        assert_eq!(event["op"], 5);
+        assert_eq!(event["d"]["eventType"], "RecordStateChanged");
+
+        // A bad message closes the session with the protocol's code.
+        client.send(Message::Text("{}".into())).unwrap();
+        loop {
+            match client.read() {
+                Ok(Message::Close(Some(frame))) => {
+                    assert_eq!(u16::from(frame.code), obs::close::MISSING_DATA_FIELD);
+                    break;
+                }
+                Ok(_) => {}
+                Err(e) => panic!("{}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn obs_endpoint_is_opt_in() {
+        let server = Server::start(0, TOKEN.into(), false, Arc::new(|_| Ok(Value::Null))).unwrap();
+        assert!(tungstenite::connect(format!("ws://127.0.0.1:{}/", server.port())).is_err());
+    }
+}
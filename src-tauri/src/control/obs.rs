@@ -0,0 +1,668 @@
+//! obs-websocket v5 compatibility, so stream controllers that already speak
+//! OBS's protocol can drive Floaty with no custom plugin.
+//!
+//! Served at `/` on the control API port when `controlApiObsCompat` is on,
+//! with the control API token as the password. Only the JSON encoding
+//! (`obswebsocket.json`) and the requests in [`REQUESTS`] are implemented;
+//! anything else is answered with `UnknownRequestType`. To such a client
+//! Floaty looks like OBS with one scene, [`SCENE_NAME`], holding two items
+//! whose visibility maps to the camera window and the keyboard display.
+//!
+//! A [`Session`] is one connection's protocol state: it turns client
+//! messages and app events into messages to send, and runs requests
+//! through the control API's [`Handler`] as [`Action`]s.
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+
+use super::protocol::{constant_time_eq, Action};
+use super::Handler;
+use crate::events::evt;
+
+/// The WebSocket subprotocol for JSON-encoded messages.
+pub const SUBPROTOCOL: &str = "obswebsocket.json";
+pub const SCENE_NAME: &str = "Floaty";
+const SCENE_UUID: &str = "f10a7700-0000-4000-8000-000000000000";
+const RPC_VERSION: u64 = 1;
+/// The obs-websocket release whose protocol this follows.
+const OBS_WEBSOCKET_VERSION: &str = "5.5.0";
+
+/// Message op codes.
+mod op {
+    pub const HELLO: u64 = 0;
+    pub const IDENTIFY: u64 = 1;
+    pub const IDENTIFIED: u64 = 2;
+    pub const REIDENTIFY: u64 = 3;
+    pub const EVENT: u64 = 5;
+    pub const REQUEST: u64 = 6;
+    pub const REQUEST_RESPONSE: u64 = 7;
+    pub const REQUEST_BATCH: u64 = 8;
+    pub const REQUEST_BATCH_RESPONSE: u64 = 9;
+}
+
+/// `requestStatus.code` values.
+mod status {
+    pub const SUCCESS: u16 = 100;
+    pub const MISSING_REQUEST_TYPE: u16 = 203;
+    pub const UNKNOWN_REQUEST_TYPE: u16 = 204;
+    pub const MISSING_REQUEST_FIELD: u16 = 300;
+    pub const INVALID_REQUEST_FIELD_TYPE: u16 = 401;
+    pub const OUTPUT_RUNNING: u16 = 500;
+    pub const OUTPUT_NOT_RUNNING: u16 = 501;
+    pub const OUTPUT_PAUSED: u16 = 502;
+    pub const OUTPUT_NOT_PAUSED: u16 = 503;
+    pub const RESOURCE_NOT_FOUND: u16 = 600;
+    pub const REQUEST_PROCESSING_FAILED: u16 = 702;
+}
+
+/// WebSocket close codes.
+pub mod close {
+    pub const MESSAGE_DECODE_ERROR: u16 = 4002;
+    pub const MISSING_DATA_FIELD: u16 = 4003;
+    pub const UNKNOWN_OP_CODE: u16 = 4006;
+    pub const NOT_IDENTIFIED: u16 = 4007;
+    pub const ALREADY_IDENTIFIED: u16 = 4008;
+    pub const AUTHENTICATION_FAILED: u16 = 4009;
+    pub const UNSUPPORTED_RPC_VERSION: u16 = 4010;
+}
+
+/// Event subscription bits (`eventIntent`).
+const INTENT_OUTPUTS: u64 = 1 << 6;
+const INTENT_SCENE_ITEMS: u64 = 1 << 7;
+/// Every non-high-volume category; the default subscription.
+const INTENT_ALL: u64 = (1 << 11) - 1;
+
+pub const REQUESTS: &[&str] = &[
+    "GetVersion",
+    "StartRecord",
+    "StopRecord",
+    "ToggleRecord",
+    "PauseRecord",
+    "ResumeRecord",
+    "ToggleRecordPause",
+    "GetRecordStatus",
+    "GetSceneList",
+    "GetCurrentProgramScene",
+    "GetSceneItemList",
+    "GetSceneItemId",
+    "GetSceneItemEnabled",
+    "SetSceneItemEnabled",
+];
+
+const CAMERA_ITEM: u64 = 1;
+const KEYBOARD_ITEM: u64 = 2;
+/// Scene items: id and source name.
+const SCENE_ITEMS: [(u64, &str); 2] = [(CAMERA_ITEM, "Camera"), (KEYBOARD_ITEM, "Keyboard")];
+
+/// Why the server closes the connection.
+#[derive(Debug, PartialEq)]
+pub struct Close {
+    pub code: u16,
+    pub reason: String,
+}
+
+impl Close {
+    fn new(code: u16, reason: impl Into<String>) -> Self {
+        Close { code, reason: reason.into() }
+    }
+}
+
+/// A failed request: `requestStatus.code` and `comment`.
+type Failure = (u16, String);
+
+fn message(op: u64, d: Value) -> String {
+    json!({ "op": op, "d": d }).to_string()
+}
+
+/// `base64(sha256(base64(sha256(password + salt)) + challenge))`, what the
+/// client must send as `authentication`.
+pub fn auth_response(password: &str, salt: &str, challenge: &str) -> String {
+    let secret = BASE64.encode(Sha256::digest(format!("{}{}", password, salt)));
+    BASE64.encode(Sha256::digest(format!("{}{}", secret, challenge)))
+}
+
+fn random_base64() -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).map_err(|e| e.to_string())?;
+    Ok(BASE64.encode(bytes))
+}
+
+/// The recording state as reported by `getStatus`.
+struct Recording {
+    active: bool,
+    paused: bool,
+    elapsed_ms: u64,
+}
+
+/// `HH:MM:SS.mmm`
+fn timecode(ms: u64) -> String {
+    format!("{:02}:{:02}:{:02}.{:03}", ms / 3_600_000, ms / 60_000 % 60, ms / 1000 % 60, ms % 1000)
+}
+
+fn field<'a>(data: &'a Map<String, Value>, name: &str) -> Result<&'a Value, Failure> {
+    data.get(name)
+        .filter(|v| !v.is_null())
+        .ok_or_else(|| (status::MISSING_REQUEST_FIELD, format!("Your request is missing the `{}` field.", name)))
+}
+
+fn wrong_type(name: &str, expected: &str) -> Failure {
+    (status::INVALID_REQUEST_FIELD_TYPE, format!("The field `{}` must be {}.", name, expected))
+}
+
+/// Accept either scene field, as OBS does; only [`SCENE_NAME`] exists.
+fn check_scene(data: &Map<String, Value>) -> Result<(), Failure> {
+    let (name, uuid) = (data.get("sceneName"), data.get("sceneUuid"));
+    let found = match (name.and_then(Value::as_str), uuid.and_then(Value::as_str)) {
+        (Some(name), _) => name == SCENE_NAME,
+        (None, Some(uuid)) => uuid == SCENE_UUID,
+        (None, None) => {
+            return Err((status::MISSING_REQUEST_FIELD, "Your request must contain `sceneName` or `sceneUuid`.".into()))
+        }
+    };
+    if found {
+        Ok(())
+    } else {
+        Err((status::RESOURCE_NOT_FOUND, "No scene was found by that name or UUID.".into()))
+    }
+}
+
+fn scene_item(data: &Map<String, Value>) -> Result<u64, Failure> {
+    check_scene(data)?;
+    let id = field(data, "sceneItemId")?
+        .as_u64()
+        .ok_or_else(|| wrong_type("sceneItemId", "a number"))?;
+    SCENE_ITEMS
+        .iter()
+        .any(|(item, _)| *item == id)
+        .then_some(id)
+        .ok_or_else(|| (status::RESOURCE_NOT_FOUND, "No scene items were found in that scene by that ID.".into()))
+}
+
+pub struct Session {
+    handler: Handler,
+    password: String,
+    challenge: String,
+    salt: String,
+    identified: bool,
+    subscriptions: u64,
+    /// Pipeline status from the last `RECORDING_UI`, to report transitions.
+    record_status: String,
+}
+
+impl Session {
+    pub fn new(password: &str, handler: Handler) -> Result<Self, String> {
+        Ok(Session {
+            handler,
+            password: password.to_string(),
+            challenge: random_base64()?,
+            salt: random_base64()?,
+            identified: false,
+            subscriptions: INTENT_ALL,
+            record_status: "idle".into(),
+        })
+    }
+
+    /// First message, sent as soon as the socket opens.
+    pub fn hello(&self) -> String {
+        message(
+            op::HELLO,
+            json!({
+                "obsWebSocketVersion": OBS_WEBSOCKET_VERSION,
+                "rpcVersion": RPC_VERSION,
+                "authentication": { "challenge": self.challenge, "salt": self.salt },
+            }),
+        )
+    }
+
+    /// Handle one text message from the client: the messages to send back,
+    /// or why the connection must close.
+    pub fn receive(&mut self, text: &str) -> Result<Vec<String>, Close> {
+        let msg: Value = serde_json::from_str(text).map_err(|e| Close::new(close::MESSAGE_DECODE_ERROR, e.to_string()))?;
+        let op = msg["op"]
+            .as_u64()
+            .ok_or_else(|| Close::new(close::MISSING_DATA_FIELD, "missing `op`"))?;
+        let d = msg["d"]
+            .as_object()
+            .ok_or_else(|| Close::new(close::MISSING_DATA_FIELD, "missing `d`"))?;
+
+        match op {
+            op::IDENTIFY => {
+                if self.identified {
+                    return Err(Close::new(close::ALREADY_IDENTIFIED, "already identified"));
+                }
+                let rpc = d
+                    .get("rpcVersion")
+                    .and_then(Value::as_u64)
+                    .ok_or_else(|| Close::new(close::MISSING_DATA_FIELD, "missing `rpcVersion`"))?;
+                if rpc != RPC_VERSION {
+                    return Err(Close::new(close::UNSUPPORTED_RPC_VERSION, format!("only rpcVersion {} is supported", RPC_VERSION)));
+                }
+                let expected = auth_response(&self.password, &self.salt, &self.challenge);
+                let given = d.get("authentication").and_then(Value::as_str).unwrap_or_default();
+                if !constant_time_eq(given.as_bytes(), expected.as_bytes()) {
+                    return Err(Close::new(close::AUTHENTICATION_FAILED, "authentication failed"));
+                }
+                self.identified = true;
+                self.subscriptions = d.get("eventSubscriptions").and_then(Value::as_u64).unwrap_or(INTENT_ALL);
+                if let Ok(recording) = (self.handler)(Action::GetStatus) {
+                    if let Some(s) = recording["recordingStatus"].as_str() {
+                        self.record_status = s.to_string();
+                    }
+                }
+                Ok(vec![message(op::IDENTIFIED, json!({ "negotiatedRpcVersion": RPC_VERSION }))])
+            }
+            _ if !self.identified => Err(Close::new(close::NOT_IDENTIFIED, "identify first")),
+            op::REIDENTIFY => {
+                if let Some(subscriptions) = d.get("eventSubscriptions").and_then(Value::as_u64) {
+                    self.subscriptions = subscriptions;
+                }
+                Ok(vec![message(op::IDENTIFIED, json!({ "negotiatedRpcVersion": RPC_VERSION }))])
+            }
+            op::REQUEST => Ok(vec![message(op::REQUEST_RESPONSE, self.request(d))]),
+            op::REQUEST_BATCH => {
+                let halt = d.get("haltOnFailure").and_then(Value::as_bool).unwrap_or(false);
+                let mut results = Vec::new();
+                for request in d.get("requests").and_then(Value::as_array).into_iter().flatten() {
+                    let response = match request.as_object() {
+                        Some(request) => self.request(request),
+                        None => json!({ "requestStatus": { "result": false, "code": status::MISSING_REQUEST_TYPE } }),
+                    };
+                    let failed = response["requestStatus"]["result"] != json!(true);
+                    results.push(response);
+                    if failed && halt {
+                        break;
+                    }
+                }
+                Ok(vec![message(
+                    op::REQUEST_BATCH_RESPONSE,
+                    json!({ "requestId": d.get("requestId"), "results": results }),
+                )])
+            }
+            other => Err(Close::new(close::UNKNOWN_OP_CODE, format!("unknown op {}", other))),
+        }
+    }
+
+    /// Translate an `app://` event into OBS events for this client.
+    pub fn event(&mut self, event: &str, payload: &str) -> Vec<String> {
+        let payload: Value = serde_json::from_str(payload).unwrap_or(Value::Null);
+        let mut out = Vec::new();
+        match event {
+            evt::RECORDING_UI => {
+                let status = payload["status"].as_str().unwrap_or("idle").to_string();
+                if status == self.record_status {
+                    return out;
+                }
+                let previous = std::mem::replace(&mut self.record_status, status);
+                let state = match (previous.as_str(), self.record_status.as_str()) {
+                    (_, "countdown") => "OBS_WEBSOCKET_OUTPUT_STARTING",
+                    ("paused", "recording") => "OBS_WEBSOCKET_OUTPUT_RESUMED",
+                    (_, "recording") => "OBS_WEBSOCKET_OUTPUT_STARTED",
+                    (_, "paused") => "OBS_WEBSOCKET_OUTPUT_PAUSED",
+                    (_, "saving") => "OBS_WEBSOCKET_OUTPUT_STOPPING",
+                    _ => "OBS_WEBSOCKET_OUTPUT_STOPPED",
+                };
+                let active = matches!(self.record_status.as_str(), "recording" | "paused");
+                self.push_event(
+                    &mut out,
+                    "RecordStateChanged",
+                    INTENT_OUTPUTS,
+                    json!({ "outputActive": active, "outputState": state, "outputPath": null }),
+                );
+            }
+            evt::SETTINGS_UPDATED => {
+                let changed = payload["changed"].as_array().into_iter().flatten();
+                if changed.into_iter().any(|key| key == "keyboardDisplayEnabled") {
+                    let enabled = payload["settings"]["keyboardDisplayEnabled"].as_bool().unwrap_or(false);
+                    self.push_item_event(&mut out, KEYBOARD_ITEM, enabled);
+                }
+            }
+            evt::HOTKEY_TRIGGERED if payload["action"] == "toggle_visibility" => {
+                if let Ok(status) = (self.handler)(Action::GetStatus) {
+                    let visible = status["cameraVisible"].as_bool().unwrap_or(false);
+                    self.push_item_event(&mut out, CAMERA_ITEM, visible);
+                }
+            }
+            _ => {}
+        }
+        out
+    }
+
+    fn push_event(&self, out: &mut Vec<String>, event_type: &str, intent: u64, data: Value) {
+        if self.identified && self.subscriptions & intent != 0 {
+            out.push(message(
+                op::EVENT,
+                json!({ "eventType": event_type, "eventIntent": intent, "eventData": data }),
+            ));
+        }
+    }
+
+    fn push_item_event(&self, out: &mut Vec<String>, item: u64, enabled: bool) {
+        self.push_event(
+            out,
+            "SceneItemEnableStateChanged",
+            INTENT_SCENE_ITEMS,
+            json!({ "sceneName": SCENE_NAME, "sceneUuid": SCENE_UUID, "sceneItemId": item, "sceneItemEnabled": enabled }),
+        );
+    }
+
+    /// Run one request and build its `RequestResponse` data.
+    fn request(&mut self, d: &Map<String, Value>) -> Value {
+        let request_type = d.get("requestType").and_then(Value::as_str);
+        let empty = Map::new();
+        let data = d.get("requestData").and_then(Value::as_object).unwrap_or(&empty);
+        let result = match request_type {
+            Some(request_type) => self.run(request_type, data),
+            None => Err((status::MISSING_REQUEST_TYPE, "Your request is missing a `requestType`.".into())),
+        };
+        let mut response = json!({ "requestType": request_type, "requestId": d.get("requestId") });
+        match result {
+            Ok(data) => {
+                response["requestStatus"] = json!({ "result": true, "code": status::SUCCESS });
+                if let Some(data) = data {
+                    response["responseData"] = data;
+                }
+            }
+            Err((code, comment)) => {
+                response["requestStatus"] = json!({ "result": false, "code": code, "comment": comment });
+            }
+        }
+        response
+    }
+
+    fn act(&self, action: Action) -> Result<Value, Failure> {
+        (self.handler)(action).map_err(|e| (status::REQUEST_PROCESSING_FAILED, e))
+    }
+
+    fn recording(&self) -> Result<Recording, Failure> {
+        let status = self.act(Action::GetStatus)?;
+        Ok(Recording {
+            active: status["recording"].as_bool().unwrap_or(false),
+            paused: status["recordingStatus"] == "paused",
+            elapsed_ms: (status["elapsed"].as_f64().unwrap_or(0.0) * 1000.0) as u64,
+        })
+    }
+
+    fn run(&mut self, request_type: &str, data: &Map<String, Value>) -> Result<Option<Value>, Failure> {
+        let not_running = || (status::OUTPUT_NOT_RUNNING, "Record is not active.".to_string());
+        match request_type {
+            "GetVersion" => Ok(Some(json!({
+                "obsVersion": env!("CARGO_PKG_VERSION"),
+                "obsWebSocketVersion": OBS_WEBSOCKET_VERSION,
+                "rpcVersion": RPC_VERSION,
+                "availableRequests": REQUESTS,
+                "supportedImageFormats": [],
+                "platform": std::env::consts::OS,
+                "platformDescription": "Floaty",
+            }))),
+            "StartRecord" => {
+                if self.recording()?.active {
+                    return Err((status::OUTPUT_RUNNING, "Record is already active.".into()));
+                }
+                self.act(Action::StartRecording)?;
+                Ok(None)
+            }
+            "StopRecord" => {
+                if !self.recording()?.active {
+                    return Err(not_running());
+                }
+                self.act(Action::StopRecording)?;
+                // The file is named when the draft is saved, after this reply.
+                Ok(Some(json!({ "outputPath": null })))
+            }
+            "ToggleRecord" => {
+                let active = self.recording()?.active;
+                self.act(Action::ToggleRecording)?;
+                Ok(Some(json!({ "outputActive": !active })))
+            }
+            "PauseRecord" | "ResumeRecord" | "ToggleRecordPause" => {
+                let recording = self.recording()?;
+                if !recording.active {
+                    return Err(not_running());
+                }
+                match (request_type, recording.paused) {
+                    ("PauseRecord", true) => return Err((status::OUTPUT_PAUSED, "Record is already paused.".into())),
+                    ("ResumeRecord", false) => return Err((status::OUTPUT_NOT_PAUSED, "Record is not paused.".into())),
+                    _ => {}
+                }
+                self.act(Action::TogglePause)?;
+                Ok(None)
+            }
+            "GetRecordStatus" => {
+                let recording = self.recording()?;
+                Ok(Some(json!({
+                    "outputActive": recording.active,
+                    "outputPaused": recording.paused,
+                    "outputTimecode": timecode(recording.elapsed_ms),
+                    "outputDuration": recording.elapsed_ms,
+                    "outputBytes": 0,
+                })))
+            }
+            "GetSceneList" => Ok(Some(json!({
+                "currentProgramSceneName": SCENE_NAME,
+                "currentProgramSceneUuid": SCENE_UUID,
+                "currentPreviewSceneName": null,
+                "currentPreviewSceneUuid": null,
+                "scenes": [{ "sceneIndex": 0, "sceneName": SCENE_NAME, "sceneUuid": SCENE_UUID }],
+            }))),
+            "GetCurrentProgramScene" => Ok(Some(json!({
+                "sceneName": SCENE_NAME,
+                "sceneUuid": SCENE_UUID,
+                "currentProgramSceneName": SCENE_NAME,
+                "currentProgramSceneUuid": SCENE_UUID,
+            }))),
+            "GetSceneItemList" => {
+                check_scene(data)?;
+                let enabled = self.items_enabled()?;
+                let items: Vec<Value> = SCENE_ITEMS
+                    .iter()
+                    .zip(enabled)
+                    .enumerate()
+                    .map(|(index, ((id, source), enabled))| {
+                        json!({
+                            "sceneItemId": id,
+                            "sceneItemIndex": index,
+                            "sourceName": source,
+                            "sourceType": "OBS_SOURCE_TYPE_INPUT",
+                            "sceneItemEnabled": enabled,
+                            "sceneItemLocked": false,
+                            "isGroup": false,
+                        })
+                    })
+                    .collect();
+                Ok(Some(json!({ "sceneItems": items })))
+            }
+            "GetSceneItemId" => {
+                check_scene(data)?;
+                let source = field(data, "sourceName")?
+                    .as_str()
+                    .ok_or_else(|| wrong_type("sourceName", "a string"))?;
+                SCENE_ITEMS
+                    .iter()
+                    .find(|(_, name)| *name == source)
+                    .map(|(id, _)| Some(json!({ "sceneItemId": id })))
+                    .ok_or_else(|| (status::RESOURCE_NOT_FOUND, "No scene items were found in the specified scene by that name.".into()))
+            }
+            "GetSceneItemEnabled" => {
+                let item = scene_item(data)?;
+                let [camera, keyboard] = self.items_enabled()?;
+                let enabled = if item == CAMERA_ITEM { camera } else { keyboard };
+                Ok(Some(json!({ "sceneItemEnabled": enabled })))
+            }
+            "SetSceneItemEnabled" => {
+                let item = scene_item(data)?;
+                let visible = field(data, "sceneItemEnabled")?
+                    .as_bool()
+                    .ok_or_else(|| wrong_type("sceneItemEnabled", "a boolean"))?;
+                self.act(if item == CAMERA_ITEM {
+                    Action::SetCameraVisible { visible }
+                } else {
+                    Action::SetKeyboardVisible { visible }
+                })?;
+                Ok(None)
+            }
+            _ => Err((status::UNKNOWN_REQUEST_TYPE, "Your request type is not valid.".into())),
+        }
+    }
+
+    /// Visibility of the camera and keyboard items, in [`SCENE_ITEMS`] order.
+    fn items_enabled(&self) -> Result<[bool; 2], Failure> {
+        let status = self.act(Action::GetStatus)?;
+        Ok([
+            status["cameraVisible"].as_bool().unwrap_or(false),
+            status["keyboardVisible"].as_bool().unwrap_or(false),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// A session whose handler records actions and reports `status`.
+    fn session(status: Value) -> (Session, Arc<Mutex<Vec<Action>>>) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        let handler: Handler = Arc::new(move |action| match action {
+            Action::GetStatus => Ok(status.clone()),
+            action => {
+                log.lock().unwrap().push(action);
+                Ok(Value::Null)
+            }
+        });
+        (Session::new("pw", handler).unwrap(), seen)
+    }
+
+    fn identify(session: &mut Session) {
+        let auth = auth_response("pw", &session.salt, &session.challenge);
+        let reply = session
+            .receive(&message(op::IDENTIFY, json!({ "rpcVersion": 1, "authentication": auth })))
+            .unwrap();
+        assert_eq!(reply, [message(op::IDENTIFIED, json!({ "negotiatedRpcVersion": 1 }))]);
+    }
+
+    fn request(session: &mut Session, request_type: &str, data: Value) -> Value {
+        let text = message(op::REQUEST, json!({ "requestType": request_type, "requestId": "r", "requestData": data }));
+        let reply: Value = serde_json::from_str(&session.receive(&text).unwrap()[0]).unwrap();
+        assert_eq!(reply["op"], op::REQUEST_RESPONSE);
+        assert_eq!(reply["d"]["requestId"], "r");
+        reply["d"].clone()
+    }
+
+    #[test]
+    fn handshake_requires_the_password() {
+        let (mut s, _) = session(json!({}));
+        let hello: Value = serde_json::from_str(&s.hello()).unwrap();
+        assert_eq!(hello["op"], op::HELLO);
+        assert_eq!(hello["d"]["rpcVersion"], 1);
+        assert_eq!(hello["d"]["authentication"]["salt"], s.salt.as_str());
+
+        let request = message(op::REQUEST, json!({ "requestType": "GetVersion", "requestId": "1" }));
+        assert_eq!(s.receive(&request).unwrap_err().code, close::NOT_IDENTIFIED);
+        let wrong = auth_response("nope", &s.salt, &s.challenge);
+        let err = s.receive(&message(op::IDENTIFY, json!({ "rpcVersion": 1, "authentication": wrong }))).unwrap_err();
+        assert_eq!(err.code, close::AUTHENTICATION_FAILED);
+        let err = s.receive(&message(op::IDENTIFY, json!({ "rpcVersion": 2 }))).unwrap_err();
+        assert_eq!(err.code, close::UNSUPPORTED_RPC_VERSION);
+        assert_eq!(s.receive("{").unwrap_err().code, close::MESSAGE_DECODE_ERROR);
+
+        identify(&mut s);
+        assert_eq!(
+            s.receive(&message(op::IDENTIFY, json!({ "rpcVersion": 1 }))).unwrap_err().code,
+            close::ALREADY_IDENTIFIED
+        );
+        assert_eq!(s.receive(&message(42, json!({}))).unwrap_err().code, close::UNKNOWN_OP_CODE);
+    }
+
+    #[test]
+    fn record_requests_check_the_output_state() {
+        let (mut s, seen) = session(json!({ "recording": false, "recordingStatus": "idle", "elapsed": 0 }));
+        identify(&mut s);
+        assert_eq!(request(&mut s, "StartRecord", json!({}))["requestStatus"]["code"], status::SUCCESS);
+        assert_eq!(request(&mut s, "StopRecord", json!({}))["requestStatus"]["code"], status::OUTPUT_NOT_RUNNING);
+        assert_eq!(request(&mut s, "PauseRecord", json!({}))["requestStatus"]["code"], status::OUTPUT_NOT_RUNNING);
+        assert_eq!(request(&mut s, "ToggleRecord", json!({}))["responseData"]["outputActive"], true);
+        assert_eq!(*seen.lock().unwrap(), [Action::StartRecording, Action::ToggleRecording]);
+
+        let (mut s, seen) = session(json!({ "recording": true, "recordingStatus": "paused", "elapsed": 61.5 }));
+        identify(&mut s);
+        assert_eq!(request(&mut s, "StartRecord", json!({}))["requestStatus"]["code"], status::OUTPUT_RUNNING);
+        assert_eq!(request(&mut s, "PauseRecord", json!({}))["requestStatus"]["code"], status::OUTPUT_PAUSED);
+        assert_eq!(request(&mut s, "ResumeRecord", json!({}))["requestStatus"]["code"], status::SUCCESS);
+        let status = request(&mut s, "GetRecordStatus", json!({}));
+        assert_eq!(
+            status["responseData"],
+            json!({ "outputActive": true, "outputPaused": true, "outputTimecode": "00:01:01.500", "outputDuration": 61500, "outputBytes": 0 })
+        );
+        assert_eq!(*seen.lock().unwrap(), [Action::TogglePause]);
+    }
+
+    #[test]
+    fn scene_items_map_to_windows() {
+        let (mut s, seen) = session(json!({ "cameraVisible": true, "keyboardVisible": false }));
+        identify(&mut s);
+        let items = request(&mut s, "GetSceneItemList", json!({ "sceneName": SCENE_NAME }));
+        assert_eq!(items["responseData"]["sceneItems"][1]["sourceName"], "Keyboard");
+        assert_eq!(items["responseData"]["sceneItems"][1]["sceneItemEnabled"], false);
+        let id = request(&mut s, "GetSceneItemId", json!({ "sceneName": SCENE_NAME, "sourceName": "Keyboard" }));
+        assert_eq!(id["responseData"]["sceneItemId"], KEYBOARD_ITEM);
+        let enabled = request(&mut s, "GetSceneItemEnabled", json!({ "sceneUuid": SCENE_UUID, "sceneItemId": 1 }));
+        assert_eq!(enabled["responseData"]["sceneItemEnabled"], true);
+
+        let set = json!({ "sceneName": SCENE_NAME, "sceneItemId": 2, "sceneItemEnabled": true });
+        assert_eq!(request(&mut s, "SetSceneItemEnabled", set)["requestStatus"]["result"], true);
+        assert_eq!(*seen.lock().unwrap(), [Action::SetKeyboardVisible { visible: true }]);
+
+        let failures = [
+            ("SetSceneItemEnabled", json!({ "sceneName": "Other", "sceneItemId": 1, "sceneItemEnabled": true }), status::RESOURCE_NOT_FOUND),
+            ("SetSceneItemEnabled", json!({ "sceneName": SCENE_NAME, "sceneItemId": 9, "sceneItemEnabled": true }), status::RESOURCE_NOT_FOUND),
+            ("SetSceneItemEnabled", json!({ "sceneName": SCENE_NAME, "sceneItemId": 1 }), status::MISSING_REQUEST_FIELD),
+            ("SetSceneItemEnabled", json!({ "sceneName": SCENE_NAME, "sceneItemId": 1, "sceneItemEnabled": "yes" }), status::INVALID_REQUEST_FIELD_TYPE),
+            ("GetSceneItemList", json!({}), status::MISSING_REQUEST_FIELD),
+            ("SetCurrentProgramScene", json!({ "sceneName": SCENE_NAME }), status::UNKNOWN_REQUEST_TYPE),
+        ];
+        for (request_type, data, code) in failures {
+            let reply = request(&mut s, request_type, data.clone());
+            assert_eq!(reply["requestStatus"]["code"], code, "{} {}", request_type, data);
+            assert_eq!(reply["requestStatus"]["result"], false);
+        }
+        assert_eq!(seen.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn batches_and_events() {
+        let (mut s, _) = session(json!({ "recording": false, "recordingStatus": "idle", "cameraVisible": false }));
+        identify(&mut s);
+        let batch = message(
+            op::REQUEST_BATCH,
+            json!({ "requestId": "b", "haltOnFailure": true, "requests": [
+                { "requestType": "GetVersion" }, { "requestType": "Nope" }, { "requestType": "GetVersion" },
+            ] }),
+        );
+        let reply: Value = serde_json::from_str(&s.receive(&batch).unwrap()[0]).unwrap();
+        assert_eq!(reply["op"], op::REQUEST_BATCH_RESPONSE);
+        assert_eq!(reply["d"]["results"].as_array().unwrap().len(), 2);
+
+        let started = s.event(evt::RECORDING_UI, r#"{"status":"recording","elapsed":0}"#);
+        let started: Value = serde_json::from_str(&started[0]).unwrap();
+        assert_eq!(started["d"]["eventType"], "RecordStateChanged");
+        assert_eq!(started["d"]["eventData"]["outputState"], "OBS_WEBSOCKET_OUTPUT_STARTED");
+        // Elapsed ticks are not state changes.
+        assert!(s.event(evt::RECORDING_UI, r#"{"status":"recording","elapsed":1}"#).is_empty());
+
+        let keyboard = s.event(
+            evt::SETTINGS_UPDATED,
+            r#"{"changed":["keyboardDisplayEnabled"],"settings":{"keyboardDisplayEnabled":true}}"#,
+        );
+        let keyboard: Value = serde_json::from_str(&keyboard[0]).unwrap();
+        assert_eq!(keyboard["d"]["eventData"]["sceneItemId"], KEYBOARD_ITEM);
+        assert_eq!(keyboard["d"]["eventData"]["sceneItemEnabled"], true);
+
+        // Unsubscribed from outputs: no record events.
+        s.receive(&message(op::REIDENTIFY, json!({ "eventSubscriptions": INTENT_SCENE_ITEMS }))).unwrap();
+        assert!(s.event(evt::RECORDING_UI, r#"{"status":"paused","elapsed":2}"#).is_empty());
+        assert_eq!(s.event(evt::HOTKEY_TRIGGERED, r#"{"action":"toggle_visibility"}"#).len(), 1);
+    }
+}
@@ -0,0 +1,118 @@
+//! Messages of the control API: what a client may ask for, how it proves
+//! it holds the token, and the JSON sent back.
+//!
+//! A request is a JSON object naming an `action` plus its arguments, e.g.
+//! `{ "action": "setShape", "shape": "mickey" }`. Over the WebSocket a
+//! request may carry an `id`, echoed in its response so a client can match
+//! replies to requests among the streamed events.
+
+use serde_json::{json, Value};
+
+pub use crate::action::Action;
+
+/// Split a request into its `id` (`null` if absent) and its action. The id
+/// is kept even when the action is invalid, so the error can be matched.
+pub fn parse_request(text: &str) -> (Value, Result<Action, String>) {
+    let mut value: Value = match serde_json::from_str(text) {
+        Ok(value) => value,
+        Err(e) => return (Value::Null, Err(format!("request is not JSON: {}", e))),
+    };
+    let id = match value.as_object_mut() {
+        Some(map) => map.remove("id").unwrap_or(Value::Null),
+        None => return (Value::Null, Err("request must be a JSON object".into())),
+    };
+    let action = serde_json::from_value::<Action>(value)
+        .map_err(|e| e.to_string())
+        .and_then(|action| match action {
+            Action::SelectRegion { region: Some(r) } if r.width <= 0 || r.height <= 0 => {
+                Err(format!("region must have a positive size; got {}x{}", r.width, r.height))
+            }
+            action => Ok(action),
+        });
+    (id, action)
+}
+
+/// Reply to one request. `result` is the action's result, or why it failed.
+pub fn response(id: &Value, result: &Result<Value, String>) -> Value {
+    match result {
+        Ok(value) => json!({ "type": "response", "id": id, "ok": true, "result": value }),
+        Err(error) => json!({ "type": "response", "id": id, "ok": false, "error": error }),
+    }
+}
+
+/// An `app://` event as streamed to WebSocket clients. `payload` is the
+/// event's JSON as emitted.
+pub fn event_message(event: &str, payload: &str) -> String {
+    let payload = serde_json::from_str::<Value>(payload).unwrap_or(Value::Null);
+    json!({ "type": "event", "event": event, "payload": payload }).to_string()
+}
+
+/// Whether the request carries `token`, either as `Authorization: Bearer`
+/// or (for WebSocket clients that cannot set headers, such as browsers) as
+/// a `token` query parameter.
+pub fn authorized(token: &str, authorization: Option<&str>, query: Option<&str>) -> bool {
+    let from_header = authorization.and_then(|h| h.strip_prefix("Bearer ")).map(str::trim);
+    let from_query = query.and_then(|q| {
+        q.split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == "token")
+            .map(|(_, value)| value)
+    });
+    !token.is_empty()
+        && [from_header, from_query]
+            .into_iter()
+            .flatten()
+            .any(|given| constant_time_eq(given.as_bytes(), token.as_bytes()))
+}
+
+/// Compare without returning early, so response timing does not reveal how
+/// much of a guessed token was right.
+pub(super) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::ShapePreset;
+
+    #[test]
+    fn parses_actions_with_ids() {
+        let (id, action) = parse_request(r#"{ "id": 7, "action": "setShape", "shape": "mickey" }"#);
+        assert_eq!(id, json!(7));
+        assert_eq!(action.unwrap(), Action::SetShape { shape: ShapePreset::Mickey });
+
+        let (id, action) = parse_request(r#"{ "action": "selectRegion" }"#);
+        assert_eq!(id, Value::Null);
+        assert_eq!(action.unwrap(), Action::SelectRegion { region: None });
+
+        let (_, action) = parse_request(r#"{ "action": "selectRegion", "region": { "x": 0, "y": 0, "width": 1280, "height": 720 } }"#);
+        assert!(matches!(action.unwrap(), Action::SelectRegion { region: Some(r) } if r.width == 1280));
+    }
+
+    #[test]
+    fn rejects_bad_requests_but_keeps_the_id() {
+        for text in [
+            r#"{ "id": "a", "action": "explode" }"#,
+            r#"{ "id": "a", "action": "setShape" }"#,
+            r#"{ "id": "a", "action": "setShape", "shape": "hexagon" }"#,
+            r#"{ "id": "a", "action": "selectRegion", "region": { "x": 0, "y": 0, "width": 0, "height": 9 } }"#,
+        ] {
+            let (id, action) = parse_request(text);
+            assert_eq!(id, json!("a"), "{}", text);
+            assert!(action.is_err(), "{}", text);
+        }
+        assert!(parse_request("[]").1.is_err());
+        assert!(parse_request("toggleLock").1.is_err());
+    }
+
+    #[test]
+    fn token_from_header_or_query() {
+        assert!(authorized("s3cret", Some("Bearer s3cret"), None));
+        assert!(authorized("s3cret", None, Some("v=1&token=s3cret")));
+        assert!(!authorized("s3cret", Some("Bearer s3cre"), Some("token=s3cret2")));
+        assert!(!authorized("s3cret", Some("s3cret"), None));
+        assert!(!authorized("s3cret", None, None));
+        assert!(!authorized("", Some("Bearer "), Some("token=")));
+    }
+}
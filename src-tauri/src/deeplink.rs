@@ -0,0 +1,130 @@
+//! `floaty://` links, so docs, launchers and browser bookmarks can trigger
+//! actions: `floaty://record/start`, `floaty://shape/circle`,
+//! `floaty://region?x=0&y=0&w=1280&h=720`, ...
+//!
+//! The OS hands the link to the deep-link plugin (directly on macOS, as a
+//! launch argument forwarded by the single-instance plugin elsewhere);
+//! [`parse`] turns it into the same [`Action`] the control API runs.
+
+use crate::action::Action;
+use crate::settings::ShapePreset;
+use crate::RecordingRegion;
+
+pub const SCHEME: &str = "floaty";
+
+/// Whether a launch argument is a `floaty://` link rather than a command.
+pub fn is_link(arg: &str) -> bool {
+    arg.get(..SCHEME.len() + 3)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("floaty://"))
+}
+
+pub fn parse(url: &str) -> Result<Action, String> {
+    if !is_link(url) {
+        return Err(format!("not a {}:// link: {}", SCHEME, url));
+    }
+    let rest = &url[SCHEME.len() + 3..];
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    // Some launchers append a slash; hosts may arrive lowercased.
+    let path = path.trim_end_matches('/').to_ascii_lowercase();
+    let segments: Vec<&str> = path.split('/').collect();
+    let unknown = || format!("unknown link: {}", url);
+
+    let action = match segments.as_slice() {
+        ["record", "start"] => Action::StartRecording,
+        ["record", "stop"] => Action::StopRecording,
+        ["record", "toggle"] | ["record"] => Action::ToggleRecording,
+        ["record", "pause"] => Action::TogglePause,
+        ["camera", "show"] => Action::SetCameraVisible { visible: true },
+        ["camera", "hide"] => Action::SetCameraVisible { visible: false },
+        ["camera", "toggle"] | ["camera"] => Action::ToggleVisibility,
+        ["keyboard", "show"] => Action::SetKeyboardVisible { visible: true },
+        ["keyboard", "hide"] => Action::SetKeyboardVisible { visible: false },
+        ["keyboard", "toggle"] | ["keyboard"] => Action::ToggleKeyboard,
+        ["lock", "toggle"] | ["lock"] => Action::ToggleLock,
+        ["settings"] => Action::OpenSettings,
+        ["shape", "next"] => Action::CycleShape,
+        ["shape", preset] => Action::SetShape { shape: shape(preset).ok_or_else(unknown)? },
+        ["region", "reset"] => Action::ResetRegion,
+        ["region"] if query.is_empty() => Action::SelectRegion { region: None },
+        ["region"] => Action::SelectRegion { region: Some(region(query)?) },
+        _ => return Err(unknown()),
+    };
+    Ok(action)
+}
+
+/// Preset names are matched case-insensitively (`roundedsquare` too).
+fn shape(name: &str) -> Option<ShapePreset> {
+    [ShapePreset::Circle, ShapePreset::RoundedSquare, ShapePreset::Mickey]
+        .into_iter()
+        .find(|preset| {
+            serde_json::to_value(preset)
+                .ok()
+                .and_then(|v| v.as_str().map(|s| s.eq_ignore_ascii_case(name)))
+                .unwrap_or(false)
+        })
+}
+
+/// `x`, `y`, `w` and `h`, in physical pixels, all required.
+fn region(query: &str) -> Result<RecordingRegion, String> {
+    let param = |name: &str| -> Result<i32, String> {
+        let value = query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+            .ok_or_else(|| format!("region link is missing `{}`", name))?;
+        value
+            .parse()
+            .map_err(|_| format!("region `{}` must be a whole number; got {}", name, value))
+    };
+    let region = RecordingRegion {
+        x: param("x")?,
+        y: param("y")?,
+        width: param("w")?,
+        height: param("h")?,
+    };
+    if region.width <= 0 || region.height <= 0 {
+        return Err(format!("region must have a positive size; got {}x{}", region.width, region.height));
+    }
+    Ok(region)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_links() {
+        assert_eq!(parse("floaty://record/start").unwrap(), Action::StartRecording);
+        assert_eq!(parse("floaty://record/pause/").unwrap(), Action::TogglePause);
+        assert_eq!(parse("FLOATY://Camera/Hide").unwrap(), Action::SetCameraVisible { visible: false });
+        assert_eq!(parse("floaty://keyboard").unwrap(), Action::ToggleKeyboard);
+        assert_eq!(parse("floaty://shape/circle").unwrap(), Action::SetShape { shape: ShapePreset::Circle });
+        assert_eq!(parse("floaty://shape/roundedSquare").unwrap(), Action::SetShape { shape: ShapePreset::RoundedSquare });
+        assert_eq!(parse("floaty://region").unwrap(), Action::SelectRegion { region: None });
+        assert_eq!(parse("floaty://region/reset").unwrap(), Action::ResetRegion);
+        assert_eq!(
+            parse("floaty://region?x=0&y=-20&w=1280&h=720").unwrap(),
+            Action::SelectRegion {
+                region: Some(RecordingRegion { x: 0, y: -20, width: 1280, height: 720 })
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_bad_links() {
+        for url in [
+            "https://example.com/record/start",
+            "floaty://",
+            "floaty://record/rewind",
+            "floaty://shape/hexagon",
+            "floaty://region?x=0&y=0&w=1280",
+            "floaty://region?x=0&y=0&w=1280&h=tall",
+            "floaty://region?x=0&y=0&w=0&h=720",
+        ] {
+            assert!(parse(url).is_err(), "{}", url);
+        }
+        assert!(is_link("floaty://record/start"));
+        assert!(!is_link("record"));
+    }
+}
@@ -8,11 +8,17 @@
 pub mod evt {
     // Settings
     pub const SETTINGS_UPDATED: &str = "app://settings-updated";
+    pub const SETTINGS_WARNING: &str = "app://settings-warning";
+    pub const SETTINGS_ERROR: &str = "app://settings-error";
+    pub const PROFILES_UPDATED: &str = "app://profiles-updated";
     // Hotkey / tray action fan-out
     pub const HOTKEY_TRIGGERED: &str = "app://hotkey-triggered";
+    pub const HOTKEY_ERROR: &str = "app://hotkey-error";
     // Camera
     pub const CAMERA_ERROR: &str = "app://camera-error";
     pub const CAMERA_REACQUIRE: &str = "app://camera-reacquire";
+    pub const CAMERA_ADDED: &str = "app://camera-added";
+    pub const CAMERA_REMOVED: &str = "app://camera-removed";
     // Keyboard display (macOS event tap)
     pub const KEY_PRESSED: &str = "app://key-pressed";
     pub const KEY_RELEASED: &str = "app://key-released";
@@ -24,6 +30,8 @@ pub mod evt {
     pub const MOUSE_MOVE: &str = "app://mouse-move";
     // Screen recording — orchestration
     pub const RECORDING_STATUS: &str = "app://recording-status";
+    pub const RECORDING_CMD: &str = "app://recording-cmd";
+    pub const RECORDING_UI: &str = "app://recording-ui";
     pub const REGION_STARTED: &str = "app://region-started";
     pub const REGION_SELECTED: &str = "app://region-selected";
     pub const REGION_CANCELED: &str = "app://region-canceled";
@@ -0,0 +1,171 @@
+//! Global hotkeys. Each binding is an `AppSettings` field holding an
+//! accelerator such as `CommandOrControl+Shift+R` (empty = unbound) and runs
+//! one [`Action`] when pressed. Momentary bindings also run one when
+//! released: the camera shows only while `hotkeyHoldToShow` is held, and
+//! recording runs only while `hotkeyHoldToRecord` is. `sync_hotkeys` in
+//! `lib.rs` registers them with the OS on startup and again whenever a
+//! `hotkey*` setting changes.
+//!
+//! Bindings that do not parse, or repeat another binding, fail settings
+//! validation like an out-of-range number. Only the OS can tell whether a
+//! shortcut is free, so a binding another app holds is reported after the
+//! fact as a [`HotkeyError`].
+
+use serde::Serialize;
+use tauri_plugin_global_shortcut::Shortcut;
+
+use crate::action::Action;
+use crate::settings::validate::FieldError;
+use crate::settings::AppSettings;
+
+pub struct Binding {
+    /// The setting, by its camelCase name as stored.
+    pub field: &'static str,
+    pub action: fn() -> Action,
+    /// What releasing the shortcut runs, for momentary bindings.
+    pub release: Option<fn() -> Action>,
+    pub get: fn(&AppSettings) -> &str,
+}
+
+pub const BINDINGS: &[Binding] = &[
+    Binding { field: "hotkeyToggleVisibility", action: || Action::ToggleVisibility, release: None, get: |s| &s.hotkey_toggle_visibility },
+    Binding { field: "hotkeyToggleLock", action: || Action::ToggleLock, release: None, get: |s| &s.hotkey_toggle_lock },
+    Binding { field: "hotkeyOpenSettings", action: || Action::OpenSettings, release: None, get: |s| &s.hotkey_open_settings },
+    Binding { field: "hotkeyToggleRecording", action: || Action::ToggleRecording, release: None, get: |s| &s.hotkey_toggle_recording },
+    Binding { field: "hotkeyTogglePause", action: || Action::TogglePause, release: None, get: |s| &s.hotkey_toggle_pause },
+    Binding { field: "hotkeyToggleKeyboard", action: || Action::ToggleKeyboard, release: None, get: |s| &s.hotkey_toggle_keyboard },
+    Binding { field: "hotkeySelectRegion", action: || Action::SelectRegion { region: None }, release: None, get: |s| &s.hotkey_select_region },
+    Binding { field: "hotkeyCycleShape", action: || Action::CycleShape, release: None, get: |s| &s.hotkey_cycle_shape },
+    Binding { field: "hotkeyNextProfile", action: || Action::ActivateNextProfile, release: None, get: |s| &s.hotkey_next_profile },
+    Binding {
+        field: "hotkeyHoldToShow",
+        action: || Action::SetCameraVisible { visible: true },
+        release: Some(|| Action::SetCameraVisible { visible: false }),
+        get: |s| &s.hotkey_hold_to_show,
+    },
+    Binding {
+        field: "hotkeyHoldToRecord",
+        action: || Action::ResumeRecording,
+        release: Some(|| Action::PauseRecording),
+        get: |s| &s.hotkey_hold_to_record,
+    },
+];
+
+/// A binding registered with the OS, as the shortcut handler sees it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Registered {
+    press: Action,
+    release: Option<Action>,
+    held: bool,
+}
+
+impl Registered {
+    pub fn new(binding: &Binding) -> Self {
+        Self {
+            press: (binding.action)(),
+            release: binding.release.map(|release| release()),
+            held: false,
+        }
+    }
+
+    /// The action to run for a press or release, if any. Presses repeated
+    /// while the key is held (auto-repeat) run nothing.
+    pub fn on(&mut self, pressed: bool) -> Option<Action> {
+        match (pressed, self.held) {
+            (true, true) => None,
+            (true, false) => {
+                self.held = true;
+                Some(self.press.clone())
+            }
+            (false, _) => {
+                self.held = false;
+                self.release.clone()
+            }
+        }
+    }
+}
+
+/// A binding the OS refused to register (usually because another app
+/// holds the shortcut). Emitted as `HOTKEY_ERROR`.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyError {
+    pub field: String,
+    pub shortcut: String,
+    pub message: String,
+}
+
+/// The bound shortcuts, in [`BINDINGS`] order, and the bindings that are
+/// invalid: unparseable, or the same shortcut as an earlier binding.
+pub fn resolve(settings: &AppSettings) -> (Vec<(Shortcut, &'static Binding)>, Vec<FieldError>) {
+    let mut bound: Vec<(Shortcut, &'static Binding)> = Vec::new();
+    let mut errors = Vec::new();
+    for binding in BINDINGS {
+        let text = (binding.get)(settings).trim();
+        if text.is_empty() {
+            continue;
+        }
+        let message = match text.parse::<Shortcut>() {
+            Ok(shortcut) => match bound.iter().find(|(other, _)| *other == shortcut) {
+                Some((_, other)) => format!("{} is already bound to {}", text, other.field),
+                None => {
+                    bound.push((shortcut, binding));
+                    continue;
+                }
+            },
+            Err(e) => format!("{} is not a valid shortcut: {}", text, e),
+        };
+        errors.push(FieldError {
+            field: binding.field.into(),
+            message,
+        });
+    }
+    (bound, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_resolve() {
+        let (bound, errors) = resolve(&AppSettings::default());
+        assert!(errors.is_empty(), "{:?}", errors);
+        let fields: Vec<_> = bound.iter().map(|(_, b)| b.field).collect();
+        assert_eq!(
+            fields,
+            ["hotkeyToggleVisibility", "hotkeyToggleLock", "hotkeyOpenSettings", "hotkeyToggleRecording", "hotkeyNextProfile"]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_and_conflicting_bindings() {
+        let s = AppSettings {
+            hotkey_toggle_pause: "Ctrl+Shift+Banana".into(),
+            hotkey_cycle_shape: "shift+ctrl+keyS".into(),
+            hotkey_select_region: "Control+Shift+S".into(),
+            hotkey_toggle_keyboard: "  ".into(),
+            ..AppSettings::default()
+        };
+        let (bound, errors) = resolve(&s);
+        let fields: Vec<_> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, ["hotkeyTogglePause", "hotkeyCycleShape"]);
+        assert!(errors[1].message.contains("hotkeySelectRegion"));
+        assert!(bound.iter().any(|(_, b)| b.field == "hotkeySelectRegion"));
+    }
+
+    #[test]
+    fn momentary_bindings_act_on_release_and_ignore_repeats() {
+        let hold = BINDINGS.iter().find(|b| b.field == "hotkeyHoldToRecord").unwrap();
+        let mut registered = Registered::new(hold);
+        assert_eq!(registered.on(true), Some(Action::ResumeRecording));
+        assert_eq!(registered.on(true), None);
+        assert_eq!(registered.on(false), Some(Action::PauseRecording));
+        assert_eq!(registered.on(true), Some(Action::ResumeRecording));
+
+        let mut toggle = Registered::new(&BINDINGS[0]);
+        assert_eq!(toggle.on(true), Some(Action::ToggleVisibility));
+        assert_eq!(toggle.on(false), None);
+        assert_eq!(toggle.on(true), Some(Action::ToggleVisibility));
+    }
+}
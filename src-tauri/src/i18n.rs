@@ -0,0 +1,95 @@
+//! Text the Rust side shows itself: tray items, window titles and file
+//! dialog filters. The messages live in the frontend's locale files
+//! (`src/i18n/locales/<tag>.json`, embedded by `build.rs`), so both sides
+//! read one catalog and a new language is just a new file.
+//!
+//! A message a locale lacks falls back through its base language
+//! (`zh-TW` → `zh`) to English, and to the key itself as a last resort.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Every locale file as `(tag, json)`, sorted by tag.
+const FILES: &[(&str, &str)] = include!(concat!(env!("OUT_DIR"), "/locales.rs"));
+
+const FALLBACK: &str = "en";
+
+type Messages = HashMap<String, String>;
+
+fn catalog() -> &'static HashMap<&'static str, Messages> {
+    static CATALOG: OnceLock<HashMap<&'static str, Messages>> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        FILES
+            .iter()
+            .map(|(tag, json)| {
+                let messages = serde_json::from_str(json).unwrap_or_else(|e| {
+                    eprintln!("Locale {} is not a flat JSON object of strings: {}", tag, e);
+                    Messages::new()
+                });
+                (*tag, messages)
+            })
+            .collect()
+    })
+}
+
+/// Tags of the available locales, e.g. `en`, `zh-CN`.
+pub fn locales() -> impl Iterator<Item = &'static str> {
+    FILES.iter().map(|(tag, _)| *tag)
+}
+
+pub fn is_locale(tag: &str) -> bool {
+    catalog().contains_key(tag)
+}
+
+/// The locales `tag` looks messages up in, most specific first.
+pub fn fallback_chain(tag: &str) -> Vec<&str> {
+    let mut chain = vec![tag];
+    if let Some((base, _)) = tag.split_once('-') {
+        chain.push(base);
+    }
+    chain.push(FALLBACK);
+    chain.dedup();
+    chain.retain(|t| is_locale(t));
+    chain
+}
+
+/// The message `key` in `locale`.
+pub fn t(locale: &str, key: &'static str) -> &'static str {
+    let catalog = catalog();
+    fallback_chain(locale)
+        .into_iter()
+        .find_map(|tag| catalog.get(tag)?.get(key))
+        .map(String::as_str)
+        .unwrap_or(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_locale_has_every_key() {
+        let catalog = catalog();
+        let english = &catalog[FALLBACK];
+        assert!(english.contains_key("tray_quit"));
+        for (tag, json) in FILES {
+            let messages: Messages = serde_json::from_str(json).unwrap_or_else(|e| panic!("{}: {}", tag, e));
+            let mut missing: Vec<_> = english.keys().filter(|k| !messages.contains_key(*k)).collect();
+            let mut extra: Vec<_> = messages.keys().filter(|k| !english.contains_key(*k)).collect();
+            missing.sort();
+            extra.sort();
+            assert!(missing.is_empty(), "{} lacks {:?}", tag, missing);
+            assert!(extra.is_empty(), "{} has keys English lacks: {:?}", tag, extra);
+        }
+    }
+
+    #[test]
+    fn falls_back_to_base_language_then_english() {
+        assert_eq!(t("zh-CN", "tray_quit"), "退出");
+        assert_eq!(t("en", "tray_quit"), "Quit");
+        assert_eq!(fallback_chain("fr-CA"), ["en"]);
+        assert_eq!(t("fr-CA", "tray_quit"), "Quit");
+        assert_eq!(t("en", "no_such_message"), "no_such_message");
+        assert!(locales().any(|tag| tag == "zh-CN"));
+    }
+}
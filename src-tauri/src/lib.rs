@@ -1,20 +1,77 @@
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, Ordering};
-use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::tray::TrayIconBuilder;
-use tauri::{App, AppHandle, Emitter, Manager, PhysicalPosition, Size, State, WebviewUrl, WebviewWindowBuilder};
+use tauri::{App, AppHandle, Emitter, Listener, Manager, PhysicalPosition, Size, State, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+use tauri_plugin_opener::OpenerExt;
 use tauri_plugin_store::StoreExt;
 
+mod action;
+mod camera;
+mod cli;
+mod control;
+mod deeplink;
 mod events;
+mod hotkeys;
+mod i18n;
 mod recording;
+mod settings;
+mod tray;
+use action::Action;
+use camera::CameraDevice;
 use events::evt;
-
-/// Application-wide state. Currently holds the single source of truth for
-/// "is recording active", queryable from any window via `get_recording_state`.
+use settings::validate::SettingsError;
+use settings::{AppSettings, Locale, ShapePreset};
+
+/// Application-wide state. Holds the single source of truth for "is
+/// recording active", queryable from any window via `get_recording_state`,
+/// the locks that serialise read-modify-write of draft edit documents and
+/// of settings (the latter guarding the settings revision), a settings
+/// file import waiting for the user to confirm its preview, the control
+/// API server while it runs, the registered hotkeys, the tray items and the
+/// cameras the tray offers.
 /// Registered with `.manage()` — this is the only managed state in the app.
 #[derive(Default)]
 pub struct AppState {
     pub recording_active: AtomicBool,
+    pub edits_lock: std::sync::Mutex<()>,
+    /// Bumped on every settings write that changes something. Not persisted:
+    /// windows fetch it with the settings when they load.
+    pub settings_revision: std::sync::Mutex<u64>,
+    pub pending_settings_import: std::sync::Mutex<Option<settings::profiles::Snapshot>>,
+    /// Load warnings last reported through `SETTINGS_WARNING`. A blob from
+    /// a newer build is never written back, so without this its warnings
+    /// would repeat on every read.
+    pub settings_warnings: std::sync::Mutex<Vec<settings::migrate::FieldWarning>>,
+    /// Last `RECORDING_UI` state broadcast by the recording pipeline.
+    pub recording_ui: std::sync::Mutex<RecordingUi>,
+    /// Set while a start asked for by `ResumeRecording` (hold-to-record)
+    /// is under way, to whether the key has been released since. The
+    /// pipeline only reports the start asynchronously, so a release that
+    /// arrives first is kept here and applied once it is recording.
+    pub pending_start: std::sync::Mutex<Option<bool>>,
+    pub control_server: std::sync::Mutex<Option<control::Server>>,
+    /// Why the control API is not running although enabled.
+    pub control_error: std::sync::Mutex<Option<String>>,
+    /// Registered hotkeys by shortcut id (see `sync_hotkeys`).
+    pub hotkeys: std::sync::Mutex<HashMap<u32, hotkeys::Registered>>,
+    /// Bindings the OS refused at the last `sync_hotkeys`.
+    pub hotkey_errors: std::sync::Mutex<Vec<hotkeys::HotkeyError>>,
+    /// The tray menu's stateful items, once the tray exists.
+    pub tray: std::sync::Mutex<Option<TrayItems>>,
+    /// Cameras the camera window last reported (see `report_cameras`);
+    /// `None` until its first report.
+    pub cameras: std::sync::Mutex<Option<Vec<CameraDevice>>>,
+    /// Cameras found natively at the last hot-plug (see `watch_cameras`).
+    pub native_cameras: std::sync::Mutex<Vec<CameraDevice>>,
+    /// The virtual camera output, while enabled and open.
+    pub virtual_camera: std::sync::Mutex<Option<camera::output::VirtualCamera>>,
+    /// Why the virtual camera is not running although enabled.
+    pub virtual_camera_error: std::sync::Mutex<Option<String>>,
 }
 
 /// Check whether a point (top-left of a window of the given size) lands on any
@@ -48,26 +105,34 @@ const POSITION_KEY: &str = "window_position";
 const KEYBOARD_POSITION_KEY: &str = "keyboard_position";
 const RECORDING_POSITION_KEY: &str = "recording_position";
 const RECORDING_REGION_KEY: &str = "recording_region";
+const PROFILES_KEY: &str = "settings_profiles";
+/// Paths of the last saved recordings, newest first (see
+/// `recording::store::remember_recent`).
+const RECENT_RECORDINGS_KEY: &str = "recent_recordings";
+/// Control API token; kept out of the settings blob so it is never exported.
+const CONTROL_TOKEN_KEY: &str = "control_api_token";
 const MAIN_WINDOW_LABEL: &str = "main";
 const SETTINGS_WINDOW_LABEL: &str = "settings";
 const KEYBOARD_WINDOW_LABEL: &str = "keyboard";
 const RECORDING_WINDOW_LABEL: &str = "recording";
 const REGION_WINDOW_LABEL: &str = "region-select";
 const CURSOR_WINDOW_LABEL: &str = "cursor-overlay";
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct CameraDevice {
-    pub device_id: String,
-    pub label: String,
-    pub group_id: Option<String>,
-}
+/// Catalog key of each window's title (see `i18n`). The camera window has
+/// no visible title.
+const WINDOW_TITLES: &[(&str, &str)] = &[
+    (SETTINGS_WINDOW_LABEL, "window_settings"),
+    (EDITOR_WINDOW_LABEL, "window_editor"),
+    (KEYBOARD_WINDOW_LABEL, "window_keyboard"),
+    (RECORDING_WINDOW_LABEL, "window_recording"),
+    (REGION_WINDOW_LABEL, "window_region_select"),
+    (CURSOR_WINDOW_LABEL, "window_cursor_overlay"),
+];
 
 /// Recording region in physical screen pixels. Same coordinate space as the
 /// mouse coordinates emitted by the event tap. Stored separately from
 /// AppSettings so it can be read/written without rewriting the whole settings
 /// blob during region selection.
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct RecordingRegion {
     pub x: i32,
@@ -76,158 +141,40 @@ pub struct RecordingRegion {
     pub height: i32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// The recording pipeline's state as last broadcast in `RECORDING_UI`
+/// (the pipeline runs in the camera window; Rust only mirrors it).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub enum ShapePreset {
-    Circle,
-    RoundedSquare,
-    Mickey,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct AppSettings {
-    pub selected_camera_id: Option<String>,
-    pub shape: ShapePreset,
-    pub scale: f64,
-    pub mirror: bool,
-    pub always_on_top: bool,
-    pub click_through: bool,
-    pub locked: bool,
-    pub quality_mode: String,
-    #[serde(default)]
-    pub beauty: bool,
-    #[serde(default = "default_beauty_smoothness")]
-    pub beauty_smoothness: f64,
-    #[serde(default = "default_beauty_brightness")]
-    pub beauty_brightness: f64,
-    #[serde(default = "default_locale")]
-    pub locale: String,
-    #[serde(default = "default_theme")]
-    pub theme: String,
+pub struct RecordingUi {
+    /// `idle`, `countdown`, `recording`, `paused` or `saving`.
     #[serde(default)]
-    pub keyboard_display_enabled: bool,
-    #[serde(default = "default_keyboard_position")]
-    pub keyboard_display_position: String,
-    #[serde(default = "default_keyboard_scale")]
-    pub keyboard_display_scale: f64,
-    #[serde(default = "default_keyboard_fade_out")]
-    pub keyboard_display_fade_out: u64,
-    #[serde(default = "default_keyboard_width")]
-    pub keyboard_display_width: f64,
-    #[serde(default = "default_keyboard_style")]
-    pub keyboard_display_style: String,
-    // --- Screen recording ---
-    #[serde(default = "default_recording_enabled")]
-    pub recording_enabled: bool,
-    #[serde(default = "default_recording_fps")]
-    pub recording_fps: u32,
-    #[serde(default = "default_recording_cursor_overlay")]
-    pub recording_cursor_overlay: bool,
+    pub status: String,
+    /// Seconds recorded so far.
     #[serde(default)]
-    pub recording_auto_zoom: bool,
-    #[serde(default = "default_recording_zoom_factor")]
-    pub recording_zoom_factor: f64,
-    #[serde(default = "default_cursor_effect_style")]
-    pub cursor_effect_style: String,
-    #[serde(default = "default_cursor_trail_enabled")]
-    pub cursor_trail_enabled: bool,
-    #[serde(default)]
-    pub recording_output_dir: Option<String>,
-}
-
-fn default_recording_fps() -> u32 {
-    30
-}
-
-fn default_recording_enabled() -> bool {
-    true
-}
-
-fn default_recording_cursor_overlay() -> bool {
-    true
-}
-
-fn default_recording_zoom_factor() -> f64 {
-    2.0
-}
-
-fn default_cursor_effect_style() -> String {
-    "ripple".to_string()
-}
-
-fn default_cursor_trail_enabled() -> bool {
-    true
-}
-
-fn default_keyboard_position() -> String {
-    "bottom-center".to_string()
-}
-
-fn default_keyboard_scale() -> f64 {
-    1.0
-}
-
-fn default_keyboard_fade_out() -> u64 {
-    2000
-}
-
-fn default_keyboard_width() -> f64 {
-    800.0
-}
-
-fn default_keyboard_style() -> String {
-    "dark".to_string()
-}
-
-fn default_beauty_smoothness() -> f64 {
-    30.0
-}
-
-fn default_beauty_brightness() -> f64 {
-    50.0
+    pub elapsed: f64,
 }
 
-fn default_locale() -> String {
-    "en".to_string()
+/// Payload of `SETTINGS_UPDATED`, also returned by `patch_app_settings`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsUpdatedPayload {
+    revision: u64,
+    /// camelCase keys that changed in this write.
+    changed: Vec<String>,
+    settings: AppSettings,
 }
 
-fn default_theme() -> String {
-    "system".to_string()
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RevisionedSettings {
+    revision: u64,
+    settings: AppSettings,
 }
 
-impl Default for AppSettings {
-    fn default() -> Self {
-        Self {
-            selected_camera_id: None,
-            shape: ShapePreset::Circle,
-            scale: 1.0,
-            mirror: true,
-            always_on_top: true,
-            click_through: false,
-            locked: false,
-            quality_mode: "auto".to_string(),
-            beauty: false,
-            beauty_smoothness: 30.0,
-            beauty_brightness: 50.0,
-            locale: "en".to_string(),
-            theme: "system".to_string(),
-            keyboard_display_enabled: true,
-            keyboard_display_position: "bottom-center".to_string(),
-            keyboard_display_scale: 1.0,
-            keyboard_display_fade_out: 2000,
-            keyboard_display_width: 800.0,
-            keyboard_display_style: "dark".to_string(),
-            recording_enabled: true,
-            recording_fps: 30,
-            recording_cursor_overlay: true,
-            recording_auto_zoom: false,
-            recording_zoom_factor: 2.0,
-            cursor_effect_style: "ripple".to_string(),
-            cursor_trail_enabled: true,
-            recording_output_dir: None,
-        }
-    }
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsWarningPayload {
+    warnings: Vec<settings::migrate::FieldWarning>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -243,12 +190,6 @@ fn emit_hotkey(app: &AppHandle, action: &str) {
     let _ = app.emit(evt::HOTKEY_TRIGGERED, payload);
 }
 
-fn normalized_settings(mut settings: AppSettings) -> AppSettings {
-    settings.scale = settings.scale.clamp(0.6, 1.8);
-    settings.quality_mode = "auto".to_string();
-    settings
-}
-
 fn shape_size(shape: &ShapePreset, scale: f64) -> (f64, f64) {
     let base = 320.0 * scale.clamp(0.6, 1.8);
     match shape {
@@ -270,19 +211,51 @@ fn apply_main_window_size(app: &AppHandle, settings: &AppSettings) -> Result<(),
     Ok(())
 }
 
+/// Load settings, upgrading blobs written by older versions. Fields that no
+/// longer parse fall back to their defaults and are reported through
+/// `SETTINGS_WARNING` once per blob: a repaired blob is written back, and
+/// one from a newer build (kept as is) is remembered in `AppState`.
 fn read_settings_from_store(app: &AppHandle) -> Result<AppSettings, String> {
     let store = app.store(STORE_FILE).map_err(|err| err.to_string())?;
 
-    if let Some(value) = store.get(SETTINGS_KEY) {
-        return serde_json::from_value(value.clone()).map_err(|err| err.to_string());
+    let Some(value) = store.get(SETTINGS_KEY) else {
+        return Ok(AppSettings::default());
+    };
+    let loaded = settings::migrate::load(&value);
+    report_settings_warnings(app, &loaded.warnings)?;
+    if loaded.needs_write() {
+        save_settings_to_store(app, &loaded.settings)?;
     }
+    Ok(loaded.settings)
+}
 
-    Ok(AppSettings::default())
+/// Log and emit `SETTINGS_WARNING` for `warnings`, unless they are the ones
+/// last reported (the same blob read again).
+fn report_settings_warnings(app: &AppHandle, warnings: &[settings::migrate::FieldWarning]) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let mut reported = state.settings_warnings.lock().map_err(|e| e.to_string())?;
+    if reported.as_slice() == warnings {
+        return Ok(());
+    }
+    for warning in warnings {
+        eprintln!("Settings field `{}` reset to default: {}", warning.field, warning.message);
+    }
+    if !warnings.is_empty() {
+        let _ = app.emit(
+            evt::SETTINGS_WARNING,
+            SettingsWarningPayload {
+                warnings: warnings.to_vec(),
+            },
+        );
+    }
+    *reported = warnings.to_vec();
+    Ok(())
 }
 
 fn save_settings_to_store(app: &AppHandle, settings: &AppSettings) -> Result<(), String> {
     let store = app.store(STORE_FILE).map_err(|err| err.to_string())?;
-    let value = serde_json::to_value(settings).map_err(|err| err.to_string())?;
+    let value = settings::migrate::to_stored(settings, store.get(SETTINGS_KEY).as_ref())
+        .map_err(|err| err.to_string())?;
 
     store.set(SETTINGS_KEY, value);
     store.save().map_err(|err| err.to_string())?;
@@ -303,9 +276,109 @@ fn apply_window_behavior(app: &AppHandle, settings: &AppSettings) -> Result<(),
     Ok(())
 }
 
+/// A message in the current UI language.
+fn tr(app: &AppHandle, key: &'static str) -> &'static str {
+    i18n::t(read_settings_from_store(app).unwrap_or_default().locale.as_str(), key)
+}
+
+fn window_title(app: &AppHandle, label: &str) -> &'static str {
+    WINDOW_TITLES
+        .iter()
+        .find(|(l, _)| *l == label)
+        .map_or("Floaty", |(_, key)| tr(app, key))
+}
+
+/// Retitle open windows after a locale change.
+fn retitle_windows(app: &AppHandle, locale: &Locale) {
+    for (label, key) in WINDOW_TITLES {
+        if let Some(win) = app.get_webview_window(label) {
+            let _ = win.set_title(i18n::t(locale.as_str(), key));
+        }
+    }
+}
+
+/// Cameras found natively, with their modes (see `camera`). Empty where
+/// native enumeration is not implemented.
+#[tauri::command]
+async fn list_cameras(app: AppHandle) -> Vec<CameraDevice> {
+    without_virtual_camera(&app, camera::list())
+}
+
+/// `cameras` without the device the virtual camera publishes to, which
+/// lists as a camera while it is fed (see `VirtualCamera::is_output`).
+fn without_virtual_camera(app: &AppHandle, mut cameras: Vec<CameraDevice>) -> Vec<CameraDevice> {
+    if let Ok(output) = app.state::<AppState>().virtual_camera.lock() {
+        if let Some(output) = output.as_ref() {
+            cameras.retain(|camera| !output.is_output(camera));
+        }
+    }
+    cameras
+}
+
+/// The camera window's device list, for the tray's camera submenu. Only
+/// the webview knows the ids `getUserMedia` takes, so it reports them
+/// whenever its list changes. When that changes which camera should be in
+/// use (see `resolve_camera`), e.g. the selected camera is back or a
+/// preferred one was plugged in, the window is told to switch
+/// (`CAMERA_REACQUIRE`).
+#[tauri::command]
+fn report_cameras(app: AppHandle, state: State<AppState>, cameras: Vec<CameraDevice>) -> Result<(), String> {
+    let settings = read_settings_from_store(&app)?;
+    let switch = {
+        let mut known = state.cameras.lock().map_err(|e| e.to_string())?;
+        if known.as_ref() == Some(&cameras) {
+            return Ok(());
+        }
+        let pick = |list: &[CameraDevice]| {
+            let list = without_virtual_camera(&app, list.to_vec());
+            camera::resolve(&list, settings.selected_camera_id.as_deref(), &settings.camera_priority).map(String::from)
+        };
+        let next = pick(&cameras);
+        let switch = next.is_some() && known.as_deref().is_some_and(|prev| pick(prev) != next);
+        *known = Some(cameras);
+        switch
+    };
+    if switch {
+        let _ = app.emit(evt::CAMERA_REACQUIRE, ());
+    }
+    refresh_tray_menu(&app, &settings);
+    Ok(())
+}
+
+/// The camera the window should open out of `cameras`: the selected one,
+/// or while it is not connected, the first connected `cameraPriority`
+/// entry (see `camera::resolve`). `None` = try the selection as is.
 #[tauri::command]
-fn list_cameras() -> Vec<CameraDevice> {
-    Vec::new()
+fn resolve_camera(app: AppHandle, cameras: Vec<CameraDevice>) -> Result<Option<String>, String> {
+    let settings = read_settings_from_store(&app)?;
+    let cameras = without_virtual_camera(&app, cameras);
+    Ok(camera::resolve(&cameras, settings.selected_camera_id.as_deref(), &settings.camera_priority).map(String::from))
+}
+
+/// Report cameras being plugged in and out (`CAMERA_ADDED`,
+/// `CAMERA_REMOVED`, each with the native device). The camera window
+/// re-lists its cameras on either; where there is no native watcher it has
+/// the webview's `devicechange` to go on instead.
+fn watch_cameras(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    *state.native_cameras.lock().map_err(|e| e.to_string())? = camera::list();
+    let handle = app.clone();
+    camera::watch::spawn(move || {
+        let next = camera::list();
+        let state = handle.state::<AppState>();
+        let Ok(mut known) = state.native_cameras.lock() else {
+            return;
+        };
+        let (added, removed) = camera::diff(&known, &next);
+        *known = next;
+        drop(known);
+        for device in &added {
+            let _ = handle.emit(evt::CAMERA_ADDED, device);
+        }
+        for device in &removed {
+            let _ = handle.emit(evt::CAMERA_REMOVED, device);
+        }
+    })
 }
 
 #[tauri::command]
@@ -313,39 +386,253 @@ fn get_app_settings(app: AppHandle) -> Result<AppSettings, String> {
     read_settings_from_store(&app)
 }
 
+/// Settings together with the revision they were read at, so a window can
+/// pass it back to `patch_app_settings`.
 #[tauri::command]
-fn save_app_settings(app: AppHandle, payload: AppSettings) -> Result<(), String> {
-    let payload = normalized_settings(payload);
-    save_settings_to_store(&app, &payload)?;
-    apply_window_behavior(&app, &payload)?;
-    apply_main_window_size(&app, &payload)?;
-    update_tray_locale(&app, &payload.locale);
-
-    app.emit(evt::SETTINGS_UPDATED, payload)
+fn get_app_settings_revisioned(app: AppHandle, state: State<AppState>) -> Result<RevisionedSettings, String> {
+    let revision = state.settings_revision.lock().map_err(|e| e.to_string())?;
+    Ok(RevisionedSettings {
+        revision: *revision,
+        settings: read_settings_from_store(&app)?,
+    })
+}
+
+/// Read-modify-write the settings under the settings lock, then validate,
+/// persist, apply and broadcast the result. Every settings write goes
+/// through here, so nothing out of range is ever stored and concurrent
+/// writers cannot drop each other's changes. With `expected_revision`, the
+/// write is rejected if anything else was written since. A write that
+/// changes nothing is not stored or broadcast.
+fn update_settings(
+    app: &AppHandle,
+    expected_revision: Option<u64>,
+    update: impl FnOnce(&mut AppSettings) -> Result<(), SettingsError>,
+) -> Result<SettingsUpdatedPayload, SettingsError> {
+    write_settings(app, expected_revision, settings::patch::Broadcast::IfChanged, update)
+}
+
+/// `update_settings`, choosing whether a write that changes nothing is
+/// still broadcast.
+fn write_settings(
+    app: &AppHandle,
+    expected_revision: Option<u64>,
+    broadcast: settings::patch::Broadcast,
+    update: impl FnOnce(&mut AppSettings) -> Result<(), SettingsError>,
+) -> Result<SettingsUpdatedPayload, SettingsError> {
+    let state = app.state::<AppState>();
+    let mut revision = state.settings_revision.lock().map_err(|e| e.to_string())?;
+    if let Some(expected) = expected_revision {
+        if expected != *revision {
+            return Err(SettingsError::stale(expected, *revision));
+        }
+    }
+    let current = read_settings_from_store(app)?;
+    let mut settings = current.clone();
+    update(&mut settings)?;
+    publish_settings(app, &mut revision, &current, settings, broadcast)
+}
+
+/// Second half of a settings write, with the settings lock held (`revision`
+/// is its guarded value): store `settings` if they differ from `current`,
+/// bump the revision, and queue applying and broadcasting them (also when
+/// unchanged, with `Broadcast::Always`).
+///
+/// The window, tray and hotkey updates are not run here: they block on the
+/// main thread, which may itself be waiting for this lock in a settings
+/// command, while writes also come from control API and file watcher
+/// threads. They are queued to the main thread instead, in revision order
+/// since the lock is still held (on the main thread they run right away).
+fn publish_settings(
+    app: &AppHandle,
+    revision: &mut u64,
+    current: &AppSettings,
+    mut settings: AppSettings,
+    broadcast: settings::patch::Broadcast,
+) -> Result<SettingsUpdatedPayload, SettingsError> {
+    let errors = settings::validate::validate(&settings);
+    if !errors.is_empty() {
+        return Err(SettingsError::fields(errors));
+    }
+    settings.settings_version = settings::migrate::CURRENT_VERSION;
+
+    let changed = settings::patch::changed_keys(current, &settings);
+    if !broadcast.wanted(&changed) {
+        return Ok(SettingsUpdatedPayload {
+            revision: *revision,
+            changed,
+            settings,
+        });
+    }
+    if !changed.is_empty() {
+        save_settings_to_store(app, &settings)?;
+        *revision += 1;
+    }
+
+    let payload = SettingsUpdatedPayload {
+        revision: *revision,
+        changed,
+        settings,
+    };
+    let handle = app.clone();
+    let queued = payload.clone();
+    app.run_on_main_thread(move || apply_settings(&handle, &queued))
         .map_err(|err| err.to_string())?;
+    Ok(payload)
+}
 
+/// Make a stored settings change take effect (see `publish_settings`) and
+/// broadcast it. Runs on the main thread.
+fn apply_settings(app: &AppHandle, payload: &SettingsUpdatedPayload) {
+    let (settings, changed) = (&payload.settings, &payload.changed);
+    if let Err(err) = apply_window_behavior(app, settings).and_then(|_| apply_main_window_size(app, settings)) {
+        eprintln!("Settings not applied to the camera window: {}", err);
+    }
+    if changed.iter().any(|key| key == "locale") {
+        retitle_windows(app, &settings.locale);
+    }
+    if changed.iter().any(|key| tray::MENU_KEYS.contains(&key.as_str())) {
+        refresh_tray_menu(app, settings);
+    } else {
+        sync_tray(app, settings);
+    }
+    if changed.iter().any(|key| key.starts_with("controlApi")) {
+        sync_control_api(app, settings);
+    }
+    if changed.iter().any(|key| key.starts_with("hotkey")) {
+        sync_hotkeys(app, settings);
+    }
+    if changed.iter().any(|key| key.starts_with("virtualCamera")) {
+        sync_virtual_camera(app, settings);
+    }
+    let _ = app.emit(evt::SETTINGS_UPDATED, payload);
+}
+
+/// Re-read the settings blob after `settings.json` changed on disk. An
+/// external edit is loaded like the stored blob at startup (upgraded, bad
+/// fields reset and reported through `SETTINGS_WARNING`), then applied and
+/// broadcast like a save (with a new revision). An edit that cannot be read
+/// or moves the drafts directory is returned as the error and the live
+/// settings stay as they were; the next save writes them back over it.
+fn reload_external_settings(app: &AppHandle) -> Result<(), SettingsError> {
+    let path = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(STORE_FILE);
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    let state = app.state::<AppState>();
+    let mut revision = state.settings_revision.lock().map_err(|e| e.to_string())?;
+    // Read under the lock, so a save of ours in flight is not mistaken for
+    // an external edit.
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        // Deleted: the next save recreates it.
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.to_string().into()),
+    };
+    match settings::watch::classify(&text, store.get(SETTINGS_KEY).as_ref(), SETTINGS_KEY) {
+        settings::watch::ExternalEdit::Unchanged => Ok(()),
+        settings::watch::ExternalEdit::Unreadable(message) => {
+            Err(format!("{} could not be read: {}", STORE_FILE, message).into())
+        }
+        settings::watch::ExternalEdit::Settings(loaded) => {
+            let current = read_settings_from_store(app)?;
+            keep_drafts_dir(&current, &loaded.settings)?;
+            report_settings_warnings(app, &loaded.warnings)?;
+            publish_settings(
+                app,
+                &mut revision,
+                &current,
+                loaded.settings,
+                settings::patch::Broadcast::IfChanged,
+            )?;
+            Ok(())
+        }
+    }
+}
+
+/// Start watching the store file for external edits. Rejected edits are
+/// reported through `SETTINGS_ERROR`.
+fn watch_settings_file(app: &AppHandle) -> Result<(), String> {
+    let path = app.path().app_data_dir().map_err(|e| e.to_string())?.join(STORE_FILE);
+    let handle = app.clone();
+    settings::watch::spawn(path, move || {
+        if let Err(err) = reload_external_settings(&handle) {
+            eprintln!("Ignoring external edit to {}: {}", STORE_FILE, err);
+            let _ = handle.emit(evt::SETTINGS_ERROR, &err);
+        }
+    })
+}
+
+/// Save settings from the frontend. The payload is parsed field by field so
+/// a bad value is reported by name (`SettingsError.fields`) instead of as
+/// one opaque deserialization error; nothing is stored unless all are valid.
+#[tauri::command]
+fn save_app_settings(app: AppHandle, payload: serde_json::Value) -> Result<(), SettingsError> {
+    let settings = settings::validate::parse(&payload)?;
+    update_settings(&app, None, |s| {
+        keep_drafts_dir(s, &settings)?;
+        *s = settings;
+        Ok(())
+    })?;
     Ok(())
 }
 
+/// Refuse a write that moves the drafts directory: only `move_drafts_dir`
+/// may, since it takes the existing drafts along.
+fn keep_drafts_dir(current: &AppSettings, next: &AppSettings) -> Result<(), SettingsError> {
+    if next.drafts_dir == current.drafts_dir {
+        return Ok(());
+    }
+    Err(SettingsError::fields(vec![settings::validate::FieldError {
+        field: "draftsDir".into(),
+        message: "use move_drafts_dir to move the drafts directory".into(),
+    }]))
+}
+
+/// Change only the settings named in `patch` (camelCase keys; `null`
+/// clears an optional one). Pass the `revision` the caller last saw to have
+/// the write rejected (`SettingsError.currentRevision`) if another window
+/// wrote in between.
+#[tauri::command]
+fn patch_app_settings(
+    app: AppHandle,
+    patch: serde_json::Value,
+    revision: Option<u64>,
+) -> Result<SettingsUpdatedPayload, SettingsError> {
+    update_settings(&app, revision, |s| {
+        let next = settings::patch::apply(s, &patch)?;
+        keep_drafts_dir(s, &next)?;
+        *s = next;
+        Ok(())
+    })
+}
+
 #[tauri::command]
 fn apply_window_shape(app: AppHandle, payload: ShapePreset) -> Result<(), String> {
-    let mut settings = read_settings_from_store(&app)?;
-    settings.shape = payload;
-    save_app_settings(app, settings)
+    update_settings(&app, None, |s| {
+        s.shape = payload;
+        Ok(())
+    })?;
+    Ok(())
 }
 
 #[tauri::command]
 fn set_always_on_top(app: AppHandle, enabled: bool) -> Result<(), String> {
-    let mut settings = read_settings_from_store(&app)?;
-    settings.always_on_top = enabled;
-    save_app_settings(app, settings)
+    update_settings(&app, None, |s| {
+        s.always_on_top = enabled;
+        Ok(())
+    })?;
+    Ok(())
 }
 
 #[tauri::command]
 fn set_click_through(app: AppHandle, enabled: bool) -> Result<(), String> {
-    let mut settings = read_settings_from_store(&app)?;
-    settings.click_through = enabled;
-    save_app_settings(app, settings)
+    update_settings(&app, None, |s| {
+        s.click_through = enabled;
+        Ok(())
+    })?;
+    Ok(())
 }
 
 #[tauri::command]
@@ -356,13 +643,14 @@ fn toggle_main_window_visibility(app: AppHandle) -> Result<(), String> {
 
     if main.is_visible().map_err(|err| err.to_string())? {
         main.hide().map_err(|err| err.to_string())?;
-        emit_hotkey(&app, "toggle_visibility");
-        return Ok(());
+    } else {
+        main.show().map_err(|err| err.to_string())?;
+        let _ = main.set_focus();
     }
-
-    main.show().map_err(|err| err.to_string())?;
-    let _ = main.set_focus();
     emit_hotkey(&app, "toggle_visibility");
+    if let Ok(settings) = read_settings_from_store(&app) {
+        sync_tray(&app, &settings);
+    }
 
     Ok(())
 }
@@ -382,7 +670,7 @@ fn open_settings_window(app: AppHandle) -> Result<(), String> {
         SETTINGS_WINDOW_LABEL,
         WebviewUrl::App("settings.html".into()),
     )
-    .title("Floaty Settings")
+    .title(window_title(&app, SETTINGS_WINDOW_LABEL))
     .inner_size(460.0, 760.0)
     .min_inner_size(420.0, 680.0)
     .resizable(true)
@@ -432,7 +720,7 @@ fn toggle_keyboard_window(app: AppHandle, enabled: bool) -> Result<(), String> {
                 OverlayWindowSpec {
                     label: KEYBOARD_WINDOW_LABEL,
                     url: "keyboard.html",
-                    title: "Keyboard Display",
+                    title: window_title(&app, KEYBOARD_WINDOW_LABEL),
                     width,
                     height,
                     resizable: false,
@@ -453,6 +741,308 @@ fn toggle_keyboard_window(app: AppHandle, enabled: bool) -> Result<(), String> {
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// Settings profiles
+// ---------------------------------------------------------------------------
+
+/// Windows whose origin is saved, with their store keys.
+const POSITIONED_WINDOWS: [(&str, &str); 3] = [
+    (MAIN_WINDOW_LABEL, POSITION_KEY),
+    (KEYBOARD_WINDOW_LABEL, KEYBOARD_POSITION_KEY),
+    (RECORDING_WINDOW_LABEL, RECORDING_POSITION_KEY),
+];
+
+fn read_window_position(app: &AppHandle, key: &str) -> Option<settings::profiles::WindowPosition> {
+    let store = app.store(STORE_FILE).ok()?;
+    serde_json::from_value(store.get(key)?).ok()
+}
+
+/// The live setup: settings, saved window origins and recording region.
+fn capture_snapshot(app: &AppHandle) -> Result<settings::profiles::Snapshot, String> {
+    Ok(settings::profiles::Snapshot {
+        settings: read_settings_from_store(app)?,
+        positions: settings::profiles::WindowPositions {
+            main: read_window_position(app, POSITION_KEY),
+            keyboard: read_window_position(app, KEYBOARD_POSITION_KEY),
+            recording: read_window_position(app, RECORDING_POSITION_KEY),
+        },
+        recording_region: get_recording_region(app.clone()),
+    })
+}
+
+/// Make `snapshot` the live setup: store it, move open windows, and
+/// broadcast `SETTINGS_UPDATED` / `REGION_SELECTED` like the individual
+/// setters do; `SETTINGS_UPDATED` is sent even when the settings are the
+/// same, since the positions and region may not be. Positions that no
+/// longer land on a screen are stored but not applied, same as on startup.
+fn restore_snapshot(app: &AppHandle, snapshot: &settings::profiles::Snapshot) -> Result<(), String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    let positions = [
+        snapshot.positions.main,
+        snapshot.positions.keyboard,
+        snapshot.positions.recording,
+    ];
+    for ((label, key), pos) in POSITIONED_WINDOWS.iter().zip(positions) {
+        let Some(pos) = pos else {
+            store.delete(*key);
+            continue;
+        };
+        store.set(*key, serde_json::json!({ "x": pos.x, "y": pos.y }));
+        if let Some(win) = app.get_webview_window(label) {
+            let (w, h) = win
+                .outer_size()
+                .map(|s| (s.width as f64, s.height as f64))
+                .unwrap_or((100.0, 100.0));
+            if is_position_on_screen(app, pos.x, pos.y, w, h) {
+                let _ = win.set_position(PhysicalPosition::new(pos.x as i32, pos.y as i32));
+            }
+        }
+    }
+    match snapshot.recording_region {
+        Some(region) => {
+            let value = serde_json::to_value(region).map_err(|e| e.to_string())?;
+            store.set(RECORDING_REGION_KEY, value);
+        }
+        None => {
+            store.delete(RECORDING_REGION_KEY);
+        }
+    }
+    store.save().map_err(|e| e.to_string())?;
+    let _ = app.emit(evt::REGION_SELECTED, snapshot.recording_region);
+
+    write_settings(app, None, settings::patch::Broadcast::Always, |s| {
+        *s = snapshot.settings_over(s);
+        Ok(())
+    })?;
+    toggle_keyboard_window(app.clone(), snapshot.settings.keyboard_display_enabled)?;
+    toggle_recording_window(app.clone(), snapshot.settings.recording_enabled)?;
+    Ok(())
+}
+
+/// Load the profile list, creating the "Default" profile from the live
+/// setup the first time.
+fn read_profiles(app: &AppHandle) -> Result<settings::profiles::Profiles, String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    // An unreadable list is an error rather than "no profiles", which would
+    // overwrite it with just the default on the next write.
+    let mut profiles: settings::profiles::Profiles = match store.get(PROFILES_KEY) {
+        Some(value) => serde_json::from_value(value).map_err(|e| format!("settings profiles are unreadable: {}", e))?,
+        None => Default::default(),
+    };
+    profiles.ensure_default(|| capture_snapshot(app))?;
+    Ok(profiles)
+}
+
+/// Persist the profile list, then refresh the tray submenu and tell open
+/// windows.
+fn write_profiles(
+    app: &AppHandle,
+    profiles: &settings::profiles::Profiles,
+) -> Result<settings::profiles::ProfilesSummary, String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(PROFILES_KEY, serde_json::to_value(profiles).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| e.to_string())?;
+
+    let summary = profiles.summary();
+    refresh_tray_menu(app, &read_settings_from_store(app).unwrap_or_default());
+    let _ = app.emit(evt::PROFILES_UPDATED, &summary);
+    Ok(summary)
+}
+
+fn new_profile_id() -> String {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    format!("p{}", millis)
+}
+
+/// Switch the live setup to profile `id`. Shared by the command, the tray
+/// submenu and the cycle hotkey.
+fn activate_profile(app: &AppHandle, id: &str) -> Result<settings::profiles::ProfilesSummary, String> {
+    let mut profiles = read_profiles(app)?;
+    let target = profiles.activate(id, capture_snapshot(app)?)?;
+    // Record the switch before applying, so the SETTINGS_UPDATED emitted by
+    // the restore is seen with the new profile already active.
+    let summary = write_profiles(app, &profiles)?;
+    restore_snapshot(app, &target)?;
+    Ok(summary)
+}
+
+#[tauri::command]
+fn list_settings_profiles(app: AppHandle) -> Result<settings::profiles::ProfilesSummary, String> {
+    Ok(read_profiles(&app)?.summary())
+}
+
+/// Save the live setup as a new (inactive) profile.
+#[tauri::command]
+fn create_settings_profile(app: AppHandle, name: String) -> Result<settings::profiles::ProfilesSummary, String> {
+    let mut profiles = read_profiles(&app)?;
+    profiles.create(new_profile_id(), &name, capture_snapshot(&app)?)?;
+    write_profiles(&app, &profiles)
+}
+
+#[tauri::command]
+fn duplicate_settings_profile(
+    app: AppHandle,
+    id: String,
+    name: String,
+) -> Result<settings::profiles::ProfilesSummary, String> {
+    let mut profiles = read_profiles(&app)?;
+    // The active profile's stored snapshot may lag the live state.
+    profiles.store_active(capture_snapshot(&app)?);
+    profiles.duplicate(&id, new_profile_id(), &name)?;
+    write_profiles(&app, &profiles)
+}
+
+#[tauri::command]
+fn rename_settings_profile(
+    app: AppHandle,
+    id: String,
+    name: String,
+) -> Result<settings::profiles::ProfilesSummary, String> {
+    let mut profiles = read_profiles(&app)?;
+    profiles.rename(&id, &name)?;
+    write_profiles(&app, &profiles)
+}
+
+#[tauri::command]
+fn delete_settings_profile(app: AppHandle, id: String) -> Result<settings::profiles::ProfilesSummary, String> {
+    let mut profiles = read_profiles(&app)?;
+    profiles.delete(&id)?;
+    write_profiles(&app, &profiles)
+}
+
+#[tauri::command]
+fn activate_settings_profile(app: AppHandle, id: String) -> Result<settings::profiles::ProfilesSummary, String> {
+    activate_profile(&app, &id)
+}
+
+/// Switch to the next profile in list order (wrapping); bound to a hotkey.
+#[tauri::command]
+fn activate_next_settings_profile(app: AppHandle) -> Result<settings::profiles::ProfilesSummary, String> {
+    let profiles = read_profiles(&app)?;
+    match profiles.next_id() {
+        Some(id) => activate_profile(&app, &id),
+        None => Ok(profiles.summary()),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Settings files
+// ---------------------------------------------------------------------------
+
+/// What importing a settings file would do, shown before it is applied.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsImportPreview {
+    path: String,
+    changes: Vec<settings::portable::Change>,
+    /// Values that were reset while reading the file, and machine-specific
+    /// values that were replaced by this machine's.
+    warnings: Vec<settings::migrate::FieldWarning>,
+}
+
+/// Write the live setup (settings, window positions, recording region) to a
+/// JSON or TOML file chosen via the native save dialog; the format follows
+/// the extension. Returns the path, or `None` if the user cancelled.
+#[tauri::command]
+async fn export_settings_file(app: AppHandle) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let snapshot = capture_snapshot(&app)?;
+    let app_version = app.package_info().version.to_string();
+    tauri::async_runtime::spawn_blocking(move || -> Result<Option<String>, String> {
+        let picked = app
+            .dialog()
+            .file()
+            .add_filter("JSON", &["json"])
+            .add_filter("TOML", &["toml"])
+            .set_file_name("floaty-settings.json")
+            .blocking_save_file();
+        let Some(path) = picked else {
+            return Ok(None);
+        };
+        let path = path.into_path().map_err(|e| e.to_string())?;
+        let format = settings::portable::FileFormat::from_path(&path);
+        let text = settings::portable::encode(&snapshot, &app_version, format)?;
+        std::fs::write(&path, text).map_err(|e| e.to_string())?;
+        Ok(path.to_str().map(|s| s.to_string()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Read a settings file chosen via the native open dialog and describe what
+/// importing it would change. Nothing is applied: the decoded setup is held
+/// until `apply_settings_import`. `camera_ids` are the devices the webview
+/// can see, since camera ids differ between machines. Returns `None` if the
+/// user cancelled.
+#[tauri::command]
+async fn preview_settings_import(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    camera_ids: Vec<String>,
+) -> Result<Option<SettingsImportPreview>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let handle = app.clone();
+    let picked = tauri::async_runtime::spawn_blocking(move || -> Result<Option<(String, String)>, String> {
+        let picked = handle
+            .dialog()
+            .file()
+            .add_filter(tr(&handle, "dialog_filter_settings"), &["json", "toml"])
+            .blocking_pick_file();
+        let Some(path) = picked else {
+            return Ok(None);
+        };
+        let path = path.into_path().map_err(|e| e.to_string())?;
+        let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        Ok(Some((path.to_string_lossy().into_owned(), text)))
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    let Some((path, text)) = picked else {
+        return Ok(None);
+    };
+
+    let format = settings::portable::FileFormat::from_path(std::path::Path::new(&path));
+    let decoded = settings::portable::decode(&text, format)?;
+    let mut snapshot = decoded.snapshot;
+    let mut warnings = decoded.warnings;
+    let current = capture_snapshot(&app)?;
+    let on_screen = |x, y, w, h| is_position_on_screen(&app, x, y, w, h);
+    let machine = settings::portable::Machine {
+        camera_ids: &camera_ids,
+        dir_exists: &|dir| std::path::Path::new(dir).is_dir(),
+        on_screen: &on_screen,
+    };
+    warnings.extend(settings::portable::localize(&mut snapshot, &current, &machine));
+    let changes = settings::portable::diff(&current, &snapshot);
+
+    *state.pending_settings_import.lock().map_err(|e| e.to_string())? = Some(snapshot);
+    Ok(Some(SettingsImportPreview {
+        path,
+        changes,
+        warnings,
+    }))
+}
+
+/// Apply the import last previewed, the same way switching profiles does.
+#[tauri::command]
+fn apply_settings_import(app: AppHandle, state: State<AppState>) -> Result<(), String> {
+    let pending = state.pending_settings_import.lock().map_err(|e| e.to_string())?.take();
+    let snapshot = pending.ok_or("no settings import to apply")?;
+    restore_snapshot(&app, &snapshot)
+}
+
+#[tauri::command]
+fn cancel_settings_import(state: State<AppState>) {
+    if let Ok(mut pending) = state.pending_settings_import.lock() {
+        *pending = None;
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Screen recording commands
 // ---------------------------------------------------------------------------
@@ -581,7 +1171,7 @@ fn toggle_recording_window(app: AppHandle, enabled: bool) -> Result<(), String>
                 OverlayWindowSpec {
                     label: RECORDING_WINDOW_LABEL,
                     url: "recording.html",
-                    title: "Floaty Recording",
+                    title: window_title(&app, RECORDING_WINDOW_LABEL),
                     width: 430.0,
                     height: 52.0,
                     resizable: false,
@@ -622,7 +1212,7 @@ fn start_region_select(app: AppHandle) -> Result<(), String> {
             OverlayWindowSpec {
                 label: REGION_WINDOW_LABEL,
                 url: "region.html",
-                title: "Select Recording Region",
+                title: window_title(&app, REGION_WINDOW_LABEL),
                 width: phys_w / scale,
                 height: phys_h / scale,
                 resizable: false,
@@ -697,7 +1287,7 @@ fn set_cursor_overlay(app: AppHandle, enabled: bool) -> Result<(), String> {
                 OverlayWindowSpec {
                     label: CURSOR_WINDOW_LABEL,
                     url: "cursor.html",
-                    title: "Cursor Overlay",
+                    title: window_title(&app, CURSOR_WINDOW_LABEL),
                     width: w,
                     height: h,
                     resizable: false,
@@ -791,7 +1381,7 @@ async fn save_recording(app: AppHandle, bytes: Vec<u8>, suggested_name: String)
         let mut builder = app
             .dialog()
             .file()
-            .add_filter("Video", &["mp4", "webm"])
+            .add_filter(tr(&app, "dialog_filter_video"), &["mp4", "webm"])
             .set_file_name(&name);
         if let Some(dir) = default_dir.as_deref() {
             builder = builder.set_directory(dir);
@@ -811,81 +1401,150 @@ async fn save_recording(app: AppHandle, bytes: Vec<u8>, suggested_name: String)
     };
 
     recording::store::write_recording(&path, &bytes)?;
-    Ok(path.to_str().map(|s| s.to_string()))
+    let Some(path) = path.to_str().map(|s| s.to_string()) else {
+        return Ok(None);
+    };
+    remember_recording(&app, &path)?;
+    Ok(Some(path))
+}
+
+fn read_recent_recordings(app: &AppHandle) -> Vec<String> {
+    app.store(STORE_FILE)
+        .ok()
+        .and_then(|store| store.get(RECENT_RECORDINGS_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Add a saved recording to the tray's recent list.
+fn remember_recording(app: &AppHandle, path: &str) -> Result<(), String> {
+    let mut recent = read_recent_recordings(app);
+    recording::store::remember_recent(&mut recent, path);
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(RECENT_RECORDINGS_KEY, serde_json::to_value(&recent).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| e.to_string())?;
+    refresh_tray_menu(app, &read_settings_from_store(app)?);
+    Ok(())
+}
+
+/// `path` if it is one of the recent recordings. Actions can arrive from
+/// the control API, so they never open arbitrary paths.
+fn recent_recording(app: &AppHandle, path: &str) -> Result<String, String> {
+    read_recent_recordings(app)
+        .into_iter()
+        .find(|p| p == path)
+        .ok_or_else(|| format!("{} is not a recent recording", path))
 }
 
 // --- Recording editor (post-capture trim/export) ---
 
 const EDITOR_WINDOW_LABEL: &str = "editor";
-const EDITOR_DRAFT_KEY: &str = "editor_draft_path";
+const EDITOR_DRAFT_KEY: &str = "editor_draft_id";
 
 /// Directory holding un-edited recordings between capture and export.
-/// Lives in the OS temp dir; drafts older than 24h are pruned on write.
-fn drafts_dir() -> std::path::PathBuf {
-    std::env::temp_dir().join("floaty-drafts")
+/// Defaults to `<app data>/drafts` (per user, on a real disk rather than a
+/// shared, often tmpfs-backed `/tmp`); `AppSettings.drafts_dir` moves it.
+/// Drafts older than 24h are pruned on write. Commands never take paths
+/// into it — drafts are addressed by id and resolved through
+/// `recording::drafts`, which owns the confinement rules.
+fn drafts_dir(app: &AppHandle) -> std::path::PathBuf {
+    let settings = read_settings_from_store(app).unwrap_or_default();
+    recording::drafts::resolve_dir(settings.drafts_dir.as_deref(), default_drafts_dir(app))
+}
+
+fn default_drafts_dir(app: &AppHandle) -> std::path::PathBuf {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("drafts"))
+        .unwrap_or_else(|_| std::env::temp_dir().join("floaty-drafts"))
+}
+
+/// Check the drafts volume has room for a recording. Called by the pipeline
+/// before capture starts so a full disk fails fast instead of after a long
+/// take. Returns the available bytes.
+#[tauri::command]
+async fn check_drafts_space(app: AppHandle) -> Result<u64, String> {
+    let dir = recording::drafts::ensure_dir(&drafts_dir(&app))?;
+    recording::drafts::ensure_free_space(&dir, recording::drafts::MIN_FREE_BYTES)
+}
+
+/// Move the drafts directory to `target` (or back to the default when
+/// `None`), migrating existing drafts and sidecars, then persist the new
+/// location. Files that would overwrite something at the destination are
+/// left in place and reported.
+#[tauri::command]
+async fn move_drafts_dir(app: AppHandle, target: Option<String>) -> Result<recording::drafts::MigrationReport, String> {
+    let target = target.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    if let Some(dir) = target.as_deref() {
+        if !std::path::Path::new(dir).is_absolute() {
+            return Err("drafts directory must be an absolute path".to_string());
+        }
+    }
+    let from = drafts_dir(&app);
+    let to = recording::drafts::resolve_dir(target.as_deref(), default_drafts_dir(&app));
+    let report = recording::drafts::migrate(&from, &to)?;
+
+    update_settings(&app, None, |s| {
+        s.drafts_dir = target;
+        Ok(())
+    })?;
+    Ok(report)
 }
 
 /// Persist a freshly-recorded clip as a draft and remember it as the editor's
-/// current source. Returns the draft path.
+/// current source. Returns the draft id.
 #[tauri::command]
 async fn save_recording_draft(app: AppHandle, bytes: Vec<u8>, ext: String) -> Result<String, String> {
-    let dir = drafts_dir();
-    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let dir = recording::drafts::ensure_dir(&drafts_dir(&app))?;
     // Prune stale drafts so abandoned edits don't pile up (recordings are big).
-    if let Ok(entries) = std::fs::read_dir(&dir) {
-        let cutoff = std::time::SystemTime::now() - std::time::Duration::from_secs(24 * 3600);
-        for entry in entries.flatten() {
-            if let Ok(meta) = entry.metadata() {
-                if meta.modified().map(|m| m < cutoff).unwrap_or(false) {
-                    let _ = std::fs::remove_file(entry.path());
-                }
-            }
-        }
-    }
+    recording::drafts::prune(&dir, std::time::SystemTime::now() - std::time::Duration::from_secs(24 * 3600));
     let ext = if ext == "webm" { "webm" } else { "mp4" };
-    let millis = std::time::SystemTime::now()
+    let id = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map(|d| d.as_millis())
-        .unwrap_or(0);
-    let path = dir.join(format!("draft-{}.{}", millis, ext));
+        .unwrap_or(0)
+        .to_string();
+    let path = dir.join(recording::drafts::draft_file_name(&id, ext));
+    recording::drafts::ensure_free_space(&dir, bytes.len() as u64)?;
     recording::store::write_recording(&path, &bytes)?;
 
     let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
-    store.set(EDITOR_DRAFT_KEY, serde_json::json!(path.to_str().unwrap_or_default()));
+    store.set(EDITOR_DRAFT_KEY, serde_json::json!(id));
     let _ = store.save();
-    Ok(path.to_str().unwrap_or_default().to_string())
+    Ok(id)
 }
 
-/// The draft path the editor window should load (set by save_recording_draft).
+/// The draft id the editor window should load (set by save_recording_draft).
 #[tauri::command]
-async fn get_editor_draft_path(app: AppHandle) -> Option<String> {
+async fn get_editor_draft_id(app: AppHandle) -> Option<String> {
     let store = app.store(STORE_FILE).ok()?;
     store.get(EDITOR_DRAFT_KEY)?.as_str().map(|s| s.to_string())
 }
 
-/// Read a draft back for the editor preview. Restricted to the drafts dir so
-/// the editor can't exfiltrate arbitrary files. Returned as raw bytes (Tauri
-/// v2 binary response — avoids JSON-encoding megabytes of video).
+/// Read a draft back as raw bytes. The editor itself plays drafts through
+/// the `floaty-draft://` scheme (see `recording::stream`), which avoids
+/// buffering the whole file; this stays for small reads.
 #[tauri::command]
-async fn read_recording_file(path: String) -> Result<tauri::ipc::Response, String> {
-    let p = std::path::PathBuf::from(&path);
-    if !p.starts_with(drafts_dir()) {
-        return Err("path is outside the drafts directory".to_string());
-    }
+async fn read_recording_file(app: AppHandle, id: String) -> Result<tauri::ipc::Response, String> {
+    let p = recording::drafts::resolve(&drafts_dir(&app), &id)?;
     let data = std::fs::read(&p).map_err(|e| e.to_string())?;
     Ok(tauri::ipc::Response::new(data))
 }
 
 /// Delete a draft after a successful export or when the user discards it.
-/// Also removes the metadata sidecar (`draft-<id>.json`) if present, so the
-/// two files don't get orphaned from each other.
+/// Also removes the metadata sidecar (`draft-<id>.json`) and the edit list
+/// (`draft-<id>.edits.json`) if present, so they don't get orphaned from
+/// the video.
 #[tauri::command]
-async fn delete_recording_draft(path: String) -> Result<(), String> {
-    let p = std::path::PathBuf::from(&path);
-    if p.starts_with(drafts_dir()) {
+async fn delete_recording_draft(app: AppHandle, id: String) -> Result<(), String> {
+    let dir = drafts_dir(&app);
+    if let Ok(p) = recording::drafts::resolve(&dir, &id) {
         let _ = std::fs::remove_file(&p);
-        let _ = std::fs::remove_file(recording::store::meta_sidecar_path(&p));
     }
+    let sidecar = recording::drafts::resolve_sidecar(&dir, &id)?;
+    let _ = std::fs::remove_file(&sidecar);
+    let edits = recording::drafts::resolve_edits(&dir, &id)?;
+    let _ = std::fs::remove_file(&edits);
     Ok(())
 }
 
@@ -896,25 +1555,18 @@ async fn delete_recording_draft(path: String) -> Result<(), String> {
 /// `draft-<id>.json` and is pruned by the same 24h GC that sweeps the
 /// drafts directory.
 #[tauri::command]
-async fn save_recording_meta(draft_path: String, meta: serde_json::Value) -> Result<(), String> {
-    let p = std::path::PathBuf::from(&draft_path);
-    if !p.starts_with(drafts_dir()) {
-        return Err("draft path is outside the drafts directory".to_string());
-    }
+async fn save_recording_meta(app: AppHandle, draft_id: String, meta: serde_json::Value) -> Result<(), String> {
+    let sidecar = recording::drafts::resolve_sidecar(&drafts_dir(&app), &draft_id)?;
     let json = serde_json::to_string(&meta).map_err(|e| e.to_string())?;
-    recording::store::write_recording_meta(&recording::store::meta_sidecar_path(&p), &json)
+    recording::store::write_recording_meta(&sidecar, &json)
 }
 
 /// Read the metadata sidecar for a draft. Returns `null` when no sidecar
 /// exists (e.g. recordings made before sidecars were introduced), so the
 /// editor can gracefully degrade: no zoom preview, export still works.
 #[tauri::command]
-async fn read_recording_meta(draft_path: String) -> Result<Option<serde_json::Value>, String> {
-    let p = std::path::PathBuf::from(&draft_path);
-    if !p.starts_with(drafts_dir()) {
-        return Err("draft path is outside the drafts directory".to_string());
-    }
-    let sidecar = recording::store::meta_sidecar_path(&p);
+async fn read_recording_meta(app: AppHandle, draft_id: String) -> Result<Option<serde_json::Value>, String> {
+    let sidecar = recording::drafts::resolve_sidecar(&drafts_dir(&app), &draft_id)?;
     match std::fs::read_to_string(&sidecar) {
         Ok(body) => {
             let value: serde_json::Value = serde_json::from_str(&body).map_err(|e| e.to_string())?;
@@ -925,6 +1577,203 @@ async fn read_recording_meta(draft_path: String) -> Result<Option<serde_json::Va
     }
 }
 
+/// Read the persisted edit decision list for a draft, with undo/redo
+/// availability. Returns `null` when the draft has never been edited, so the
+/// editor starts from its defaults.
+#[tauri::command]
+async fn read_recording_edits(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    draft_id: String,
+) -> Result<Option<recording::edits::EditState>, String> {
+    let path = recording::drafts::resolve_edits(&drafts_dir(&app), &draft_id)?;
+    let _guard = state.edits_lock.lock().map_err(|e| e.to_string())?;
+    Ok(recording::edits::load(&path)?.map(|doc| doc.state()))
+}
+
+/// Record a new edit decision list for a draft. The previous list is pushed
+/// onto the undo history (identical lists are ignored) and the document is
+/// written next to the draft. Returns the normalised state the editor should
+/// display.
+#[tauri::command]
+async fn save_recording_edits(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    draft_id: String,
+    edits: recording::edits::EditDecisionList,
+) -> Result<recording::edits::EditState, String> {
+    let dir = drafts_dir(&app);
+    // Only drafts that still exist get an edit list; a late autosave after
+    // export/discard must not leave an orphan behind.
+    recording::drafts::resolve(&dir, &draft_id)?;
+    let path = recording::drafts::resolve_edits(&dir, &draft_id)?;
+    let edits = recording::edits::normalize(edits);
+    let _guard = state.edits_lock.lock().map_err(|e| e.to_string())?;
+    let doc = match recording::edits::load(&path)? {
+        Some(mut doc) => {
+            doc.apply(edits);
+            doc
+        }
+        None => recording::edits::EditDocument::new(edits),
+    };
+    recording::edits::store(&path, &doc)?;
+    Ok(doc.state())
+}
+
+/// Step a draft's edit history back (`redo == false`) or forward. Returns
+/// `null` when the draft has no edit document; otherwise the resulting
+/// state, unchanged if there was nothing to undo/redo.
+fn step_recording_edits(
+    app: &AppHandle,
+    state: &AppState,
+    draft_id: &str,
+    redo: bool,
+) -> Result<Option<recording::edits::EditState>, String> {
+    let path = recording::drafts::resolve_edits(&drafts_dir(app), draft_id)?;
+    let _guard = state.edits_lock.lock().map_err(|e| e.to_string())?;
+    let Some(mut doc) = recording::edits::load(&path)? else {
+        return Ok(None);
+    };
+    let moved = if redo { doc.redo() } else { doc.undo() };
+    if moved {
+        recording::edits::store(&path, &doc)?;
+    }
+    Ok(Some(doc.state()))
+}
+
+/// Undo the last edit to a draft; see `step_recording_edits`.
+#[tauri::command]
+async fn undo_recording_edits(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    draft_id: String,
+) -> Result<Option<recording::edits::EditState>, String> {
+    step_recording_edits(&app, &state, &draft_id, false)
+}
+
+/// Redo the last undone edit to a draft; see `step_recording_edits`.
+#[tauri::command]
+async fn redo_recording_edits(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    draft_id: String,
+) -> Result<Option<recording::edits::EditState>, String> {
+    step_recording_edits(&app, &state, &draft_id, true)
+}
+
+/// Pack a draft (video, metadata sidecar, its saved edit decision list and
+/// a poster frame) into a `.floaty` bundle at a location chosen via the
+/// native save dialog. Returns the bundle path, or `None` if the user
+/// cancelled.
+#[tauri::command]
+async fn export_project_bundle(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    draft_id: String,
+    poster_png: Option<Vec<u8>>,
+) -> Result<Option<String>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let dir = drafts_dir(&app);
+    let media = recording::drafts::resolve(&dir, &draft_id)?;
+    let edits = {
+        let path = recording::drafts::resolve_edits(&dir, &draft_id)?;
+        let _guard = state.edits_lock.lock().map_err(|e| e.to_string())?;
+        recording::edits::load(&path)?.map(|doc| doc.current)
+    };
+    let sidecar = recording::drafts::resolve_sidecar(&dir, &draft_id)?;
+    let meta = match std::fs::read_to_string(&sidecar) {
+        Ok(body) => Some(serde_json::from_str::<serde_json::Value>(&body).map_err(|e| e.to_string())?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let settings = read_settings_from_store(&app).unwrap_or_default();
+    let default_dir = recording::store::resolve_output_dir(settings.recording_output_dir.as_deref());
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let name = format!(
+        "floaty-{}.{}",
+        recording::store::timestamp_parts(secs),
+        recording::bundle::EXTENSION
+    );
+    let app_version = app.package_info().version.to_string();
+
+    // Dialog and the (potentially multi-gigabyte) zip write both stay off
+    // the main thread.
+    tauri::async_runtime::spawn_blocking(move || -> Result<Option<String>, String> {
+        let mut builder = app
+            .dialog()
+            .file()
+            .add_filter(tr(&app, "dialog_filter_project"), &[recording::bundle::EXTENSION])
+            .set_file_name(&name);
+        if let Some(dir) = default_dir.as_deref() {
+            builder = builder.set_directory(dir);
+        }
+        let Some(path) = builder.blocking_save_file() else {
+            return Ok(None);
+        };
+        let path = path.into_path().map_err(|e| e.to_string())?;
+        let out = std::fs::File::create(&path).map_err(|e| e.to_string())?;
+        recording::bundle::write_bundle(
+            std::io::BufWriter::new(out),
+            recording::bundle::BundleParts {
+                media: &media,
+                meta,
+                edits,
+                poster_png: poster_png.as_deref(),
+                app_version: &app_version,
+                created_at: secs,
+            },
+        )?;
+        Ok(path.to_str().map(|s| s.to_string()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Open a `.floaty` bundle chosen via the native open dialog and unpack it
+/// as a fresh draft (with its edit list restored), which becomes the
+/// editor's current source. Returns the new draft id, or `None` if the user
+/// cancelled.
+#[tauri::command]
+async fn import_project_bundle(app: AppHandle) -> Result<Option<recording::bundle::ImportedDraft>, String> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let dir = drafts_dir(&app);
+    let handle = app.clone();
+    let imported = tauri::async_runtime::spawn_blocking(
+        move || -> Result<Option<recording::bundle::ImportedDraft>, String> {
+            let picked = handle
+                .dialog()
+                .file()
+                .add_filter(tr(&handle, "dialog_filter_project"), &[recording::bundle::EXTENSION])
+                .blocking_pick_file();
+            let Some(path) = picked else {
+                return Ok(None);
+            };
+            let path = path.into_path().map_err(|e| e.to_string())?;
+            let id = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0)
+                .to_string();
+            recording::bundle::import_bundle(&path, &dir, &id).map(Some)
+        },
+    )
+    .await
+    .map_err(|e| e.to_string())??;
+
+    if let Some(imported) = &imported {
+        let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+        store.set(EDITOR_DRAFT_KEY, serde_json::json!(imported.draft_id));
+        let _ = store.save();
+    }
+    Ok(imported)
+}
+
 /// Open (or replace) the editor window for post-capture trim/export.
 #[tauri::command]
 async fn open_editor_window(app: AppHandle) -> Result<(), String> {
@@ -937,7 +1786,7 @@ async fn open_editor_window(app: AppHandle) -> Result<(), String> {
         EDITOR_WINDOW_LABEL,
         WebviewUrl::App("editor.html".into()),
     )
-    .title("Floaty Editor")
+    .title(window_title(&app, EDITOR_WINDOW_LABEL))
     .inner_size(980.0, 700.0)
     .min_inner_size(760.0, 560.0)
     .resizable(true)
@@ -949,89 +1798,843 @@ async fn open_editor_window(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// Ask the recording control bar to start or stop. The bar must be open to
+/// act on the hotkey action, so it is opened first; the frontend owns the
+/// start/stop decision.
+fn trigger_recording_toggle(app: &AppHandle) {
+    let updated = update_settings(app, None, |s| {
+        s.recording_enabled = true;
+        Ok(())
+    });
+    if updated.is_ok_and(|u| u.changed.iter().any(|k| k == "recordingEnabled")) {
+        let _ = toggle_recording_window(app.clone(), true);
+    }
+    emit_hotkey(app, "toggle_recording");
+}
+
+/// Start, stop or toggle recording. Start and stop do nothing if the
+/// pipeline already is in that state.
+fn record(app: &AppHandle, action: cli::RecordAction) {
+    let active = app.state::<AppState>().recording_active.load(Ordering::SeqCst);
+    let toggle = match action {
+        cli::RecordAction::Start => !active,
+        cli::RecordAction::Stop => active,
+        cli::RecordAction::Toggle => true,
+    };
+    if toggle {
+        trigger_recording_toggle(app);
+    }
+}
+
+/// Whether the recording pipeline is idle (as last broadcast), i.e. not
+/// counting down, recording, paused or saving.
+fn recording_idle(app: &AppHandle) -> bool {
+    app.state::<AppState>()
+        .recording_ui
+        .lock()
+        .map(|ui| matches!(ui.status.as_str(), "" | "idle"))
+        .unwrap_or(true)
+}
+
+/// Send a `RECORDING_CMD` to the pipeline of a recording in progress.
+fn send_recording_cmd(app: &AppHandle, action: &str) -> Result<(), String> {
+    if recording_idle(app) {
+        return Err("not recording".into());
+    }
+    app.emit(evt::RECORDING_CMD, serde_json::json!({ "action": action }))
+        .map_err(|e| e.to_string())
+}
+
+/// Resume a paused recording, or start one when idle (hold-to-record
+/// pressed). See `AppState::pending_start`.
+fn resume_recording(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let mut pending = state.pending_start.lock().map_err(|e| e.to_string())?;
+    if let Some(released) = pending.as_mut() {
+        // Pressed again before the start finished: keep recording.
+        *released = false;
+        return Ok(());
+    }
+    if recording_idle(app) {
+        *pending = Some(false);
+        drop(pending);
+        record(app, cli::RecordAction::Start);
+        return Ok(());
+    }
+    drop(pending);
+    send_recording_cmd(app, "resume")
+}
+
+/// Pause the recording (hold-to-record released). While a start is still
+/// under way, the pause waits for it (see `finish_pending_start`).
+fn pause_recording(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let mut pending = state.pending_start.lock().map_err(|e| e.to_string())?;
+    if let Some(released) = pending.as_mut() {
+        *released = true;
+        return Ok(());
+    }
+    drop(pending);
+    send_recording_cmd(app, "pause")
+}
+
+/// The pipeline moved from `previous` to `status`. Once it leaves idle
+/// (counting down or recording), a start asked for by hold-to-record has
+/// arrived and is paused at once if the key was released meanwhile; pause
+/// commands go straight to the pipeline from then on. Should it go back to
+/// idle first, the start is forgotten.
+fn finish_pending_start(app: &AppHandle, previous: &str, status: &str) {
+    let idle = |s: &str| matches!(s, "" | "idle");
+    if idle(status) == idle(previous) {
+        return;
+    }
+    let released = app
+        .state::<AppState>()
+        .pending_start
+        .lock()
+        .ok()
+        .and_then(|mut pending| pending.take());
+    if !idle(status) && released == Some(true) {
+        let _ = send_recording_cmd(app, "pause");
+    }
+}
+
+/// Run a `floaty <command>` (see `cli`) against this instance. Everything
+/// but `settings set` is an [`Action`].
+fn run_cli_command(app: &AppHandle, command: cli::CliCommand) -> Result<(), String> {
+    use cli::CliCommand;
+
+    let action = match command {
+        CliCommand::Record(cli::RecordAction::Start) => Action::StartRecording,
+        CliCommand::Record(cli::RecordAction::Stop) => Action::StopRecording,
+        CliCommand::Record(cli::RecordAction::Toggle) => Action::ToggleRecording,
+        CliCommand::Camera { visible } => Action::SetCameraVisible { visible },
+        CliCommand::RegionSet(region) => Action::SelectRegion { region: Some(region) },
+        CliCommand::RegionReset => Action::ResetRegion,
+        CliCommand::ShapeSet(shape) => Action::SetShape { shape },
+        CliCommand::SettingsSet(patch) => {
+            let patch = serde_json::Value::Object(patch);
+            let updated = update_settings(app, None, |s| {
+                let next = settings::patch::apply(s, &patch)?;
+                keep_drafts_dir(s, &next)?;
+                *s = next;
+                Ok(())
+            });
+            if let Err(err) = updated {
+                // Nobody sees the CLI's output once forwarded; tell the UI.
+                let _ = app.emit(evt::SETTINGS_ERROR, &err);
+                return Err(err.into());
+            }
+            return Ok(());
+        }
+        // Answered by the launching process before it forwards anything.
+        CliCommand::Help | CliCommand::SettingsGet(_) => return Ok(()),
+    };
+    dispatch(app, action).map(|_| ())
+}
+
+/// Show or hide the camera window, if it is not already in that state.
+fn set_camera_visible(app: &AppHandle, visible: bool) -> Result<(), String> {
+    let shown = app
+        .get_webview_window(MAIN_WINDOW_LABEL)
+        .and_then(|w| w.is_visible().ok())
+        .unwrap_or(false);
+    if shown != visible {
+        toggle_main_window_visibility(app.clone())?;
+    }
+    Ok(())
+}
+
+/// Set `keyboardDisplayEnabled` and show or hide the keyboard window to match.
+fn set_keyboard_display(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    update_settings(app, None, |s| {
+        s.keyboard_display_enabled = enabled;
+        Ok(())
+    })?;
+    toggle_keyboard_window(app.clone(), enabled)
+}
+
+/// Flip `keyboardDisplayEnabled` and show or hide the keyboard window to match.
+fn toggle_keyboard_display(app: &AppHandle) -> Result<(), String> {
+    let updated = update_settings(app, None, |s| {
+        s.keyboard_display_enabled = !s.keyboard_display_enabled;
+        Ok(())
+    })?;
+    toggle_keyboard_window(app.clone(), updated.settings.keyboard_display_enabled)
+}
+
+fn toggle_click_through(app: &AppHandle) -> Result<(), String> {
+    update_settings(app, None, |s| {
+        s.click_through = !s.click_through;
+        Ok(())
+    })?;
+    Ok(())
+}
+
+fn toggle_always_on_top(app: &AppHandle) -> Result<(), String> {
+    update_settings(app, None, |s| {
+        s.always_on_top = !s.always_on_top;
+        Ok(())
+    })?;
+    Ok(())
+}
+
 fn toggle_lock_state(app: &AppHandle) -> Result<(), String> {
-    let mut settings = read_settings_from_store(app)?;
-    settings.locked = !settings.locked;
-    save_app_settings(app.clone(), settings)
+    update_settings(app, None, |s| {
+        s.locked = !s.locked;
+        Ok(())
+    })?;
+    Ok(())
+}
+
+fn set_scale(app: &AppHandle, scale: f64) -> Result<(), String> {
+    update_settings(app, None, |s| {
+        s.scale = scale;
+        Ok(())
+    })?;
+    Ok(())
 }
 
-fn tray_text(locale: &str) -> (&'static str, &'static str, &'static str, &'static str, &'static str, &'static str) {
-    match locale {
-        "zh-CN" => ("显示/隐藏摄像头窗", "打开设置", "锁定/解锁拖拽", "显示/隐藏按键", "开始/停止录制", "退出"),
-        _ => ("Show/Hide Camera", "Open Settings", "Lock/Unlock Drag", "Show/Hide Keys", "Start/Stop Recording", "Quit"),
+fn select_camera(app: &AppHandle, id: String) -> Result<(), String> {
+    update_settings(app, None, |s| {
+        s.selected_camera_id = Some(id);
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// Switch to the next shape preset, wrapping around.
+fn cycle_shape(app: &AppHandle) -> Result<(), String> {
+    update_settings(app, None, |s| {
+        s.shape = match s.shape {
+            ShapePreset::Circle => ShapePreset::RoundedSquare,
+            ShapePreset::RoundedSquare => ShapePreset::Mickey,
+            ShapePreset::Mickey => ShapePreset::Circle,
+        };
+        Ok(())
+    })?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Actions
+// ---------------------------------------------------------------------------
+
+/// Carry out an [`Action`], whoever asked for it: tray, hotkey, deep link,
+/// CLI, control API or a window. Returns the action's result (`null` for
+/// all but status and profile switches).
+fn dispatch(app: &AppHandle, action: Action) -> Result<serde_json::Value, String> {
+    match action {
+        Action::ToggleVisibility => toggle_main_window_visibility(app.clone())?,
+        Action::SetCameraVisible { visible } => set_camera_visible(app, visible)?,
+        Action::ToggleLock => {
+            toggle_lock_state(app)?;
+            emit_hotkey(app, "toggle_lock");
+        }
+        Action::ToggleKeyboard => toggle_keyboard_display(app)?,
+        Action::ToggleClickThrough => toggle_click_through(app)?,
+        Action::ToggleAlwaysOnTop => toggle_always_on_top(app)?,
+        Action::SetKeyboardVisible { visible } => set_keyboard_display(app, visible)?,
+        Action::StartRecording => record(app, cli::RecordAction::Start),
+        Action::StopRecording => record(app, cli::RecordAction::Stop),
+        Action::ToggleRecording => record(app, cli::RecordAction::Toggle),
+        Action::TogglePause => send_recording_cmd(app, "toggle-pause")?,
+        Action::PauseRecording => pause_recording(app)?,
+        Action::ResumeRecording => resume_recording(app)?,
+        Action::SelectRegion { region: Some(region) } => confirm_region(app.clone(), region)?,
+        Action::SelectRegion { region: None } => start_region_select(app.clone())?,
+        Action::ResetRegion => reset_recording_region(app.clone())?,
+        Action::SetShape { shape } => apply_window_shape(app.clone(), shape)?,
+        Action::CycleShape => cycle_shape(app)?,
+        Action::SetScale { scale } => set_scale(app, scale)?,
+        Action::SelectCamera { id } => select_camera(app, id)?,
+        Action::OpenRecording { path } => app
+            .opener()
+            .open_path(recent_recording(app, &path)?, None::<&str>)
+            .map_err(|e| e.to_string())?,
+        Action::RevealRecording { path } => app
+            .opener()
+            .reveal_item_in_dir(recent_recording(app, &path)?)
+            .map_err(|e| e.to_string())?,
+        Action::OpenSettings => open_settings_window(app.clone())?,
+        Action::ActivateProfile { id } => {
+            return serde_json::to_value(activate_profile(app, &id)?).map_err(|e| e.to_string());
+        }
+        Action::ActivateNextProfile => {
+            return serde_json::to_value(activate_next_settings_profile(app.clone())?).map_err(|e| e.to_string());
+        }
+        Action::GetStatus => return control_status(app),
+        Action::Quit => app.exit(0),
     }
+    Ok(serde_json::Value::Null)
 }
 
-fn build_tray_menu(app: &AppHandle, locale: &str) -> Result<Menu<tauri::Wry>, Box<dyn std::error::Error>> {
-    let (show_text, settings_text, lock_text, keyboard_text, recording_text, quit_text) = tray_text(locale);
+/// Run an action from a window (e.g. a hotkey registered by the frontend).
+#[tauri::command]
+fn run_action(app: AppHandle, action: Action) -> Result<serde_json::Value, String> {
+    dispatch(&app, action)
+}
 
-    let show_toggle = MenuItem::new(app, show_text, true, None::<&str>)?;
-    let open_settings = MenuItem::new(app, settings_text, true, None::<&str>)?;
-    let toggle_lock = MenuItem::new(app, lock_text, true, None::<&str>)?;
-    let toggle_keyboard = MenuItem::new(app, keyboard_text, true, None::<&str>)?;
-    let toggle_recording = MenuItem::new(app, recording_text, true, None::<&str>)?;
-    let separator = PredefinedMenuItem::separator(app)?;
-    let quit = MenuItem::new(app, quit_text, true, None::<&str>)?;
+/// Run a `floaty://` link. Links come from outside (a browser, a launcher),
+/// so failures are only logged.
+fn open_deep_link(app: &AppHandle, url: &str) {
+    if let Err(err) = deeplink::parse(url).and_then(|action| dispatch(app, action)) {
+        eprintln!("floaty: {}", err);
+    }
+}
 
-    let show_id = show_toggle.id().clone();
-    let settings_id = open_settings.id().clone();
-    let lock_id = toggle_lock.id().clone();
-    let keyboard_id = toggle_keyboard.id().clone();
-    let recording_id = toggle_recording.id().clone();
-    let quit_id = quit.id().clone();
+// ---------------------------------------------------------------------------
+// Hotkeys
+// ---------------------------------------------------------------------------
 
-    let menu = Menu::with_items(
-        app,
-        &[&show_toggle, &open_settings, &toggle_lock, &toggle_keyboard, &toggle_recording, &separator, &quit],
-    )?;
+/// Register the hotkey bindings in `settings` with the OS, replacing the
+/// previous ones. Bindings the OS refuses are emitted as `HOTKEY_ERROR`
+/// and kept for `get_hotkey_errors`; the others still work.
+fn sync_hotkeys(app: &AppHandle, settings: &AppSettings) {
+    let shortcuts = app.global_shortcut();
+    let state = app.state::<AppState>();
+    let (Ok(mut registered), Ok(mut errors)) = (state.hotkeys.lock(), state.hotkey_errors.lock()) else {
+        return;
+    };
+    if let Err(e) = shortcuts.unregister_all() {
+        eprintln!("Failed to unregister hotkeys: {}", e);
+    }
+    registered.clear();
+    errors.clear();
+    // Invalid or duplicate bindings never get this far: validation rejects
+    // them on save and resets them on load.
+    for (shortcut, binding) in hotkeys::resolve(settings).0 {
+        match shortcuts.register(shortcut) {
+            Ok(()) => {
+                registered.insert(shortcut.id(), hotkeys::Registered::new(binding));
+            }
+            Err(e) => {
+                let error = hotkeys::HotkeyError {
+                    field: binding.field.into(),
+                    shortcut: (binding.get)(settings).into(),
+                    message: e.to_string(),
+                };
+                eprintln!("Hotkey {} not registered: {}", error.shortcut, error.message);
+                let _ = app.emit(evt::HOTKEY_ERROR, &error);
+                errors.push(error);
+            }
+        }
+    }
+}
 
-    // Store item IDs in app state for event matching
-    if let Ok(store) = app.store(STORE_FILE) {
-        let ids = serde_json::json!({
-            "toggle_visibility": show_id.as_ref(),
-            "open_settings": settings_id.as_ref(),
-            "toggle_lock": lock_id.as_ref(),
-            "toggle_keyboard": keyboard_id.as_ref(),
-            "toggle_recording": recording_id.as_ref(),
-            "quit": quit_id.as_ref(),
-        });
-        store.set("_tray_menu_ids", ids);
+/// Run the action bound to a hotkey being pressed or, for momentary
+/// bindings, released.
+fn hotkey_event(app: &AppHandle, shortcut: &tauri_plugin_global_shortcut::Shortcut, pressed: bool) {
+    let action = app
+        .state::<AppState>()
+        .hotkeys
+        .lock()
+        .ok()
+        .and_then(|mut registered| registered.get_mut(&shortcut.id())?.on(pressed));
+    if let Some(action) = action {
+        if let Err(err) = dispatch(app, action) {
+            eprintln!("Hotkey action failed: {}", err);
+        }
     }
+}
 
-    Ok(menu)
+#[tauri::command]
+fn get_hotkey_errors(state: State<AppState>) -> Result<Vec<hotkeys::HotkeyError>, String> {
+    Ok(state.hotkey_errors.lock().map_err(|e| e.to_string())?.clone())
 }
 
-fn resolve_tray_action(app: &AppHandle, event_id: &str) -> Option<&'static str> {
-    let store = app.store(STORE_FILE).ok()?;
-    let ids = store.get("_tray_menu_ids")?;
-    let map = ids.as_object()?;
-    for (action, id_val) in map {
-        if id_val.as_str() == Some(event_id) {
-            return match action.as_str() {
-                "toggle_visibility" => Some("toggle_visibility"),
-                "open_settings" => Some("open_settings"),
-                "toggle_lock" => Some("toggle_lock"),
-                "toggle_keyboard" => Some("toggle_keyboard"),
-                "toggle_recording" => Some("toggle_recording"),
-                "quit" => Some("quit"),
-                _ => None,
+// ---------------------------------------------------------------------------
+// Control API
+// ---------------------------------------------------------------------------
+
+/// What the settings window shows about the control API.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ControlApiInfo {
+    /// Port it listens on; `None` while off or failed to start.
+    port: Option<u16>,
+    token: String,
+    /// Why it is not running although enabled (e.g. the port is taken).
+    error: Option<String>,
+}
+
+/// The control API token, generated and stored on first use.
+fn control_token(app: &AppHandle) -> Result<String, String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    let stored = store
+        .get(CONTROL_TOKEN_KEY)
+        .and_then(|v| v.as_str().map(str::to_string))
+        .filter(|token| !token.is_empty());
+    if let Some(token) = stored {
+        return Ok(token);
+    }
+    let token = control::new_token()?;
+    store.set(CONTROL_TOKEN_KEY, token.clone());
+    store.save().map_err(|e| e.to_string())?;
+    Ok(token)
+}
+
+/// Start, restart or stop the control API to match `settings` and the
+/// stored token. Called at startup and when either changes.
+fn sync_control_api(app: &AppHandle, settings: &AppSettings) {
+    let state = app.state::<AppState>();
+    let (Ok(mut server), Ok(mut error)) = (state.control_server.lock(), state.control_error.lock()) else {
+        return;
+    };
+    if !settings.control_api_enabled {
+        *server = None;
+        *error = None;
+        return;
+    }
+    let started = control_token(app).and_then(|token| {
+        let (port, obs_compat) = (settings.control_api_port, settings.control_api_obs_compat);
+        if server.as_ref().is_some_and(|s| s.serves(port, &token, obs_compat)) {
+            return Ok(());
+        }
+        // Release the old port (and close its clients) before binding.
+        *server = None;
+        let handle = app.clone();
+        let handler: control::Handler = Arc::new(move |action| dispatch(&handle, action));
+        *server = Some(control::Server::start(port, token, obs_compat, handler)?);
+        Ok(())
+    });
+    *error = started.err();
+    if let Some(err) = error.as_ref() {
+        eprintln!("Control API not started: {}", err);
+    }
+}
+
+/// Open, reopen or close the virtual camera to match the settings. A
+/// failure (no loopback device, not Linux) is kept for the settings window.
+fn sync_virtual_camera(app: &AppHandle, settings: &AppSettings) {
+    let state = app.state::<AppState>();
+    let (Ok(mut output), Ok(mut error)) = (state.virtual_camera.lock(), state.virtual_camera_error.lock()) else {
+        return;
+    };
+    if !settings.virtual_camera_enabled {
+        *output = None;
+        *error = None;
+        return;
+    }
+    let device = settings.virtual_camera_device.as_deref();
+    let (width, height) = (settings.virtual_camera_width, settings.virtual_camera_height);
+    if output.as_ref().is_some_and(|o| o.serves(device, width, height)) {
+        return;
+    }
+    // Let go of the device before opening it again at the new size.
+    *output = None;
+    match camera::output::VirtualCamera::open(device, width, height) {
+        Ok(opened) => {
+            *output = Some(opened);
+            *error = None;
+        }
+        Err(err) => {
+            eprintln!("Virtual camera not started: {}", err);
+            *error = Some(err);
+        }
+    }
+}
+
+/// What the settings window shows about the virtual camera.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VirtualCameraInfo {
+    /// Device it publishes to; `None` while off or failed to open.
+    device: Option<String>,
+    /// Why it is not running although enabled.
+    error: Option<String>,
+}
+
+#[tauri::command]
+fn get_virtual_camera_info(state: State<AppState>) -> Result<VirtualCameraInfo, String> {
+    Ok(VirtualCameraInfo {
+        device: state
+            .virtual_camera
+            .lock()
+            .map_err(|e| e.to_string())?
+            .as_ref()
+            .map(|o| o.device().to_string()),
+        error: state.virtual_camera_error.lock().map_err(|e| e.to_string())?.clone(),
+    })
+}
+
+/// One rendered RGBA frame from the camera window, sent as the raw invoke
+/// body (JSON would turn every byte into a number) with its size in the
+/// `x-frame-width` / `x-frame-height` headers. Async so the conversion
+/// stays off the main thread.
+#[tauri::command]
+async fn push_virtual_camera_frame(state: State<'_, AppState>, request: tauri::ipc::Request<'_>) -> Result<(), String> {
+    let tauri::ipc::InvokeBody::Raw(frame) = request.body() else {
+        return Err("expected the frame as raw bytes".into());
+    };
+    let dimension = |name: &str| -> Result<u32, String> {
+        request
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| format!("missing or bad {} header", name))
+    };
+    let (width, height) = (dimension("x-frame-width")?, dimension("x-frame-height")?);
+    let mut output = state.virtual_camera.lock().map_err(|e| e.to_string())?;
+    let Some(camera) = output.as_mut() else {
+        return Err(state
+            .virtual_camera_error
+            .lock()
+            .map_err(|e| e.to_string())?
+            .clone()
+            .unwrap_or_else(|| "the virtual camera is off".into()));
+    };
+    let pushed = camera.push(width, height, frame);
+    // A frame of another size is the window catching up with a resolution
+    // change. Any other failure (the device went away) closes the output and
+    // is kept for `get_virtual_camera_info`, which stops the window's feed.
+    if let Err(err) = &pushed {
+        if camera.size() == (width, height) {
+            eprintln!("Virtual camera stopped: {}", err);
+            *state.virtual_camera_error.lock().map_err(|e| e.to_string())? = Some(err.clone());
+            *output = None;
+        }
+    }
+    pushed
+}
+
+/// Pass an `app://` event on to the control API's WebSocket clients.
+fn forward_control_events(app: &AppHandle) {
+    for &event in control::FORWARDED_EVENTS {
+        let handle = app.clone();
+        app.listen_any(event, move |e| {
+            let state = handle.state::<AppState>();
+            if let Some(server) = state.control_server.lock().ok().as_deref().and_then(Option::as_ref) {
+                server.broadcast(event, e.payload());
+            }
+        });
+    }
+}
+
+/// Keep the recording state in Rust, and the tray showing it, in step with
+/// the pipeline, which runs in the camera window and only broadcasts it.
+fn track_recording_state(app: &AppHandle) {
+    let handle = app.clone();
+    app.listen_any(evt::RECORDING_STATUS, move |e| {
+        if let Ok(status) = serde_json::from_str::<serde_json::Value>(e.payload()) {
+            let active = status["active"].as_bool().unwrap_or(false);
+            handle.state::<AppState>().recording_active.store(active, Ordering::SeqCst);
+            if let Ok(settings) = read_settings_from_store(&handle) {
+                sync_tray(&handle, &settings);
+            }
+        }
+    });
+    let handle = app.clone();
+    app.listen_any(evt::RECORDING_UI, move |e| {
+        if let Ok(ui) = serde_json::from_str::<RecordingUi>(e.payload()) {
+            let status = ui.status.clone();
+            let previous = match handle.state::<AppState>().recording_ui.lock() {
+                Ok(mut last) => std::mem::replace(&mut *last, ui).status,
+                Err(_) => return,
             };
+            finish_pending_start(&handle, &previous, &status);
+            if let Ok(settings) = read_settings_from_store(&handle) {
+                sync_tray(&handle, &settings);
+            }
         }
+    });
+}
+
+/// What `getStatus` reports.
+fn control_status(app: &AppHandle) -> Result<serde_json::Value, String> {
+    let state = app.state::<AppState>();
+    let settings = read_settings_from_store(app)?;
+    let ui = state.recording_ui.lock().map_err(|e| e.to_string())?.clone();
+    let camera_visible = app
+        .get_webview_window(MAIN_WINDOW_LABEL)
+        .and_then(|w| w.is_visible().ok())
+        .unwrap_or(false);
+    Ok(serde_json::json!({
+        "recording": state.recording_active.load(Ordering::SeqCst),
+        "recordingStatus": if ui.status.is_empty() { "idle" } else { ui.status.as_str() },
+        "elapsed": ui.elapsed,
+        "cameraVisible": camera_visible,
+        "keyboardVisible": settings.keyboard_display_enabled,
+        "locked": settings.locked,
+        "shape": settings.shape,
+        "region": get_recording_region(app.clone()),
+    }))
+}
+
+#[tauri::command]
+fn get_control_api_info(app: AppHandle, state: State<AppState>) -> Result<ControlApiInfo, String> {
+    let port = state.control_server.lock().map_err(|e| e.to_string())?.as_ref().map(|s| s.port());
+    Ok(ControlApiInfo {
+        port,
+        token: control_token(&app)?,
+        error: state.control_error.lock().map_err(|e| e.to_string())?.clone(),
+    })
+}
+
+/// Replace the token. Clients holding the old one are disconnected.
+#[tauri::command]
+fn regenerate_control_api_token(app: AppHandle, state: State<AppState>) -> Result<ControlApiInfo, String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(CONTROL_TOKEN_KEY, control::new_token()?);
+    store.save().map_err(|e| e.to_string())?;
+    sync_control_api(&app, &read_settings_from_store(&app)?);
+    get_control_api_info(app, state)
+}
+
+// ---------------------------------------------------------------------------
+// Tray
+// ---------------------------------------------------------------------------
+
+/// The tray items whose state follows the app, and what they last showed
+/// (see `sync_tray`).
+#[derive(Clone)]
+pub struct TrayItems {
+    locale: Locale,
+    camera: CheckMenuItem<tauri::Wry>,
+    locked: CheckMenuItem<tauri::Wry>,
+    keyboard: CheckMenuItem<tauri::Wry>,
+    click_through: CheckMenuItem<tauri::Wry>,
+    always_on_top: CheckMenuItem<tauri::Wry>,
+    recording: MenuItem<tauri::Wry>,
+    shown: Option<TrayShown>,
+}
+
+#[derive(Clone, PartialEq)]
+struct TrayShown {
+    checks: [bool; 5],
+    recording: tray::Recording,
+}
+
+fn menu_refs<T: IsMenuItem<tauri::Wry>>(items: &[T]) -> Vec<&dyn IsMenuItem<tauri::Wry>> {
+    items.iter().map(|i| i as &dyn IsMenuItem<tauri::Wry>).collect()
+}
+
+fn build_tray_menu(
+    app: &AppHandle,
+    settings: &AppSettings,
+) -> Result<(Menu<tauri::Wry>, TrayItems), Box<dyn std::error::Error>> {
+    let locale = settings.locale.clone();
+    let text = tray::TrayText::new(&locale);
+
+    // Item ids are the actions they run (see `Action::menu_id`). Check marks
+    // and the recording label are filled in by `sync_tray`.
+    let item = |text: &str, action: Action| MenuItem::with_id(app, action.menu_id(), text, true, None::<&str>);
+    let check = |text: &str, action: Action| CheckMenuItem::with_id(app, action.menu_id(), text, true, false, None::<&str>);
+    let items = TrayItems {
+        locale,
+        camera: check(text.show_camera, Action::ToggleVisibility)?,
+        locked: check(text.lock_drag, Action::ToggleLock)?,
+        keyboard: check(text.show_keys, Action::ToggleKeyboard)?,
+        click_through: check(text.click_through, Action::ToggleClickThrough)?,
+        always_on_top: check(text.always_on_top, Action::ToggleAlwaysOnTop)?,
+        recording: item(text.start_recording, Action::ToggleRecording)?,
+        shown: None,
+    };
+    let open_settings = item(text.open_settings, Action::OpenSettings)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let quit = item(text.quit, Action::Quit)?;
+
+    let shape_items = tray::SHAPES
+        .iter()
+        .map(|shape| {
+            let action = Action::SetShape { shape: shape.clone() };
+            let checked = settings.shape == *shape;
+            CheckMenuItem::with_id(app, action.menu_id(), text.shape_name(shape), true, checked, None::<&str>)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let shape_menu = Submenu::with_items(app, text.shape, true, &menu_refs(&shape_items))?;
+
+    let scale_items = tray::SCALES
+        .iter()
+        .map(|&scale| {
+            let checked = tray::is_scale(settings.scale, scale);
+            CheckMenuItem::with_id(
+                app,
+                Action::SetScale { scale }.menu_id(),
+                tray::scale_label(scale),
+                true,
+                checked,
+                None::<&str>,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let scale_menu = Submenu::with_items(app, text.size, true, &menu_refs(&scale_items))?;
+
+    let cameras = app.state::<AppState>().cameras.lock().map(|c| c.clone().unwrap_or_default()).unwrap_or_default();
+    let cameras = without_virtual_camera(app, cameras);
+    let camera_items = cameras
+        .iter()
+        .map(|camera| {
+            CheckMenuItem::with_id(
+                app,
+                Action::SelectCamera { id: camera.device_id.clone() }.menu_id(),
+                &camera.label,
+                true,
+                settings.selected_camera_id.as_deref() == Some(camera.device_id.as_str()),
+                None::<&str>,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let no_cameras = MenuItem::new(app, text.no_cameras, false, None::<&str>)?;
+    let camera_refs = if camera_items.is_empty() {
+        vec![&no_cameras as &dyn IsMenuItem<tauri::Wry>]
+    } else {
+        menu_refs(&camera_items)
+    };
+    let camera_menu = Submenu::with_items(app, text.camera, true, &camera_refs)?;
+
+    // Recordings moved or deleted since are left out.
+    let recent_items = read_recent_recordings(app)
+        .into_iter()
+        .filter(|path| std::path::Path::new(path).exists())
+        .map(|path| {
+            let open = item(text.open, Action::OpenRecording { path: path.clone() })?;
+            let reveal = item(text.show_in_folder, Action::RevealRecording { path: path.clone() })?;
+            Submenu::with_items(app, tray::recording_label(&path), true, &[&open, &reveal])
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let no_recordings = MenuItem::new(app, text.no_recordings, false, None::<&str>)?;
+    let recent_refs = if recent_items.is_empty() {
+        vec![&no_recordings as &dyn IsMenuItem<tauri::Wry>]
+    } else {
+        menu_refs(&recent_items)
+    };
+    let recent_menu = Submenu::with_items(app, text.recent_recordings, true, &recent_refs)?;
+
+    // One check item per profile. An unreadable list must not keep the app
+    // from starting; the submenu is just empty until it is fixed.
+    let profiles = read_profiles(app).unwrap_or_else(|err| {
+        eprintln!("Tray lists no profiles: {}", err);
+        settings::profiles::Profiles::default()
+    });
+    let profile_items = profiles
+        .profiles
+        .iter()
+        .map(|p| {
+            CheckMenuItem::with_id(
+                app,
+                Action::ActivateProfile { id: p.id.clone() }.menu_id(),
+                &p.name,
+                true,
+                profiles.active.as_deref() == Some(p.id.as_str()),
+                None::<&str>,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let profiles_menu = Submenu::with_items(app, text.profiles, true, &menu_refs(&profile_items))?;
+
+    let menu = Menu::with_items(
+        app,
+        &[
+            &items.camera,
+            &open_settings,
+            &items.locked,
+            &items.keyboard,
+            &items.click_through,
+            &items.always_on_top,
+            &shape_menu,
+            &scale_menu,
+            &camera_menu,
+            &items.recording,
+            &recent_menu,
+            &profiles_menu,
+            &separator,
+            &quit,
+        ],
+    )?;
+
+    Ok((menu, items))
+}
+
+/// Rebuild the tray menu (labels follow `locale`; the submenus follow the
+/// settings, the reported cameras, the recent recordings and the stored
+/// profile list).
+fn refresh_tray_menu(app: &AppHandle, settings: &AppSettings) {
+    let Some(tray) = app.tray_by_id("floaty-tray") else {
+        return;
+    };
+    if let Ok((menu, items)) = build_tray_menu(app, settings) {
+        let _ = tray.set_menu(Some(menu));
+        if let Ok(mut current) = app.state::<AppState>().tray.lock() {
+            *current = Some(items);
+        }
+        sync_tray(app, settings);
     }
-    None
 }
 
-fn update_tray_locale(app: &AppHandle, locale: &str) {
-    if let Ok(menu) = build_tray_menu(app, locale) {
+/// Bring the tray's check marks, recording item and icon in line with the
+/// settings, the camera window and the recording pipeline. Cheap when
+/// nothing changed, so it runs on every recording tick.
+fn sync_tray(app: &AppHandle, settings: &AppSettings) {
+    let state = app.state::<AppState>();
+    let camera_visible = app
+        .get_webview_window(MAIN_WINDOW_LABEL)
+        .and_then(|w| w.is_visible().ok())
+        .unwrap_or(false);
+    let ui = state.recording_ui.lock().map(|ui| ui.clone()).unwrap_or_default();
+    let active = state.recording_active.load(Ordering::SeqCst);
+
+    // Tray calls block on the main thread: update the record under the lock,
+    // make the calls after releasing it.
+    let (items, previous, shown) = {
+        let Ok(mut current) = state.tray.lock() else {
+            return;
+        };
+        let Some(items) = current.as_mut() else {
+            return;
+        };
+        let shown = TrayShown {
+            checks: [
+                camera_visible,
+                settings.locked,
+                settings.keyboard_display_enabled,
+                settings.click_through,
+                settings.always_on_top,
+            ],
+            recording: tray::Recording::new(&tray::TrayText::new(&items.locale), &ui.status, active, ui.elapsed),
+        };
+        if items.shown.as_ref() == Some(&shown) {
+            return;
+        }
+        let previous = items.shown.replace(shown.clone());
+        (items.clone(), previous, shown)
+    };
+
+    let checks = [&items.camera, &items.locked, &items.keyboard, &items.click_through, &items.always_on_top];
+    for (item, checked) in checks.into_iter().zip(shown.checks) {
+        let _ = item.set_checked(checked);
+    }
+    let _ = items.recording.set_text(&shown.recording.label);
+    let _ = items.recording.set_enabled(shown.recording.enabled);
+
+    let icon = shown.recording.icon;
+    if previous.map(|p| p.recording.icon) != Some(icon) {
         if let Some(tray) = app.tray_by_id("floaty-tray") {
-            let _ = tray.set_menu(Some(menu));
+            let bytes: &[u8] = match icon {
+                tray::Icon::Idle => include_bytes!("../icons/tray-icon.png"),
+                tray::Icon::Recording => include_bytes!("../icons/tray-icon-recording.png"),
+                tray::Icon::Paused => include_bytes!("../icons/tray-icon-paused.png"),
+            };
+            if let Ok(image) = tauri::image::Image::from_bytes(bytes) {
+                let _ = tray.set_icon(Some(image));
+            }
+            // The badges are coloured; only the plain icon follows the
+            // menu bar's appearance.
+            let _ = tray.set_icon_as_template(icon == tray::Icon::Idle);
         }
     }
 }
 
 fn setup_tray(app: &App) -> Result<(), Box<dyn std::error::Error>> {
+    // Older versions persisted the menu item ids; they are not settings.
+    if let Ok(store) = app.store(STORE_FILE) {
+        if store.delete("_tray_menu_ids") {
+            let _ = store.save();
+        }
+    }
     let settings = read_settings_from_store(&app.handle()).unwrap_or_default();
-    let menu = build_tray_menu(&app.handle(), &settings.locale)?;
+    let (menu, items) = build_tray_menu(&app.handle(), &settings)?;
 
     TrayIconBuilder::with_id("floaty-tray")
         .icon(tauri::image::Image::from_bytes(include_bytes!(
@@ -1041,47 +2644,24 @@ fn setup_tray(app: &App) -> Result<(), Box<dyn std::error::Error>> {
         .tooltip("Floaty McFloatFace")
         .menu(&menu)
         .on_menu_event(|app, event| {
-            let action = resolve_tray_action(app, event.id().as_ref());
-            match action {
-                Some("toggle_visibility") => {
-                    let _ = toggle_main_window_visibility(app.clone());
-                }
-                Some("open_settings") => {
-                    let _ = open_settings_window(app.clone());
-                }
-                Some("toggle_lock") => {
-                    let _ = toggle_lock_state(app);
-                    emit_hotkey(app, "toggle_lock");
-                }
-                Some("toggle_keyboard") => {
-                    let mut settings = read_settings_from_store(app).unwrap_or_default();
-                    settings.keyboard_display_enabled = !settings.keyboard_display_enabled;
-                    let enabled = settings.keyboard_display_enabled;
-                    let _ = toggle_keyboard_window(app.clone(), enabled);
-                    let _ = save_app_settings(app.clone(), settings);
-                }
-                Some("toggle_recording") => {
-                    // The recording control bar must be visible to act on the
-                    // hotkey action; ensure it's open, then fire the toggle.
-                    let mut settings = read_settings_from_store(app).unwrap_or_default();
-                    if !settings.recording_enabled {
-                        settings.recording_enabled = true;
-                        let _ = toggle_recording_window(app.clone(), true);
-                        let _ = save_app_settings(app.clone(), settings);
-                    }
-                    // State query is informational here; the frontend owns the
-                    // start/stop decision via the hotkey-triggered event.
-                    if let Some(state) = app.try_state::<AppState>() {
-                        let _was = state.recording_active.load(Ordering::SeqCst);
-                    }
-                    emit_hotkey(app, "toggle_recording");
+            if let Some(action) = Action::from_menu_id(event.id().as_ref()) {
+                if let Err(err) = dispatch(app, action) {
+                    eprintln!("Tray action failed: {}", err);
                 }
-                Some("quit") => app.exit(0),
-                _ => {}
+            }
+            // A check item ticks itself when clicked; rebuild to show
+            // whatever the action left in place, even if it failed or
+            // changed nothing.
+            if let Ok(settings) = read_settings_from_store(app) {
+                refresh_tray_menu(app, &settings);
             }
         })
         .build(app)?;
 
+    if let Ok(mut current) = app.state::<AppState>().tray.lock() {
+        *current = Some(items);
+    }
+    sync_tray(&app.handle(), &settings);
     Ok(())
 }
 
@@ -1139,19 +2719,118 @@ fn setup_windows(app: &App) -> Result<(), String> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let context = tauri::generate_context!();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = match cli::parse(&args) {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    match &command {
+        Some(cli::CliCommand::Help) => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Some(cli::CliCommand::SettingsGet(key)) => {
+            // Same location the store plugin resolves (the app data dir).
+            let store = dirs::data_dir()
+                .unwrap_or_default()
+                .join(&context.config().identifier)
+                .join(STORE_FILE);
+            match cli::settings_get(&store, SETTINGS_KEY, key.as_deref()) {
+                Ok(out) => println!("{}", out),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        _ => {}
+    }
+
     tauri::Builder::default()
+        // Registered first: a second launch hands its arguments to this
+        // instance and exits before anything else is set up.
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            // Links are forwarded to the deep-link plugin's handler.
+            if argv.iter().any(|arg| deeplink::is_link(arg)) {
+                return;
+            }
+            match cli::parse(argv.get(1..).unwrap_or_default()) {
+                Ok(Some(command)) => {
+                    if let Err(err) = run_cli_command(app, command) {
+                        eprintln!("floaty: {}", err);
+                    }
+                }
+                // A plain relaunch (e.g. from the dock): bring the camera back.
+                Ok(None) => {
+                    if let Some(main) = app.get_webview_window(MAIN_WINDOW_LABEL) {
+                        let _ = main.show();
+                        let _ = main.set_focus();
+                    }
+                }
+                Err(err) => eprintln!("floaty: {}", err),
+            }
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::default().build())
-        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| {
+                    hotkey_event(app, shortcut, event.state() == ShortcutState::Pressed);
+                })
+                .build(),
+        )
         .plugin(tauri_plugin_dialog::init())
         .manage(AppState::default())
-        .setup(|app| {
+        // Stream drafts to the editor's <video> straight from disk. Served off
+        // the main thread: a range read on a slow disk must not block the UI.
+        .register_asynchronous_uri_scheme_protocol(recording::stream::SCHEME, |ctx, request, responder| {
+            let app = ctx.app_handle().clone();
+            std::thread::spawn(move || {
+                responder.respond(recording::stream::serve_draft(&drafts_dir(&app), &request));
+            });
+        })
+        .setup(move |app| {
             setup_windows(app).map_err(|err| -> Box<dyn std::error::Error> { err.into() })?;
             setup_tray(app)?;
             
             // Start keyboard listener
             keyboard::start_keyboard_listener(app.handle().clone());
 
+            if let Err(e) = watch_settings_file(app.handle()) {
+                eprintln!("Failed to watch settings file: {}", e);
+            }
+            if let Err(e) = watch_cameras(app.handle()) {
+                eprintln!("Failed to watch for cameras: {}", e);
+            }
+
+            // Linux and Windows only know the scheme once it is registered
+            // at runtime; bundles register it on install, dev builds do not.
+            #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+            if let Err(e) = app.deep_link().register_all() {
+                eprintln!("Failed to register {}:// links: {}", deeplink::SCHEME, e);
+            }
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                for url in event.urls() {
+                    open_deep_link(&handle, url.as_str());
+                }
+            });
+
+            track_recording_state(app.handle());
+            forward_control_events(app.handle());
+            if let Ok(settings) = read_settings_from_store(app.handle()) {
+                sync_control_api(app.handle(), &settings);
+                sync_hotkeys(app.handle(), &settings);
+                sync_virtual_camera(app.handle(), &settings);
+            }
+
             // Auto-open keyboard window on startup
             if let Err(e) = toggle_keyboard_window(app.handle().clone(), true) {
                 eprintln!("Failed to open keyboard window: {}", e);
@@ -1175,13 +2854,31 @@ pub fn run() {
                 }
             }
 
+            // Launched as `floaty <command>` with no instance running: this
+            // process is the app now, so run the command itself.
+            if let Some(command) = command.filter(|c| !c.is_local()) {
+                if let Err(e) = run_cli_command(app.handle(), command) {
+                    eprintln!("floaty: {}", e);
+                }
+            }
+            // Likewise for a launch through a link.
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                for url in urls {
+                    open_deep_link(app.handle(), url.as_str());
+                }
+            }
+
             
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             list_cameras,
+            report_cameras,
+            resolve_camera,
             get_app_settings,
+            get_app_settings_revisioned,
             save_app_settings,
+            patch_app_settings,
             apply_window_shape,
             set_always_on_top,
             set_click_through,
@@ -1190,6 +2887,24 @@ pub fn run() {
             start_drag_main_window,
             toggle_keyboard_window,
             open_camera_privacy_settings,
+            // Settings profiles
+            list_settings_profiles,
+            create_settings_profile,
+            duplicate_settings_profile,
+            rename_settings_profile,
+            delete_settings_profile,
+            activate_settings_profile,
+            activate_next_settings_profile,
+            run_action,
+            get_hotkey_errors,
+            export_settings_file,
+            preview_settings_import,
+            apply_settings_import,
+            cancel_settings_import,
+            get_control_api_info,
+            get_virtual_camera_info,
+            push_virtual_camera_frame,
+            regenerate_control_api_token,
             // Screen recording
             toggle_recording_window,
             start_region_select,
@@ -1202,14 +2917,22 @@ pub fn run() {
             get_recording_state,
             save_recording,
             // Recording editor
+            check_drafts_space,
+            move_drafts_dir,
             save_recording_draft,
-            get_editor_draft_path,
+            get_editor_draft_id,
             read_recording_file,
             delete_recording_draft,
             save_recording_meta,
             read_recording_meta,
+            read_recording_edits,
+            save_recording_edits,
+            undo_recording_edits,
+            redo_recording_edits,
+            export_project_bundle,
+            import_project_bundle,
             open_editor_window
         ])
-        .run(tauri::generate_context!())
+        .run(context)
         .expect("error while running tauri application");
 }
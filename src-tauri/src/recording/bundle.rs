@@ -0,0 +1,377 @@
+//! `.floaty` project bundles: a draft packed into one zip for archiving or
+//! handing to a teammate, who can open it and re-export.
+//!
+//! Layout (entries are looked up by these exact names; nothing is ever
+//! extracted by its in-archive path, so a crafted bundle can't write outside
+//! the drafts directory):
+//!
+//! ```text
+//! manifest.json            schema version + which optional parts exist
+//! media.mp4 | media.webm   the untouched source recording (stored, not deflated)
+//! meta.json                the `draft-<id>.json` sidecar, if any
+//! edits.json               the current edit decision list, if any
+//! thumbnails/poster.png    preview frame, if any
+//! ```
+//!
+//! `meta.json` is opaque to Rust, same as the sidecar. `edits.json` is an
+//! [`EditDecisionList`] (no undo history — that stays with the local draft).
+
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use super::drafts;
+use super::edits::{self, EditDecisionList};
+
+/// Identifies a zip as a Floaty project (guards against opening a random zip).
+pub const FORMAT: &str = "floaty-project";
+
+/// Current bundle schema. Bump when the layout or manifest changes and teach
+/// [`upgrade_manifest`] how to read the previous version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// File extension, without the dot.
+pub const EXTENSION: &str = "floaty";
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const META_ENTRY: &str = "meta.json";
+const EDITS_ENTRY: &str = "edits.json";
+const POSTER_ENTRY: &str = "thumbnails/poster.png";
+
+/// Largest JSON entry read from a bundle. Real ones are kilobytes (the
+/// metadata sidecar holds the cursor track); the bound keeps a crafted
+/// bundle from decompressing gigabytes into memory.
+const MAX_JSON_ENTRY: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub format: String,
+    pub schema_version: u32,
+    /// Version of the app that wrote the bundle, for diagnostics only.
+    pub app_version: String,
+    /// Unix seconds.
+    pub created_at: i64,
+    /// Container of the media entry: `mp4` or `webm`.
+    pub media_ext: String,
+    pub has_meta: bool,
+    pub has_edits: bool,
+    pub thumbnails: Vec<String>,
+}
+
+/// Everything that goes into a bundle, gathered by the export command.
+pub struct BundleParts<'a> {
+    pub media: &'a Path,
+    pub meta: Option<serde_json::Value>,
+    pub edits: Option<EditDecisionList>,
+    pub poster_png: Option<&'a [u8]>,
+    pub app_version: &'a str,
+    pub created_at: i64,
+}
+
+/// What an import produced: a fresh draft in the drafts directory.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedDraft {
+    pub draft_id: String,
+    /// Whether the bundle's edit list was restored alongside the draft.
+    pub has_edits: bool,
+}
+
+fn media_entry(ext: &str) -> String {
+    format!("media.{}", ext)
+}
+
+/// Write a bundle to `out`. The media file is streamed, never buffered.
+pub fn write_bundle<W: Write + Seek>(out: W, parts: BundleParts) -> Result<(), String> {
+    let media_ext = match parts.media.extension().and_then(|e| e.to_str()) {
+        Some("webm") => "webm",
+        _ => "mp4",
+    };
+    let manifest = Manifest {
+        format: FORMAT.to_string(),
+        schema_version: SCHEMA_VERSION,
+        app_version: parts.app_version.to_string(),
+        created_at: parts.created_at,
+        media_ext: media_ext.to_string(),
+        has_meta: parts.meta.is_some(),
+        has_edits: parts.edits.is_some(),
+        thumbnails: parts
+            .poster_png
+            .map(|_| vec![POSTER_ENTRY.to_string()])
+            .unwrap_or_default(),
+    };
+
+    let deflated = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    // Recordings are already compressed; deflating them only burns CPU.
+    // Large-file (zip64) headers so multi-gigabyte drafts fit.
+    let stored = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Stored)
+        .large_file(true);
+
+    let mut zip = ZipWriter::new(out);
+    let json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    zip.start_file(MANIFEST_ENTRY, deflated).map_err(|e| e.to_string())?;
+    zip.write_all(&json).map_err(|e| e.to_string())?;
+
+    zip.start_file(media_entry(media_ext), stored).map_err(|e| e.to_string())?;
+    let mut media = std::fs::File::open(parts.media).map_err(|e| e.to_string())?;
+    std::io::copy(&mut media, &mut zip).map_err(|e| e.to_string())?;
+
+    if let Some(meta) = &parts.meta {
+        let json = serde_json::to_vec_pretty(meta).map_err(|e| e.to_string())?;
+        zip.start_file(META_ENTRY, deflated).map_err(|e| e.to_string())?;
+        zip.write_all(&json).map_err(|e| e.to_string())?;
+    }
+    if let Some(edl) = &parts.edits {
+        let json = serde_json::to_vec_pretty(edl).map_err(|e| e.to_string())?;
+        zip.start_file(EDITS_ENTRY, deflated).map_err(|e| e.to_string())?;
+        zip.write_all(&json).map_err(|e| e.to_string())?;
+    }
+
+    if let Some(png) = parts.poster_png {
+        zip.start_file(POSTER_ENTRY, stored).map_err(|e| e.to_string())?;
+        zip.write_all(png).map_err(|e| e.to_string())?;
+    }
+
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Read a manifest of any supported schema version and bring it up to
+/// [`SCHEMA_VERSION`]. Bundles from a newer app are refused rather than
+/// half-understood.
+pub fn upgrade_manifest(value: serde_json::Value) -> Result<Manifest, String> {
+    if value.get("format").and_then(|v| v.as_str()) != Some(FORMAT) {
+        return Err("not a Floaty project bundle".to_string());
+    }
+    let version = value
+        .get("schemaVersion")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "bundle manifest has no schema version".to_string())?;
+    if version > SCHEMA_VERSION as u64 {
+        return Err(format!(
+            "bundle schema v{} is newer than this app supports (v{}); update Floaty to open it",
+            version, SCHEMA_VERSION
+        ));
+    }
+    // Only v1 exists so far; older versions get migrated here step by step.
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+/// Read and parse the JSON entry `name`, refusing entries over `limit`
+/// bytes (checked while reading, not from the header, which can lie).
+fn read_json_entry<R: Read + Seek>(
+    zip: &mut ZipArchive<R>,
+    name: &str,
+    limit: u64,
+) -> Result<Option<serde_json::Value>, String> {
+    let entry = match zip.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    let mut body = String::new();
+    entry
+        .take(limit + 1)
+        .read_to_string(&mut body)
+        .map_err(|e| e.to_string())?;
+    if body.len() as u64 > limit {
+        return Err(format!("{} is larger than {} bytes", name, limit));
+    }
+    serde_json::from_str(&body).map(Some).map_err(|e| e.to_string())
+}
+
+/// Unpack `bundle` into `drafts_dir` as a new draft with id `new_id`.
+/// Writes the video and, when present, the metadata sidecar and the edit
+/// list (as a fresh document with no history), so the editor restores the
+/// project like any other draft. An edit list that no longer parses is
+/// dropped rather than failing the import. The media is extracted to a
+/// hidden temp name first and renamed into place, so a failed import never
+/// leaves a half-written draft behind.
+pub fn import_bundle(bundle: &Path, drafts_dir: &Path, new_id: &str) -> Result<ImportedDraft, String> {
+    drafts::validate_id(new_id)?;
+    let root = drafts::ensure_dir(drafts_dir)?;
+
+    let file = std::fs::File::open(bundle).map_err(|e| e.to_string())?;
+    let mut zip = ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let manifest = upgrade_manifest(
+        read_json_entry(&mut zip, MANIFEST_ENTRY, MAX_JSON_ENTRY)?.ok_or_else(|| "bundle has no manifest".to_string())?,
+    )?;
+    let ext = match manifest.media_ext.as_str() {
+        "mp4" => "mp4",
+        "webm" => "webm",
+        other => return Err(format!("unsupported media container: {}", other)),
+    };
+
+    let meta = read_json_entry(&mut zip, META_ENTRY, MAX_JSON_ENTRY)?;
+    let edl = read_json_entry(&mut zip, EDITS_ENTRY, MAX_JSON_ENTRY)?.and_then(|v| edits::from_value(v).ok());
+
+    let target: PathBuf = root.join(drafts::draft_file_name(new_id, ext));
+    let partial = root.join(format!(".{}.partial", drafts::draft_file_name(new_id, ext)));
+    {
+        let mut media = zip
+            .by_name(&media_entry(ext))
+            .map_err(|_| "bundle has no media".to_string())?;
+        drafts::ensure_free_space(&root, media.size())?;
+        let result = std::fs::File::create(&partial)
+            .and_then(|mut out| std::io::copy(&mut media, &mut out).and_then(|_| out.sync_all()));
+        if let Err(e) = result {
+            let _ = std::fs::remove_file(&partial);
+            return Err(e.to_string());
+        }
+    }
+    std::fs::rename(&partial, &target).map_err(|e| e.to_string())?;
+
+    if let Some(meta) = &meta {
+        let sidecar = drafts::resolve_sidecar(&root, new_id)?;
+        let json = serde_json::to_string(meta).map_err(|e| e.to_string())?;
+        super::store::write_recording_meta(&sidecar, &json)?;
+    }
+
+    if let Some(edl) = &edl {
+        let path = drafts::resolve_edits(&root, new_id)?;
+        edits::store(&path, &edits::EditDocument::new(edl.clone()))?;
+    }
+
+    Ok(ImportedDraft {
+        draft_id: new_id.to_string(),
+        has_edits: edl.is_some(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recording::fresh_dir;
+
+    fn read_manifest(bundle: &Path) -> Manifest {
+        let mut zip = ZipArchive::new(std::fs::File::open(bundle).unwrap()).unwrap();
+        upgrade_manifest(read_json_entry(&mut zip, MANIFEST_ENTRY, MAX_JSON_ENTRY).unwrap().unwrap()).unwrap()
+    }
+
+    fn sample_edits() -> EditDecisionList {
+        edits::from_value(serde_json::json!({
+            "trim": [1.0, 2.0],
+            "zoomEnabled": true,
+            "zoomFactor": 2.0,
+            "aspect": "square",
+            "targetHeight": 720,
+            "format": "webm"
+        }))
+        .unwrap()
+    }
+
+    fn write_sample(dir: &Path, edits: Option<EditDecisionList>) -> PathBuf {
+        let media = dir.join("draft-1.webm");
+        std::fs::write(&media, b"webm-bytes").unwrap();
+        let bundle = dir.join("clip.floaty");
+        let out = std::fs::File::create(&bundle).unwrap();
+        write_bundle(
+            out,
+            BundleParts {
+                media: &media,
+                meta: Some(serde_json::json!({ "captureWidth": 1920, "cursor": [] })),
+                edits,
+                poster_png: Some(b"png"),
+                app_version: "0.3.1",
+                created_at: 1_784_571_055,
+            },
+        )
+        .unwrap();
+        bundle
+    }
+
+    #[test]
+    fn bundle_roundtrip() {
+        let dir = fresh_dir("floaty-bundle-test-roundtrip");
+        let bundle = write_sample(&dir, Some(sample_edits()));
+
+        let manifest = read_manifest(&bundle);
+        assert_eq!(manifest.schema_version, SCHEMA_VERSION);
+        assert_eq!(manifest.media_ext, "webm");
+        assert!(manifest.has_meta && manifest.has_edits);
+        assert_eq!(manifest.thumbnails, vec![POSTER_ENTRY.to_string()]);
+
+        let drafts_dir = dir.join("drafts");
+        let imported = import_bundle(&bundle, &drafts_dir, "42").unwrap();
+        assert_eq!(imported.draft_id, "42");
+        assert!(imported.has_edits);
+        let doc = edits::load(&drafts_dir.join("draft-42.edits.json")).unwrap().unwrap();
+        assert_eq!(doc.current, sample_edits());
+        assert!(doc.undo.is_empty());
+        assert_eq!(std::fs::read(drafts_dir.join("draft-42.webm")).unwrap(), b"webm-bytes");
+        let meta: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(drafts_dir.join("draft-42.json")).unwrap()).unwrap();
+        assert_eq!(meta["captureWidth"], 1920);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn bundle_without_edits_imports() {
+        let dir = fresh_dir("floaty-bundle-test-no-edits");
+        let bundle = write_sample(&dir, None);
+        let imported = import_bundle(&bundle, &dir.join("drafts"), "7").unwrap();
+        assert!(!imported.has_edits);
+        assert!(!dir.join("drafts").join("draft-7.edits.json").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn upgrade_manifest_rejects_foreign_and_newer() {
+        assert!(upgrade_manifest(serde_json::json!({ "format": "other", "schemaVersion": 1 })).is_err());
+        assert!(upgrade_manifest(serde_json::json!({ "format": FORMAT })).is_err());
+        let err = upgrade_manifest(serde_json::json!({ "format": FORMAT, "schemaVersion": SCHEMA_VERSION + 1 }))
+            .unwrap_err();
+        assert!(err.contains("newer"));
+    }
+
+    #[test]
+    fn oversized_json_entries_are_rejected() {
+        let dir = fresh_dir("floaty-bundle-test-oversized");
+        let bundle = dir.join("big.floaty");
+        let mut zip = ZipWriter::new(std::fs::File::create(&bundle).unwrap());
+        zip.start_file(META_ENTRY, SimpleFileOptions::default()).unwrap();
+        zip.write_all(format!("\"{}\"", "x".repeat(100)).as_bytes()).unwrap();
+        zip.finish().unwrap();
+
+        let mut zip = ZipArchive::new(std::fs::File::open(&bundle).unwrap()).unwrap();
+        assert!(read_json_entry(&mut zip, META_ENTRY, 102).unwrap().is_some());
+        let err = read_json_entry(&mut zip, META_ENTRY, 101).unwrap_err();
+        assert!(err.contains("larger than 101 bytes"), "{}", err);
+        assert_eq!(read_json_entry(&mut zip, EDITS_ENTRY, 101).unwrap(), None);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn import_ignores_entry_paths() {
+        // A hostile bundle with a traversal entry: import only ever reads the
+        // fixed entry names, so the extra entry is never written anywhere.
+        let dir = fresh_dir("floaty-bundle-test-slip");
+        let bundle = dir.join("evil.floaty");
+        let mut zip = ZipWriter::new(std::fs::File::create(&bundle).unwrap());
+        let opts = SimpleFileOptions::default();
+        let manifest = serde_json::json!({
+            "format": FORMAT, "schemaVersion": 1, "appVersion": "x", "createdAt": 0,
+            "mediaExt": "mp4", "hasMeta": false, "hasEdits": false, "thumbnails": []
+        });
+        zip.start_file(MANIFEST_ENTRY, opts).unwrap();
+        zip.write_all(manifest.to_string().as_bytes()).unwrap();
+        zip.start_file("../../escaped.txt", opts).unwrap();
+        zip.write_all(b"nope").unwrap();
+        zip.start_file("media.mp4", opts).unwrap();
+        zip.write_all(b"mp4").unwrap();
+        zip.finish().unwrap();
+
+        let drafts_dir = dir.join("drafts");
+        import_bundle(&bundle, &drafts_dir, "9").unwrap();
+        assert!(!dir.join("escaped.txt").exists());
+        assert!(!std::env::temp_dir().join("escaped.txt").exists());
+        assert!(drafts_dir.join("draft-9.mp4").exists());
+        assert!(import_bundle(&bundle, &drafts_dir, "../9").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,474 @@
+//! Draft addressing, path confinement and the drafts directory itself.
+//!
+//! The frontend refers to drafts by an opaque id (the `<millis>` in
+//! `draft-<millis>.mp4`), never by absolute path. Every command that touches
+//! a draft or its sidecar resolves the id here, so there is exactly one place
+//! that decides what "inside the drafts directory" means:
+//!   * ids are plain digit strings — no separators, no `..`;
+//!   * the drafts directory itself must not be a symlink (a user-chosen
+//!     location may be shared with other accounts);
+//!   * the resolved file must not be a symlink and, once canonicalized, must
+//!     sit directly inside the canonical drafts directory.
+//!
+//! It also owns where that directory lives (per-user app data by default,
+//! or a user-chosen volume), moving drafts between locations, and the
+//! free-space check run before a recording starts.
+
+use serde::Serialize;
+use std::path::{Component, Path, PathBuf};
+
+/// Containers a draft may be recorded in, in lookup order.
+const DRAFT_EXTENSIONS: [&str; 2] = ["mp4", "webm"];
+
+/// Longest id accepted. Millisecond timestamps are 13 digits today; the
+/// bound only exists so garbage input is rejected before touching the disk.
+const MAX_ID_LEN: usize = 20;
+
+/// Check that `id` is a well-formed draft id (ASCII digits only).
+pub fn validate_id(id: &str) -> Result<(), String> {
+    if id.is_empty() || id.len() > MAX_ID_LEN || !id.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(format!("invalid draft id: {:?}", id));
+    }
+    Ok(())
+}
+
+/// Minimum free space on the drafts volume before a recording may start.
+/// Screen captures run at several MB/s; 2 GiB covers a ~10 minute take.
+pub const MIN_FREE_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Resolve the drafts directory: the user-configured location when set,
+/// otherwise `default_dir` (the per-user app data dir, supplied by the
+/// caller since resolving it needs an `AppHandle`). Empty strings fall back
+/// to the default, mirroring `store::resolve_output_dir`.
+pub fn resolve_dir(configured: Option<&str>, default_dir: PathBuf) -> PathBuf {
+    configured
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or(default_dir)
+}
+
+/// File name for a new draft with the given id and container.
+pub fn draft_file_name(id: &str, ext: &str) -> String {
+    format!("draft-{}.{}", id, ext)
+}
+
+/// Extract the id from a draft file name (`draft-<id>.<ext>`), if it is one.
+pub fn id_from_file_name(name: &str) -> Option<&str> {
+    let rest = name.strip_prefix("draft-")?;
+    let (id, ext) = rest.rsplit_once('.')?;
+    (DRAFT_EXTENSIONS.contains(&ext) && validate_id(id).is_ok()).then_some(id)
+}
+
+/// Whether `name` is a file the drafts directory owns: a draft video, its
+/// `draft-<id>.json` sidecar or its `draft-<id>.edits.json` edit list.
+/// Anything else in the directory is left alone.
+fn is_draft_artifact(name: &str) -> bool {
+    if id_from_file_name(name).is_some() {
+        return true;
+    }
+    let Some(rest) = name.strip_prefix("draft-") else {
+        return false;
+    };
+    rest.strip_suffix(".edits.json")
+        .or_else(|| rest.strip_suffix(".json"))
+        .is_some_and(|id| validate_id(id).is_ok())
+}
+
+/// Whether `name` may be pruned: a draft artifact, or what an interrupted
+/// write of one leaves behind (`.<name>.partial` from [`migrate`],
+/// `<name>.tmp` from `edits::store`).
+fn is_prunable(name: &str) -> bool {
+    let leftover = name
+        .strip_suffix(".tmp")
+        .or_else(|| name.strip_prefix('.').and_then(|n| n.strip_suffix(".partial")));
+    is_draft_artifact(name) || leftover.is_some_and(is_draft_artifact)
+}
+
+/// Delete drafts and their leftovers last modified before `cutoff`, so
+/// abandoned recordings don't pile up. The directory may be any folder the
+/// user picked: files the drafts directory does not own, symlinks and
+/// subdirectories are never touched.
+pub fn prune(dir: &Path, cutoff: std::time::SystemTime) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if !entry.file_name().to_str().is_some_and(is_prunable) {
+            continue;
+        }
+        let Ok(meta) = std::fs::symlink_metadata(entry.path()) else {
+            continue;
+        };
+        if meta.is_file() && meta.modified().is_ok_and(|m| m < cutoff) {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Create the drafts directory if needed and return its canonical path.
+/// Refuses to use a directory that is (or was swapped for) a symlink.
+pub fn ensure_dir(dir: &Path) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    canonical_dir(dir)
+}
+
+fn canonical_dir(dir: &Path) -> Result<PathBuf, String> {
+    let meta = std::fs::symlink_metadata(dir).map_err(|e| e.to_string())?;
+    if meta.file_type().is_symlink() || !meta.is_dir() {
+        return Err("drafts directory is not a plain directory".to_string());
+    }
+    dir.canonicalize().map_err(|e| e.to_string())
+}
+
+/// Confine `candidate` to `dir`. Rejects `..` components, symlinks and
+/// anything that canonicalizes outside `dir` or into a subdirectory of it.
+///
+/// `candidate` may not exist yet (e.g. a sidecar about to be written); in
+/// that case its parent is canonicalized and checked instead.
+pub fn confine(dir: &Path, candidate: &Path) -> Result<PathBuf, String> {
+    if candidate
+        .components()
+        .any(|c| matches!(c, Component::ParentDir | Component::CurDir))
+    {
+        return Err("path traversal is not allowed".to_string());
+    }
+    let root = canonical_dir(dir)?;
+    let file_name = candidate
+        .file_name()
+        .ok_or_else(|| "path has no file name".to_string())?;
+
+    let resolved = match std::fs::symlink_metadata(candidate) {
+        Ok(meta) => {
+            if meta.file_type().is_symlink() {
+                return Err("symlinked drafts are not allowed".to_string());
+            }
+            if !meta.is_file() {
+                return Err("draft is not a regular file".to_string());
+            }
+            candidate.canonicalize().map_err(|e| e.to_string())?
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let parent = candidate
+                .parent()
+                .ok_or_else(|| "path has no parent".to_string())?;
+            parent
+                .canonicalize()
+                .map_err(|e| e.to_string())?
+                .join(file_name)
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+
+    if resolved.parent() != Some(root.as_path()) {
+        return Err("path is outside the drafts directory".to_string());
+    }
+    Ok(resolved)
+}
+
+/// Resolve a draft id to its video file inside `dir`.
+pub fn resolve(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    validate_id(id)?;
+    for ext in DRAFT_EXTENSIONS {
+        let candidate = dir.join(draft_file_name(id, ext));
+        if std::fs::symlink_metadata(&candidate).is_ok() {
+            return confine(dir, &candidate);
+        }
+    }
+    Err(format!("draft {} not found", id))
+}
+
+/// Resolve the metadata sidecar (`draft-<id>.json`) for a draft id. The
+/// sidecar does not need to exist yet.
+pub fn resolve_sidecar(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    validate_id(id)?;
+    let video = dir.join(draft_file_name(id, DRAFT_EXTENSIONS[0]));
+    confine(dir, &super::store::meta_sidecar_path(&video))
+}
+
+/// Fail unless the volume holding `dir` has at least `min_bytes` free.
+/// Returns the available byte count on success.
+pub fn ensure_free_space(dir: &Path, min_bytes: u64) -> Result<u64, String> {
+    let available = fs4::available_space(dir).map_err(|e| e.to_string())?;
+    if available < min_bytes {
+        return Err(format!(
+            "not enough free space for recording drafts in {}: {} MiB available, {} MiB required",
+            dir.display(),
+            available / (1024 * 1024),
+            min_bytes / (1024 * 1024)
+        ));
+    }
+    Ok(available)
+}
+
+/// Outcome of [`migrate`], returned to the settings window.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    /// Drafts and sidecars moved to the new directory.
+    pub moved: usize,
+    /// Files left behind because a file of the same name already exists at
+    /// the destination. Never overwritten.
+    pub skipped: Vec<String>,
+}
+
+/// Move every draft and sidecar from `from` into `to`.
+///
+/// Same-volume moves are a rename. Cross-volume moves copy to a hidden
+/// `.partial` file, fsync, rename into place and only then delete the
+/// source, so an interrupted move never leaves a truncated draft under its
+/// real name. Symlinks and unrelated files in `from` are ignored.
+pub fn migrate(from: &Path, to: &Path) -> Result<MigrationReport, String> {
+    let to_root = ensure_dir(to)?;
+    let mut report = MigrationReport::default();
+    if std::fs::symlink_metadata(from).is_err() {
+        return Ok(report);
+    }
+    let from_root = canonical_dir(from)?;
+    if from_root == to_root {
+        return Ok(report);
+    }
+
+    let entries = std::fs::read_dir(&from_root).map_err(|e| e.to_string())?;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        if !is_draft_artifact(name) {
+            continue;
+        }
+        let Ok(meta) = std::fs::symlink_metadata(entry.path()) else {
+            continue;
+        };
+        if !meta.is_file() {
+            continue;
+        }
+        let dest = to_root.join(name);
+        if std::fs::symlink_metadata(&dest).is_ok() {
+            report.skipped.push(name.to_string());
+            continue;
+        }
+        move_file(&entry.path(), &dest, meta.len()).map_err(|e| {
+            format!("moved {} file(s), then failed on {}: {}", report.moved, name, e)
+        })?;
+        report.moved += 1;
+    }
+    Ok(report)
+}
+
+fn move_file(src: &Path, dest: &Path, len: u64) -> Result<(), String> {
+    if std::fs::rename(src, dest).is_ok() {
+        return Ok(());
+    }
+    let dir = dest.parent().ok_or_else(|| "destination has no parent".to_string())?;
+    ensure_free_space(dir, len)?;
+    let name = dest
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| "destination has no file name".to_string())?;
+    let partial = dir.join(format!(".{}.partial", name));
+    let copied = std::fs::copy(src, &partial)
+        .and_then(|_| std::fs::File::open(&partial)?.sync_all())
+        .and_then(|_| std::fs::rename(&partial, dest));
+    if let Err(e) = copied {
+        let _ = std::fs::remove_file(&partial);
+        return Err(e.to_string());
+    }
+    std::fs::remove_file(src).map_err(|e| e.to_string())
+}
+
+/// Resolve the edit decision list (`draft-<id>.edits.json`) for a draft
+/// id. The file does not need to exist yet.
+pub fn resolve_edits(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    validate_id(id)?;
+    let video = dir.join(draft_file_name(id, DRAFT_EXTENSIONS[0]));
+    confine(dir, &super::store::edits_sidecar_path(&video))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recording::fresh_dir;
+
+    #[test]
+    fn validate_id_accepts_only_digits() {
+        assert!(validate_id("1712345678901").is_ok());
+        assert!(validate_id("").is_err());
+        assert!(validate_id("../1").is_err());
+        assert!(validate_id("12/34").is_err());
+        assert!(validate_id("1.mp4").is_err());
+        assert!(validate_id(&"9".repeat(MAX_ID_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn id_from_file_name_roundtrips() {
+        assert_eq!(id_from_file_name(&draft_file_name("42", "webm")), Some("42"));
+        assert_eq!(id_from_file_name("draft-42.json"), None);
+        assert_eq!(id_from_file_name("draft-x.mp4"), None);
+        assert_eq!(id_from_file_name("other-42.mp4"), None);
+    }
+
+    #[test]
+    fn resolve_finds_either_container() {
+        let dir = fresh_dir("floaty-drafts-test-resolve");
+        std::fs::write(dir.join("draft-1.webm"), b"x").unwrap();
+        std::fs::write(dir.join("draft-2.mp4"), b"x").unwrap();
+        let canon = dir.canonicalize().unwrap();
+        assert_eq!(resolve(&dir, "1").unwrap(), canon.join("draft-1.webm"));
+        assert_eq!(resolve(&dir, "2").unwrap(), canon.join("draft-2.mp4"));
+        assert!(resolve(&dir, "3").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn confine_rejects_traversal() {
+        let dir = fresh_dir("floaty-drafts-test-traversal");
+        std::fs::write(dir.join("draft-1.mp4"), b"x").unwrap();
+        assert!(confine(&dir, &dir.join("..").join("draft-1.mp4")).is_err());
+        assert!(confine(&dir, &dir.join("sub").join("..").join("draft-1.mp4")).is_err());
+        assert!(confine(&dir, &std::env::temp_dir().join("draft-1.mp4")).is_err());
+        assert!(confine(&dir, &dir.join("draft-1.mp4")).is_ok());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn confine_rejects_subdirectories_and_non_files() {
+        let dir = fresh_dir("floaty-drafts-test-subdir");
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("nested").join("draft-1.mp4"), b"x").unwrap();
+        assert!(confine(&dir, &dir.join("nested").join("draft-1.mp4")).is_err());
+        assert!(confine(&dir, &dir.join("nested")).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn resolve_sidecar_allows_missing_file() {
+        let dir = fresh_dir("floaty-drafts-test-sidecar");
+        let canon = dir.canonicalize().unwrap();
+        assert_eq!(resolve_sidecar(&dir, "7").unwrap(), canon.join("draft-7.json"));
+        assert!(resolve_sidecar(&dir, "..").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn confine_rejects_symlinked_file() {
+        let dir = fresh_dir("floaty-drafts-test-symlink-file");
+        let outside = fresh_dir("floaty-drafts-test-symlink-target");
+        std::fs::write(outside.join("secret"), b"x").unwrap();
+        std::os::unix::fs::symlink(outside.join("secret"), dir.join("draft-1.mp4")).unwrap();
+        std::os::unix::fs::symlink(outside.join("missing"), dir.join("draft-2.json")).unwrap();
+        assert!(resolve(&dir, "1").is_err());
+        // A dangling symlink must not be written through either.
+        assert!(resolve_sidecar(&dir, "2").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::remove_dir_all(&outside);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn ensure_dir_rejects_symlinked_directory() {
+        let target = fresh_dir("floaty-drafts-test-symlink-dir-target");
+        let link = std::env::temp_dir().join("floaty-drafts-test-symlink-dir");
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        assert!(ensure_dir(&link).is_err());
+        assert!(resolve(&link, "1").is_err());
+        let _ = std::fs::remove_file(&link);
+        let _ = std::fs::remove_dir_all(&target);
+    }
+
+    #[test]
+    fn resolve_dir_prefers_configured() {
+        let default = PathBuf::from("/data/floaty/drafts");
+        assert_eq!(resolve_dir(Some("/mnt/big"), default.clone()), PathBuf::from("/mnt/big"));
+        assert_eq!(resolve_dir(Some("  "), default.clone()), default);
+        assert_eq!(resolve_dir(None, default.clone()), default);
+    }
+
+    #[test]
+    fn migrate_moves_drafts_and_sidecars_only() {
+        let from = fresh_dir("floaty-drafts-test-migrate-from");
+        let to = std::env::temp_dir().join("floaty-drafts-test-migrate-to");
+        let _ = std::fs::remove_dir_all(&to);
+        std::fs::write(from.join("draft-1.mp4"), b"video").unwrap();
+        std::fs::write(from.join("draft-1.json"), b"{}").unwrap();
+        std::fs::write(from.join("draft-1.edits.json"), b"{}").unwrap();
+        std::fs::write(from.join("notes.txt"), b"keep").unwrap();
+
+        let report = migrate(&from, &to).unwrap();
+        assert_eq!(report.moved, 3);
+        assert!(report.skipped.is_empty());
+        assert_eq!(std::fs::read(to.join("draft-1.mp4")).unwrap(), b"video");
+        assert!(to.join("draft-1.json").exists());
+        assert!(to.join("draft-1.edits.json").exists());
+        assert!(!from.join("draft-1.mp4").exists());
+        assert!(from.join("notes.txt").exists());
+        assert!(!to.join("notes.txt").exists());
+        let _ = std::fs::remove_dir_all(&from);
+        let _ = std::fs::remove_dir_all(&to);
+    }
+
+    #[test]
+    fn migrate_never_overwrites() {
+        let from = fresh_dir("floaty-drafts-test-migrate-conflict-from");
+        let to = fresh_dir("floaty-drafts-test-migrate-conflict-to");
+        std::fs::write(from.join("draft-1.mp4"), b"new").unwrap();
+        std::fs::write(to.join("draft-1.mp4"), b"old").unwrap();
+        let report = migrate(&from, &to).unwrap();
+        assert_eq!(report.moved, 0);
+        assert_eq!(report.skipped, vec!["draft-1.mp4".to_string()]);
+        assert_eq!(std::fs::read(to.join("draft-1.mp4")).unwrap(), b"old");
+        assert!(from.join("draft-1.mp4").exists());
+        let _ = std::fs::remove_dir_all(&from);
+        let _ = std::fs::remove_dir_all(&to);
+    }
+
+    #[test]
+    fn migrate_to_same_dir_is_noop() {
+        let dir = fresh_dir("floaty-drafts-test-migrate-same");
+        std::fs::write(dir.join("draft-1.mp4"), b"x").unwrap();
+        assert_eq!(migrate(&dir, &dir).unwrap().moved, 0);
+        assert!(dir.join("draft-1.mp4").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn prune_removes_only_stale_draft_files() {
+        let dir = fresh_dir("floaty-drafts-test-prune");
+        for name in [
+            "draft-1.mp4",
+            "draft-1.json",
+            "draft-1.edits.json.tmp",
+            ".draft-2.webm.partial",
+            "holiday.mp4",
+            "notes.tmp",
+            ".cache.partial",
+        ] {
+            std::fs::write(dir.join(name), b"x").unwrap();
+        }
+        std::fs::create_dir(dir.join("draft-3.mp4")).unwrap();
+
+        prune(&dir, std::time::SystemTime::now() + std::time::Duration::from_secs(60));
+        let mut left: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        left.sort();
+        assert_eq!(left, [".cache.partial", "draft-3.mp4", "holiday.mp4", "notes.tmp"]);
+
+        // Nothing is stale yet.
+        std::fs::write(dir.join("draft-4.mp4"), b"x").unwrap();
+        prune(&dir, std::time::SystemTime::now() - std::time::Duration::from_secs(60));
+        assert!(dir.join("draft-4.mp4").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn ensure_free_space_rejects_impossible_minimum() {
+        let dir = std::env::temp_dir();
+        assert!(ensure_free_space(&dir, 0).is_ok());
+        assert!(ensure_free_space(&dir, u64::MAX).is_err());
+    }
+}
@@ -0,0 +1,366 @@
+//! Non-destructive edit decision list (EDL) per draft.
+//!
+//! The editor's choices — trim, zoom, aspect, export size/container, manual
+//! zoom keyframes and cut segments — are saved next to the metadata sidecar
+//! as `draft-<id>.edits.json`, so closing the editor no longer loses them
+//! and reopening the draft restores them. The file holds the current list
+//! plus bounded undo/redo stacks; every save pushes the previous state.
+//!
+//! Unlike the metadata sidecar this document is typed on the Rust side:
+//! `.floaty` bundles carry it and it is validated on every write.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Schema version of the on-disk document.
+pub const DOCUMENT_VERSION: u32 = 1;
+
+/// Undo steps kept per draft. Older steps are dropped.
+const MAX_HISTORY: usize = 100;
+
+const MIN_ZOOM: f64 = 1.0;
+const MAX_ZOOM: f64 = 3.0;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum AspectPreset {
+    #[default]
+    Original,
+    Landscape,
+    Portrait,
+    Square,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    #[default]
+    Mp4,
+    Webm,
+}
+
+/// A manual zoom point. Between keyframes the editor interpolates; when
+/// present they take precedence over the cursor-following auto zoom.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ZoomKeyframe {
+    /// Seconds from the start of the source.
+    pub t: f64,
+    pub factor: f64,
+    /// Zoom centre, normalized to the frame (0..1). `None` = frame centre.
+    #[serde(default)]
+    pub x: Option<f64>,
+    #[serde(default)]
+    pub y: Option<f64>,
+}
+
+/// A span removed from the middle of the clip, in source seconds.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CutSegment {
+    pub start: f64,
+    pub end: f64,
+}
+
+/// Everything the editor decided for one draft. Mirrors `EditDecisions` in
+/// `types/app.ts`. Keyframes and cuts default to empty so lists written
+/// before they existed (and v1 `.floaty` bundles) still parse.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EditDecisionList {
+    /// Trim in/out points in source seconds.
+    pub trim: (f64, f64),
+    pub zoom_enabled: bool,
+    pub zoom_factor: f64,
+    pub aspect: AspectPreset,
+    /// Export height in px; `None` = source resolution.
+    pub target_height: Option<u32>,
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub zoom_keyframes: Vec<ZoomKeyframe>,
+    #[serde(default)]
+    pub cuts: Vec<CutSegment>,
+}
+
+/// The on-disk document: current list plus undo/redo history.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EditDocument {
+    pub version: u32,
+    pub current: EditDecisionList,
+    #[serde(default)]
+    pub undo: Vec<EditDecisionList>,
+    #[serde(default)]
+    pub redo: Vec<EditDecisionList>,
+}
+
+/// What the editor gets back from every EDL command.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EditState {
+    pub edits: EditDecisionList,
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+impl EditDocument {
+    pub fn new(current: EditDecisionList) -> Self {
+        Self {
+            version: DOCUMENT_VERSION,
+            current,
+            undo: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    pub fn state(&self) -> EditState {
+        EditState {
+            edits: self.current.clone(),
+            can_undo: !self.undo.is_empty(),
+            can_redo: !self.redo.is_empty(),
+        }
+    }
+
+    /// Record a new current list. No-op (no history entry) when unchanged.
+    pub fn apply(&mut self, next: EditDecisionList) {
+        if next == self.current {
+            return;
+        }
+        let prev = std::mem::replace(&mut self.current, next);
+        self.undo.push(prev);
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Step back one edit. Returns `false` when there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(prev) = self.undo.pop() else {
+            return false;
+        };
+        let cur = std::mem::replace(&mut self.current, prev);
+        self.redo.push(cur);
+        true
+    }
+
+    /// Re-apply the last undone edit. Returns `false` when there is none.
+    pub fn redo(&mut self) -> bool {
+        let Some(next) = self.redo.pop() else {
+            return false;
+        };
+        let cur = std::mem::replace(&mut self.current, next);
+        self.undo.push(cur);
+        true
+    }
+}
+
+fn finite_or(v: f64, fallback: f64) -> f64 {
+    if v.is_finite() {
+        v
+    } else {
+        fallback
+    }
+}
+
+/// Clean up a list coming from the frontend or a bundle: non-negative,
+/// ordered trim; zoom factors clamped to the editor's 1–3× range;
+/// keyframes sorted by time; cuts ordered, non-empty and merged where they
+/// overlap. Never fails — anything unusable is dropped or clamped.
+pub fn normalize(mut edl: EditDecisionList) -> EditDecisionList {
+    let start = finite_or(edl.trim.0, 0.0).max(0.0);
+    let end = finite_or(edl.trim.1, start).max(start);
+    edl.trim = (start, end);
+    edl.zoom_factor = finite_or(edl.zoom_factor, 2.0).clamp(MIN_ZOOM, MAX_ZOOM);
+    if edl.target_height == Some(0) {
+        edl.target_height = None;
+    }
+
+    edl.zoom_keyframes.retain(|k| k.t.is_finite() && k.t >= 0.0 && k.factor.is_finite());
+    for k in &mut edl.zoom_keyframes {
+        k.factor = k.factor.clamp(MIN_ZOOM, MAX_ZOOM);
+        k.x = k.x.filter(|v| v.is_finite()).map(|v| v.clamp(0.0, 1.0));
+        k.y = k.y.filter(|v| v.is_finite()).map(|v| v.clamp(0.0, 1.0));
+    }
+    edl.zoom_keyframes.sort_by(|a, b| a.t.total_cmp(&b.t));
+
+    let mut cuts: Vec<CutSegment> = edl
+        .cuts
+        .iter()
+        .filter(|c| c.start.is_finite() && c.end.is_finite())
+        .map(|c| CutSegment {
+            start: c.start.min(c.end).max(0.0),
+            end: c.start.max(c.end).max(0.0),
+        })
+        .filter(|c| c.end > c.start)
+        .collect();
+    cuts.sort_by(|a, b| a.start.total_cmp(&b.start));
+    let mut merged: Vec<CutSegment> = Vec::with_capacity(cuts.len());
+    for cut in cuts {
+        match merged.last_mut() {
+            Some(last) if cut.start <= last.end => last.end = last.end.max(cut.end),
+            _ => merged.push(cut),
+        }
+    }
+    edl.cuts = merged;
+    edl
+}
+
+/// Parse an edit list from an untyped value (e.g. a bundle's `edits.json`).
+pub fn from_value(value: serde_json::Value) -> Result<EditDecisionList, String> {
+    serde_json::from_value(value)
+        .map(normalize)
+        .map_err(|e| e.to_string())
+}
+
+/// Load the document at `path`. `Ok(None)` when none has been saved yet.
+pub fn load(path: &Path) -> Result<Option<EditDocument>, String> {
+    let body = match std::fs::read_to_string(path) {
+        Ok(body) => body,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    let doc: EditDocument = serde_json::from_str(&body).map_err(|e| e.to_string())?;
+    if doc.version > DOCUMENT_VERSION {
+        return Err(format!(
+            "edit list v{} is newer than this app supports (v{})",
+            doc.version, DOCUMENT_VERSION
+        ));
+    }
+    Ok(Some(doc))
+}
+
+/// Write the document to `path` via a temp file + rename, so a crash
+/// mid-write never leaves a truncated edit list.
+pub fn store(path: &Path, doc: &EditDocument) -> Result<(), String> {
+    let json = serde_json::to_string(doc).map_err(|e| e.to_string())?;
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = std::path::PathBuf::from(tmp);
+    super::store::write_recording_meta(&tmp, &json)?;
+    std::fs::rename(&tmp, path).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> EditDecisionList {
+        EditDecisionList {
+            trim: (1.0, 5.0),
+            zoom_enabled: true,
+            zoom_factor: 2.0,
+            aspect: AspectPreset::Landscape,
+            target_height: Some(1080),
+            format: ExportFormat::Mp4,
+            zoom_keyframes: Vec::new(),
+            cuts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn parses_frontend_shape_without_keyframes_or_cuts() {
+        let edl = from_value(serde_json::json!({
+            "trim": [0.5, 3.0],
+            "zoomEnabled": false,
+            "zoomFactor": 2.5,
+            "aspect": "portrait",
+            "targetHeight": null,
+            "format": "webm"
+        }))
+        .unwrap();
+        assert_eq!(edl.trim, (0.5, 3.0));
+        assert_eq!(edl.aspect, AspectPreset::Portrait);
+        assert_eq!(edl.format, ExportFormat::Webm);
+        assert!(edl.zoom_keyframes.is_empty() && edl.cuts.is_empty());
+        assert!(from_value(serde_json::json!({ "trim": "nope" })).is_err());
+    }
+
+    #[test]
+    fn normalize_clamps_and_orders() {
+        let mut edl = sample();
+        edl.trim = (-1.0, f64::NAN);
+        edl.zoom_factor = 9.0;
+        edl.target_height = Some(0);
+        edl.zoom_keyframes = vec![
+            ZoomKeyframe { t: 4.0, factor: 0.5, x: Some(2.0), y: None },
+            ZoomKeyframe { t: 1.0, factor: 2.0, x: None, y: None },
+            ZoomKeyframe { t: f64::NAN, factor: 2.0, x: None, y: None },
+        ];
+        edl.cuts = vec![
+            CutSegment { start: 6.0, end: 4.0 },
+            CutSegment { start: 5.0, end: 7.0 },
+            CutSegment { start: 1.0, end: 1.0 },
+            CutSegment { start: 0.5, end: 0.8 },
+        ];
+        let edl = normalize(edl);
+        assert_eq!(edl.trim, (0.0, 0.0));
+        assert_eq!(edl.zoom_factor, MAX_ZOOM);
+        assert_eq!(edl.target_height, None);
+        assert_eq!(edl.zoom_keyframes.len(), 2);
+        assert_eq!(edl.zoom_keyframes[0].t, 1.0);
+        assert_eq!(edl.zoom_keyframes[1].factor, MIN_ZOOM);
+        assert_eq!(edl.zoom_keyframes[1].x, Some(1.0));
+        assert_eq!(
+            edl.cuts,
+            vec![CutSegment { start: 0.5, end: 0.8 }, CutSegment { start: 4.0, end: 7.0 }]
+        );
+    }
+
+    #[test]
+    fn undo_redo_history() {
+        let mut doc = EditDocument::new(sample());
+        assert!(!doc.undo());
+
+        let mut second = sample();
+        second.zoom_factor = 3.0;
+        doc.apply(second.clone());
+        // Re-applying the same list is not a new step.
+        doc.apply(second.clone());
+        assert_eq!(doc.undo.len(), 1);
+
+        assert!(doc.undo());
+        assert_eq!(doc.current, sample());
+        assert!(doc.state().can_redo);
+        assert!(doc.redo());
+        assert_eq!(doc.current, second);
+
+        // A fresh edit after an undo drops the redo branch.
+        doc.undo();
+        let mut third = sample();
+        third.format = ExportFormat::Webm;
+        doc.apply(third);
+        assert!(!doc.state().can_redo);
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let mut doc = EditDocument::new(sample());
+        for i in 0..(MAX_HISTORY + 10) {
+            let mut next = sample();
+            next.trim = (0.0, i as f64 + 10.0);
+            doc.apply(next);
+        }
+        assert_eq!(doc.undo.len(), MAX_HISTORY);
+    }
+
+    #[test]
+    fn store_load_roundtrip() {
+        let dir = std::env::temp_dir().join("floaty-edits-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("draft-1.edits.json");
+        assert_eq!(load(&path).unwrap(), None);
+
+        let mut doc = EditDocument::new(sample());
+        let mut next = sample();
+        next.cuts = vec![CutSegment { start: 2.0, end: 3.0 }];
+        doc.apply(next);
+        store(&path, &doc).unwrap();
+        assert_eq!(load(&path).unwrap(), Some(doc));
+
+        std::fs::write(&path, r#"{"version":99,"current":{"trim":[0,1],"zoomEnabled":false,"zoomFactor":2,"aspect":"original","targetHeight":null,"format":"mp4"}}"#).unwrap();
+        assert!(load(&path).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,9 +1,27 @@
 //! Screen recording subsystem.
 //!
-//! Currently a thin umbrella over [`store`] (the persistence layer). Mouse
-//! tracking lives in the shared keyboard event tap (`crate::keyboard`); the
-//! orchestration pipeline lives in the frontend (`useRecordingPipeline`).
+//! An umbrella over [`store`] (the persistence layer), [`drafts`] (draft ids
+//! and drafts-directory confinement), [`stream`] (the `floaty-draft://`
+//! scheme the editor plays drafts through), [`edits`] (the per-draft edit
+//! decision list) and [`bundle`] (`.floaty` project archives). Mouse
+//! tracking lives in the shared keyboard event tap
+//! (`crate::keyboard`); the orchestration pipeline lives in the frontend
+//! (`useRecordingPipeline`).
 //! This module is the Rust-side home for anything recording-related that is
 //! neither window management nor mouse capture.
 
+pub mod bundle;
+pub mod drafts;
+pub mod edits;
 pub mod store;
+pub mod stream;
+
+/// An empty scratch directory for a test, unique to this process so
+/// concurrent test runs do not share it.
+#[cfg(test)]
+pub(crate) fn fresh_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -5,7 +5,8 @@
 //! `stamp()` helper:
 //!   1. where recordings are written (output directory resolution),
 //!   2. what they are named (filename policy),
-//!   3. how they hit disk (byte write).
+//!   3. how they hit disk (byte write),
+//!   4. which saved recordings count as recent (for the tray).
 //!
 //! Each piece is a pure(ish) function so it can be unit-tested without
 //! spinning up a Tauri app or a native dialog.
@@ -50,6 +51,18 @@ pub fn write_recording(path: &Path, bytes: &[u8]) -> Result<(), String> {
     std::fs::write(path, bytes).map_err(|e| e.to_string())
 }
 
+/// How many saved recordings the tray lists.
+pub const RECENT_LIMIT: usize = 8;
+
+/// Put `path` at the front of the recent recordings (newest first),
+/// dropping an older entry for the same file and anything past
+/// [`RECENT_LIMIT`].
+pub fn remember_recent(recent: &mut Vec<String>, path: &str) {
+    recent.retain(|p| p != path);
+    recent.insert(0, path.to_string());
+    recent.truncate(RECENT_LIMIT);
+}
+
 /// Write recording metadata (a pre-serialized JSON string) to `path`.
 /// Kept as its own function rather than reusing `write_recording` so the
 /// intent at the call site is explicit and a future streaming/pretty-print
@@ -62,15 +75,25 @@ pub fn write_recording_meta(path: &Path, json: &str) -> Result<(), String> {
 ///
 /// A draft at `draft-<millis>.mp4` (or `.webm`) gets a sidecar at
 /// `draft-<millis>.json` in the same directory. The two files share a
-/// basename so they travel and prune together. Used by both the
-/// `save_recording_meta` / `read_recording_meta` commands and the draft
-/// deletion path so they agree on the layout.
+/// basename so they travel and prune together. Used via
+/// `drafts::resolve_sidecar` by the meta commands and the draft deletion
+/// path so they agree on the layout.
 pub fn meta_sidecar_path(draft_path: &Path) -> PathBuf {
     let mut out = draft_path.to_path_buf();
     out.set_extension("json");
     out
 }
 
+/// Derive the edit decision list path for a draft recording:
+/// `draft-<millis>.edits.json`, next to the metadata sidecar. Kept separate
+/// from the sidecar because the sidecar is frontend-owned and write-once,
+/// while the edit list is rewritten on every edit (see `edits`).
+pub fn edits_sidecar_path(draft_path: &Path) -> PathBuf {
+    let mut out = draft_path.to_path_buf();
+    out.set_extension("edits.json");
+    out
+}
+
 /// Format the current local time as `YYYYMMDD-HHMMSS` for filename use.
 /// Split out from `make_filename` so the filename builder stays pure and
 /// clock-free (and therefore unit-testable).
@@ -131,6 +154,18 @@ mod tests {
         assert_eq!(make_filename("  spaced  ", "x"), "spaced-x.mp4");
     }
 
+    #[test]
+    fn remember_recent_keeps_newest_first() {
+        let mut recent: Vec<String> = (0..RECENT_LIMIT).map(|i| format!("/r/{}.mp4", i)).collect();
+        remember_recent(&mut recent, "/r/3.mp4");
+        assert_eq!(recent[..2], ["/r/3.mp4", "/r/0.mp4"]);
+        assert_eq!(recent.len(), RECENT_LIMIT);
+        remember_recent(&mut recent, "/r/new.mp4");
+        assert_eq!(recent[0], "/r/new.mp4");
+        assert_eq!(recent.len(), RECENT_LIMIT);
+        assert!(!recent.contains(&format!("/r/{}.mp4", RECENT_LIMIT - 1)));
+    }
+
     #[test]
     fn timestamp_parts_known_values() {
         // 1970-01-01 00:00:00 UTC
@@ -187,6 +222,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn edits_sidecar_path_sits_next_to_meta() {
+        assert_eq!(
+            edits_sidecar_path(std::path::Path::new("/tmp/floaty-drafts/draft-1234567.webm")),
+            std::path::PathBuf::from("/tmp/floaty-drafts/draft-1234567.edits.json")
+        );
+    }
+
     #[test]
     fn write_recording_meta_roundtrip() {
         let dir = std::env::temp_dir().join("floaty-store-test-meta");
@@ -0,0 +1,250 @@
+//! `floaty-draft://` URI scheme: streams draft recordings to the editor.
+//!
+//! `read_recording_file` ships the whole draft over IPC, which stalls the
+//! editor for seconds on multi-gigabyte recordings. This handler instead
+//! serves the draft straight from disk with HTTP Range support, so the
+//! editor's `<video>` element can seek and buffer like it would against a
+//! normal media server. The URL carries the draft's opaque id, never a path,
+//! and is resolved through the same confinement as every draft command.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use tauri::http::{header, Request, Response, StatusCode};
+
+use super::drafts;
+
+/// Scheme name registered with the webview (`floaty-draft://localhost/<id>`).
+pub const SCHEME: &str = "floaty-draft";
+
+/// Upper bound for any one response. Media elements ask for the rest of the
+/// file and are happy to receive less; capping every range (and plain GETs)
+/// keeps each response small so no request reads gigabytes into memory.
+const MAX_CHUNK: u64 = 4 * 1024 * 1024;
+
+/// A satisfiable byte range, inclusive on both ends (HTTP semantics).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Parse a `Range` header value against a file of `len` bytes.
+///
+/// Supports the single-range forms media elements send: `bytes=N-`,
+/// `bytes=N-M` and the suffix form `bytes=-N`. Multi-range requests are
+/// answered with the first range only, and every range is cut to
+/// [`MAX_CHUNK`] bytes. Returns `None` when the range is malformed or
+/// unsatisfiable (the caller answers 416).
+pub fn parse_range(value: &str, len: u64) -> Option<ByteRange> {
+    let spec = value.trim().strip_prefix("bytes=")?;
+    let first = spec.split(',').next()?.trim();
+    let (start, end) = first.split_once('-')?;
+    let (start, end) = (start.trim(), end.trim());
+
+    if len == 0 {
+        return None;
+    }
+    let last = len - 1;
+
+    let range = if start.is_empty() {
+        // Suffix range: the final N bytes.
+        let n: u64 = end.parse().ok()?;
+        if n == 0 {
+            return None;
+        }
+        ByteRange {
+            start: len.saturating_sub(n),
+            end: last,
+        }
+    } else {
+        let start: u64 = start.parse().ok()?;
+        let end = if end.is_empty() {
+            start.saturating_add(MAX_CHUNK - 1).min(last)
+        } else {
+            end.parse::<u64>().ok()?.min(last)
+        };
+        ByteRange { start, end }
+    };
+
+    if range.start > range.end || range.start > last {
+        return None;
+    }
+    Some(ByteRange {
+        end: range.end.min(range.start.saturating_add(MAX_CHUNK - 1)),
+        ..range
+    })
+}
+
+fn content_type_for(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("webm") => "video/webm",
+        _ => "video/mp4",
+    }
+}
+
+fn empty(status: StatusCode) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .body(Vec::new())
+        .unwrap_or_default()
+}
+
+/// Answer one `floaty-draft://` request against the drafts directory `dir`.
+///
+/// Responses carry `Access-Control-Allow-Origin: *` because the custom
+/// scheme is a different origin from the app: without it the editor's
+/// canvas would be tainted when it draws the video for zoom preview/export.
+pub fn serve_draft(dir: &Path, request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let id = request.uri().path().trim_start_matches('/');
+    if drafts::validate_id(id).is_err() {
+        return empty(StatusCode::FORBIDDEN);
+    }
+    let Ok(path) = drafts::resolve(dir, id) else {
+        return empty(StatusCode::NOT_FOUND);
+    };
+    let Ok(mut file) = File::open(&path) else {
+        return empty(StatusCode::NOT_FOUND);
+    };
+    let Ok(len) = file.metadata().map(|m| m.len()) else {
+        return empty(StatusCode::INTERNAL_SERVER_ERROR);
+    };
+
+    let builder = Response::builder()
+        .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::CONTENT_TYPE, content_type_for(&path));
+
+    let range_header = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|v| v.to_str().ok());
+
+    // Media elements always send a Range; a plain GET is answered like
+    // `bytes=0-`, with the first chunk as partial content.
+    let range_header = match range_header {
+        Some(value) => value,
+        None if len == 0 => {
+            return builder
+                .status(StatusCode::OK)
+                .header(header::CONTENT_LENGTH, 0)
+                .body(Vec::new())
+                .unwrap_or_default()
+        }
+        None => "bytes=0-",
+    };
+
+    let Some(range) = parse_range(range_header, len) else {
+        return Response::builder()
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::ACCESS_CONTROL_ALLOW_ORIGIN, "*")
+            .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+            .body(Vec::new())
+            .unwrap_or_default();
+    };
+
+    let count = range.end - range.start + 1;
+    let mut body = vec![0u8; count as usize];
+    if file.seek(SeekFrom::Start(range.start)).is_err() || file.read_exact(&mut body).is_err() {
+        return empty(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    builder
+        .status(StatusCode::PARTIAL_CONTENT)
+        .header(header::CONTENT_LENGTH, count)
+        .header(
+            header::CONTENT_RANGE,
+            format!("bytes {}-{}/{}", range.start, range.end, len),
+        )
+        .body(body)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_range_forms() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Some(ByteRange { start: 0, end: 99 }));
+        assert_eq!(parse_range("bytes=900-", 1000), Some(ByteRange { start: 900, end: 999 }));
+        assert_eq!(parse_range("bytes=-100", 1000), Some(ByteRange { start: 900, end: 999 }));
+        // End past EOF is clamped; suffix longer than the file is the whole file.
+        assert_eq!(parse_range("bytes=10-5000", 1000), Some(ByteRange { start: 10, end: 999 }));
+        assert_eq!(parse_range("bytes=-5000", 1000), Some(ByteRange { start: 0, end: 999 }));
+        // Only the first of several ranges is honoured.
+        assert_eq!(parse_range("bytes=0-1, 5-6", 1000), Some(ByteRange { start: 0, end: 1 }));
+    }
+
+    #[test]
+    fn parse_range_caps_open_ended() {
+        let len = 100 * 1024 * 1024;
+        let r = parse_range("bytes=0-", len).unwrap();
+        assert_eq!(r.end - r.start + 1, MAX_CHUNK);
+        // Closed and suffix ranges are capped too.
+        let r = parse_range(&format!("bytes=10-{}", len - 1), len).unwrap();
+        assert_eq!(r, ByteRange { start: 10, end: 10 + MAX_CHUNK - 1 });
+        let r = parse_range(&format!("bytes=-{}", len), len).unwrap();
+        assert_eq!(r, ByteRange { start: 0, end: MAX_CHUNK - 1 });
+    }
+
+    #[test]
+    fn parse_range_rejects_unsatisfiable() {
+        assert_eq!(parse_range("bytes=1000-", 1000), None);
+        assert_eq!(parse_range("bytes=5-1", 1000), None);
+        assert_eq!(parse_range("bytes=-0", 1000), None);
+        assert_eq!(parse_range("items=0-1", 1000), None);
+        assert_eq!(parse_range("bytes=0-1", 0), None);
+    }
+
+    #[test]
+    fn serve_draft_returns_partial_content() {
+        let dir = std::env::temp_dir().join("floaty-stream-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("draft-1.webm"), b"0123456789").unwrap();
+
+        let req = Request::builder()
+            .uri("floaty-draft://localhost/1")
+            .header(header::RANGE, "bytes=2-5")
+            .body(Vec::new())
+            .unwrap();
+        let res = serve_draft(&dir, &req);
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(res.body(), b"2345");
+        assert_eq!(res.headers()[header::CONTENT_RANGE], "bytes 2-5/10");
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "video/webm");
+
+        let req = Request::builder()
+            .uri("floaty-draft://localhost/1")
+            .header(header::RANGE, "bytes=20-")
+            .body(Vec::new())
+            .unwrap();
+        assert_eq!(serve_draft(&dir, &req).status(), StatusCode::RANGE_NOT_SATISFIABLE);
+
+        // Without a Range header the first chunk comes back as a range.
+        let req = Request::builder()
+            .uri("floaty-draft://localhost/1")
+            .body(Vec::new())
+            .unwrap();
+        let res = serve_draft(&dir, &req);
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(res.body(), b"0123456789");
+        assert_eq!(res.headers()[header::CONTENT_RANGE], "bytes 0-9/10");
+
+        let req = Request::builder()
+            .uri("floaty-draft://localhost/2")
+            .body(Vec::new())
+            .unwrap();
+        assert_eq!(serve_draft(&dir, &req).status(), StatusCode::NOT_FOUND);
+
+        let req = Request::builder()
+            .uri("floaty-draft://localhost/..%2Fsettings.json")
+            .body(Vec::new())
+            .unwrap();
+        assert_eq!(serve_draft(&dir, &req).status(), StatusCode::FORBIDDEN);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
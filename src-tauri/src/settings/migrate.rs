@@ -0,0 +1,363 @@
+//! Versioned loading of the persisted settings blob.
+//!
+//! A blob is upgraded one version at a time through [`STEPS`], then merged
+//! over the defaults one field at a time: a field whose value no longer
+//! deserializes keeps its default and is reported as a [`FieldWarning`]
+//! rather than failing the whole read. Values outside the ranges in
+//! [`validate`](super::validate) are reset the same way. Adding a version
+//! means appending a step and bumping [`CURRENT_VERSION`]; steps never
+//! change once shipped.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::{validate, AppSettings};
+
+/// Version written by this build. Blobs without `settingsVersion` are 0.
+pub const CURRENT_VERSION: u32 = 1;
+
+const VERSION_KEY: &str = "settingsVersion";
+
+/// One upgrade step over the raw JSON object.
+type Step = fn(&mut Map<String, Value>);
+
+/// `STEPS[n]` upgrades a version-`n` blob to version `n + 1`.
+const STEPS: &[Step] = &[v0_to_v1];
+
+/// v0 → v1: the unversioned blobs.
+fn v0_to_v1(map: &mut Map<String, Value>) {
+    // The region moved to its own store key (RECORDING_REGION_KEY); a copy
+    // left in the blob is stale and would only confuse readers.
+    map.remove("recordingRegion");
+    // Only "auto" was ever implemented; older builds persisted other modes.
+    map.insert("qualityMode".into(), Value::from("auto"));
+}
+
+/// A field that could not be loaded and fell back to its default.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldWarning {
+    /// camelCase key as stored, or empty for the blob as a whole.
+    pub field: String,
+    pub message: String,
+}
+
+/// The result of loading a stored blob.
+#[derive(Debug, Clone)]
+pub struct Loaded {
+    pub settings: AppSettings,
+    pub warnings: Vec<FieldWarning>,
+    /// Version the blob was stored at, before upgrading.
+    pub from_version: u32,
+}
+
+impl Loaded {
+    /// Whether the caller should write the loaded settings back: the blob
+    /// was upgraded or had fields reset. Never true for a blob from a newer
+    /// build, which would lose the settings this build does not know.
+    pub fn needs_write(&self) -> bool {
+        match self.from_version.cmp(&CURRENT_VERSION) {
+            std::cmp::Ordering::Less => true,
+            std::cmp::Ordering::Equal => !self.warnings.is_empty(),
+            std::cmp::Ordering::Greater => false,
+        }
+    }
+}
+
+fn stored_version(map: &Map<String, Value>, warnings: &mut Vec<FieldWarning>) -> u32 {
+    match map.get(VERSION_KEY) {
+        None => 0,
+        Some(v) => match v.as_u64().and_then(|n| u32::try_from(n).ok()) {
+            Some(n) => n,
+            None => {
+                warnings.push(FieldWarning {
+                    field: VERSION_KEY.into(),
+                    message: format!("not a version number: {}", v),
+                });
+                0
+            }
+        },
+    }
+}
+
+/// Upgrade `value` to [`CURRENT_VERSION`] and load it over the defaults.
+/// Never fails: anything unusable is reported and replaced by its default.
+/// A blob from a newer build is loaded as-is (unknown keys are ignored)
+/// with a warning, since its steps are unknown to this build; writes keep
+/// them through [`to_stored`].
+pub fn load(value: &Value) -> Loaded {
+    let mut warnings = Vec::new();
+    let Some(stored) = value.as_object() else {
+        warnings.push(FieldWarning {
+            field: String::new(),
+            message: "settings are not a JSON object; using defaults".into(),
+        });
+        return Loaded {
+            settings: AppSettings::default(),
+            warnings,
+            from_version: 0,
+        };
+    };
+
+    let from_version = stored_version(stored, &mut warnings);
+    let mut map = stored.clone();
+    if from_version > CURRENT_VERSION {
+        warnings.push(FieldWarning {
+            field: VERSION_KEY.into(),
+            message: format!(
+                "written by a newer version ({} > {}); unknown settings are ignored",
+                from_version, CURRENT_VERSION
+            ),
+        });
+    } else {
+        for step in &STEPS[from_version as usize..] {
+            step(&mut map);
+        }
+    }
+    map.remove(VERSION_KEY);
+
+    let (mut settings, field_warnings) = merge_over_defaults(map.clone());
+    warnings.extend(field_warnings);
+    // Resetting one field can invalidate another (a hotkey default taking
+    // a shortcut stored for a later binding), so repeat until the result
+    // validates. A hotkey that is invalid at its default clashes with a
+    // stored binding and is unbound instead.
+    loop {
+        let invalid = validate::validate(&settings);
+        if invalid.is_empty() {
+            break;
+        }
+        let mut repaired = false;
+        for err in &invalid {
+            if map.remove(&err.field).is_some() {
+                repaired = true;
+            } else if err.field.starts_with("hotkey") {
+                map.insert(err.field.clone(), Value::from(""));
+                repaired = true;
+            }
+        }
+        warnings.extend(invalid.into_iter().map(|err| FieldWarning {
+            field: err.field,
+            message: err.message,
+        }));
+        if !repaired {
+            break;
+        }
+        settings = merge_over_defaults(map.clone()).0;
+    }
+    Loaded {
+        settings,
+        warnings,
+        from_version,
+    }
+}
+
+/// The blob to store for `settings`, given the one stored now. Written over
+/// a blob from a newer build, the fields this build knows are replaced and
+/// the rest, along with the newer version, are kept: saving here must not
+/// downgrade the blob or drop the newer build's settings.
+pub fn to_stored(settings: &AppSettings, stored: Option<&Value>) -> serde_json::Result<Value> {
+    let value = serde_json::to_value(settings)?;
+    let newer = stored
+        .and_then(Value::as_object)
+        .filter(|map| map.get(VERSION_KEY).and_then(Value::as_u64) > Some(u64::from(CURRENT_VERSION)));
+    match (newer, value) {
+        (Some(newer), Value::Object(known)) => {
+            let mut merged = newer.clone();
+            merged.extend(known);
+            merged.insert(VERSION_KEY.into(), newer[VERSION_KEY].clone());
+            Ok(Value::Object(merged))
+        }
+        (_, value) => Ok(value),
+    }
+}
+
+/// Deserialize `map` over the defaults. The fast path parses everything at
+/// once; if that fails, each field is tried on its own so only the bad ones
+/// are dropped.
+pub(super) fn merge_over_defaults(map: Map<String, Value>) -> (AppSettings, Vec<FieldWarning>) {
+    let base = match serde_json::to_value(AppSettings::default()) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    };
+    let mut merged = base.clone();
+    merged.extend(map.clone());
+    if let Ok(settings) = serde_json::from_value::<AppSettings>(Value::Object(merged)) {
+        return (with_current_version(settings), Vec::new());
+    }
+
+    let mut accepted = base;
+    let mut warnings = Vec::new();
+    for (key, value) in map {
+        let mut candidate = accepted.clone();
+        candidate.insert(key.clone(), value);
+        match serde_json::from_value::<AppSettings>(Value::Object(candidate.clone())) {
+            Ok(_) => accepted = candidate,
+            Err(err) => warnings.push(FieldWarning {
+                field: key,
+                message: err.to_string(),
+            }),
+        }
+    }
+    let settings = serde_json::from_value(Value::Object(accepted)).unwrap_or_default();
+    (with_current_version(settings), warnings)
+}
+
+fn with_current_version(mut settings: AppSettings) -> AppSettings {
+    settings.settings_version = CURRENT_VERSION;
+    settings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{KeyboardDisplayPosition, KeyboardDisplayStyle, Locale, QualityMode, ShapePreset, Theme};
+
+    fn fixture(json: &str) -> Value {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn every_version_has_a_step() {
+        assert_eq!(STEPS.len(), CURRENT_VERSION as usize);
+    }
+
+    #[test]
+    fn loads_original_camera_only_blob() {
+        // First release: camera overlay only, no keyboard display or recording.
+        let loaded = load(&fixture(include_str!("fixtures/v0-camera.json")));
+        assert!(loaded.warnings.is_empty(), "{:?}", loaded.warnings);
+        assert_eq!(loaded.from_version, 0);
+        assert!(loaded.needs_write());
+        let s = loaded.settings;
+        assert_eq!(s.settings_version, CURRENT_VERSION);
+        assert_eq!(s.shape, ShapePreset::Mickey);
+        assert_eq!(s.scale, 1.4);
+        assert!(!s.mirror);
+        assert_eq!(s.quality_mode, QualityMode::Auto);
+        // Fields that did not exist yet come from the defaults.
+        assert_eq!(s.keyboard_display_position, KeyboardDisplayPosition::BottomCenter);
+        assert_eq!(s.recording_fps, 30);
+        assert_eq!(s.locale, Locale::from("en"));
+    }
+
+    #[test]
+    fn loads_unversioned_recording_blob() {
+        // Last unversioned layout, including the region that later moved
+        // to its own store key.
+        let loaded = load(&fixture(include_str!("fixtures/v0-recording.json")));
+        assert!(loaded.warnings.is_empty(), "{:?}", loaded.warnings);
+        let s = loaded.settings;
+        assert_eq!(s.locale, Locale::from("zh-CN"));
+        assert_eq!(s.keyboard_display_style, KeyboardDisplayStyle::Light);
+        assert_eq!(s.recording_fps, 60);
+        assert!(s.recording_auto_zoom);
+        assert_eq!(s.recording_output_dir.as_deref(), Some("/Users/demo/Movies"));
+        let out = serde_json::to_value(&s).unwrap();
+        assert!(out.get("recordingRegion").is_none());
+        assert_eq!(out[VERSION_KEY], CURRENT_VERSION);
+    }
+
+    #[test]
+    fn current_blob_loads_without_rewrite() {
+        let loaded = load(&fixture(include_str!("fixtures/v1.json")));
+        assert!(loaded.warnings.is_empty(), "{:?}", loaded.warnings);
+        assert!(!loaded.needs_write());
+        assert_eq!(loaded.settings.drafts_dir.as_deref(), Some("/data/floaty-drafts"));
+    }
+
+    #[test]
+    fn bad_fields_fall_back_individually() {
+        let mut blob = fixture(include_str!("fixtures/v1.json"));
+        blob["recordingFps"] = Value::from("sixty");
+        blob["shape"] = Value::from("hexagon");
+        let loaded = load(&blob);
+        let mut fields: Vec<_> = loaded.warnings.iter().map(|w| w.field.as_str()).collect();
+        fields.sort();
+        assert_eq!(fields, ["recordingFps", "shape"]);
+        assert!(loaded.needs_write());
+        let s = loaded.settings;
+        assert_eq!(s.recording_fps, 30);
+        assert_eq!(s.shape, ShapePreset::Circle);
+        // Everything else survives.
+        assert_eq!(s.scale, 1.2);
+        assert_eq!(s.theme, Theme::Dark);
+    }
+
+    #[test]
+    fn out_of_range_fields_reset_to_defaults() {
+        let mut blob = fixture(include_str!("fixtures/v1.json"));
+        blob["recordingFps"] = Value::from(500);
+        blob["locale"] = Value::from("");
+        let loaded = load(&blob);
+        let mut fields: Vec<_> = loaded.warnings.iter().map(|w| w.field.as_str()).collect();
+        fields.sort();
+        assert_eq!(fields, ["locale", "recordingFps"]);
+        assert_eq!(loaded.settings.recording_fps, 30);
+        assert_eq!(loaded.settings.locale, Locale::from("en"));
+        assert_eq!(loaded.settings.scale, 1.2);
+    }
+
+    #[test]
+    fn reset_hotkeys_never_leave_duplicates() {
+        let defaults = AppSettings::default();
+        // The visibility binding is reset to its default, which the lock
+        // binding already uses: the lock binding is reset too.
+        let mut blob = fixture(include_str!("fixtures/v1.json"));
+        blob["hotkeyToggleVisibility"] = Value::from("Ctrl+Banana");
+        blob["hotkeyToggleLock"] = Value::from(defaults.hotkey_toggle_visibility.clone());
+        let loaded = load(&blob);
+        assert!(validate::validate(&loaded.settings).is_empty());
+        let fields: Vec<_> = loaded.warnings.iter().map(|w| w.field.as_str()).collect();
+        assert_eq!(fields, ["hotkeyToggleVisibility", "hotkeyToggleLock"]);
+        assert_eq!(loaded.settings.hotkey_toggle_visibility, defaults.hotkey_toggle_visibility);
+        assert_eq!(loaded.settings.hotkey_toggle_lock, defaults.hotkey_toggle_lock);
+
+        // A stored binding takes a later binding's default: that one is
+        // unbound, since resetting cannot help.
+        let mut blob = fixture(include_str!("fixtures/v1.json"));
+        blob["hotkeyToggleVisibility"] = Value::from(defaults.hotkey_toggle_lock.clone());
+        let loaded = load(&blob);
+        assert!(validate::validate(&loaded.settings).is_empty());
+        assert_eq!(loaded.settings.hotkey_toggle_visibility, defaults.hotkey_toggle_lock);
+        assert_eq!(loaded.settings.hotkey_toggle_lock, "");
+        assert_eq!(loaded.warnings.len(), 1);
+    }
+
+    #[test]
+    fn non_object_and_newer_blobs() {
+        let loaded = load(&Value::from("garbage"));
+        assert_eq!(loaded.warnings.len(), 1);
+        assert_eq!(loaded.settings.scale, AppSettings::default().scale);
+
+        let mut blob = fixture(include_str!("fixtures/v1.json"));
+        blob[VERSION_KEY] = Value::from(CURRENT_VERSION + 1);
+        blob["someFutureSetting"] = Value::from(true);
+        let loaded = load(&blob);
+        assert_eq!(loaded.warnings.len(), 1);
+        assert_eq!(loaded.warnings[0].field, VERSION_KEY);
+        assert!(!loaded.needs_write());
+        assert_eq!(loaded.settings.theme, Theme::Dark);
+        assert_eq!(loaded.settings.settings_version, CURRENT_VERSION);
+    }
+
+    #[test]
+    fn saving_over_a_newer_blob_keeps_its_settings() {
+        let mut newer = fixture(include_str!("fixtures/v1.json"));
+        newer[VERSION_KEY] = Value::from(CURRENT_VERSION + 1);
+        newer["someFutureSetting"] = Value::from(true);
+        let settings = AppSettings {
+            recording_fps: 60,
+            ..load(&newer).settings
+        };
+        let stored = to_stored(&settings, Some(&newer)).unwrap();
+        assert_eq!(stored[VERSION_KEY], CURRENT_VERSION + 1);
+        assert_eq!(stored["someFutureSetting"], true);
+        assert_eq!(stored["recordingFps"], 60);
+
+        // Older and current blobs are simply replaced.
+        let stored = to_stored(&settings, Some(&fixture(include_str!("fixtures/v1.json")))).unwrap();
+        assert_eq!(stored, serde_json::to_value(&settings).unwrap());
+        assert_eq!(to_stored(&settings, None).unwrap(), stored);
+    }
+}
@@ -0,0 +1,381 @@
+//! User settings (`AppSettings`) and how they are persisted.
+//!
+//! The settings blob lives under `app_settings` in `settings.json` and has
+//! outlived many app versions. [`migrate`] upgrades old blobs step by step
+//! and loads them field by field, so one bad value falls back to its
+//! default instead of failing every command that reads settings.
+//! [`validate`] holds the numeric ranges every write is checked against,
+//! [`patch`] merges partial updates over the current settings,
+//! and [`profiles`] keeps named snapshots of settings and window layout.
+//! [`portable`] writes such a snapshot to a file and reads it back on
+//! another machine, and [`watch`] notices edits made to the store file
+//! while the app runs.
+//!
+//! The enums below mirror the string unions in `src/types/app.ts`.
+
+use serde::{Deserialize, Serialize};
+
+pub mod migrate;
+pub mod patch;
+pub mod portable;
+pub mod profiles;
+pub mod validate;
+pub mod watch;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ShapePreset {
+    Circle,
+    RoundedSquare,
+    Mickey,
+}
+
+/// Camera quality mode. Only `auto` exists today; kept as an enum so the
+/// stored value is checked like every other choice.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum QualityMode {
+    #[default]
+    Auto,
+}
+
+/// Settings that apply while one camera is in use, in place of the general
+/// ones; unset fields keep the general setting. A built-in camera and a
+/// docked webcam rarely want the same mirror and beauty values.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CameraOverride {
+    /// Device id or label pattern, as in `camera_priority`.
+    #[serde(rename = "match")]
+    pub pattern: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirror: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beauty: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beauty_smoothness: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub beauty_brightness: Option<f64>,
+    /// Tallest capture resolution to ask the camera for, e.g. `720` for a
+    /// camera that drops frames at 1080p.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_height: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+/// UI language: the tag of a locale file in `src/i18n/locales` (`en`,
+/// `zh-CN`, ...). Validation rejects tags without one, so adding a
+/// language needs only its file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Locale(String);
+
+impl Locale {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self("en".into())
+    }
+}
+
+impl From<&str> for Locale {
+    fn from(tag: &str) -> Self {
+        Self(tag.into())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeyboardDisplayPosition {
+    #[default]
+    BottomCenter,
+    TopCenter,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyboardDisplayStyle {
+    #[default]
+    Dark,
+    Light,
+    Glass,
+    Outline,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum CursorEffectStyle {
+    #[default]
+    Ripple,
+    Ring,
+    Spark,
+    None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSettings {
+    /// Schema version of the persisted blob (see [`migrate`]). Always
+    /// [`migrate::CURRENT_VERSION`] once loaded; blobs written before
+    /// versioning have no such key and count as version 0.
+    #[serde(default)]
+    pub settings_version: u32,
+    pub selected_camera_id: Option<String>,
+    /// Cameras to fall back to, in order, while the selected one is not
+    /// connected. Each entry is a device id or a label pattern (ids change
+    /// between machines and docks; labels usually do not). See
+    /// `camera::resolve`.
+    #[serde(default)]
+    pub camera_priority: Vec<String>,
+    /// Settings for particular cameras; the first entry matching the camera
+    /// in use applies.
+    #[serde(default)]
+    pub camera_overrides: Vec<CameraOverride>,
+    pub shape: ShapePreset,
+    pub scale: f64,
+    pub mirror: bool,
+    pub always_on_top: bool,
+    pub click_through: bool,
+    pub locked: bool,
+    #[serde(default)]
+    pub quality_mode: QualityMode,
+    #[serde(default)]
+    pub beauty: bool,
+    #[serde(default = "default_beauty_smoothness")]
+    pub beauty_smoothness: f64,
+    #[serde(default = "default_beauty_brightness")]
+    pub beauty_brightness: f64,
+    #[serde(default)]
+    pub locale: Locale,
+    #[serde(default)]
+    pub theme: Theme,
+    #[serde(default)]
+    pub keyboard_display_enabled: bool,
+    #[serde(default)]
+    pub keyboard_display_position: KeyboardDisplayPosition,
+    #[serde(default = "default_keyboard_scale")]
+    pub keyboard_display_scale: f64,
+    #[serde(default = "default_keyboard_fade_out")]
+    pub keyboard_display_fade_out: u64,
+    #[serde(default = "default_keyboard_width")]
+    pub keyboard_display_width: f64,
+    #[serde(default)]
+    pub keyboard_display_style: KeyboardDisplayStyle,
+    // --- Screen recording ---
+    #[serde(default = "default_recording_enabled")]
+    pub recording_enabled: bool,
+    #[serde(default = "default_recording_fps")]
+    pub recording_fps: u32,
+    #[serde(default = "default_recording_cursor_overlay")]
+    pub recording_cursor_overlay: bool,
+    #[serde(default)]
+    pub recording_auto_zoom: bool,
+    #[serde(default = "default_recording_zoom_factor")]
+    pub recording_zoom_factor: f64,
+    #[serde(default)]
+    pub cursor_effect_style: CursorEffectStyle,
+    #[serde(default = "default_cursor_trail_enabled")]
+    pub cursor_trail_enabled: bool,
+    #[serde(default)]
+    pub recording_output_dir: Option<String>,
+    /// Where drafts live between capture and export. `None` = the per-user
+    /// app data dir. Changed only through `move_drafts_dir`, which migrates
+    /// existing drafts along with the setting; other writes that change it
+    /// are rejected.
+    #[serde(default)]
+    pub drafts_dir: Option<String>,
+    /// Serve the local control API (see `control`). Off by default.
+    #[serde(default)]
+    pub control_api_enabled: bool,
+    #[serde(default = "default_control_api_port")]
+    pub control_api_port: u16,
+    /// Also speak obs-websocket v5 on the control API port.
+    #[serde(default)]
+    pub control_api_obs_compat: bool,
+    /// Global hotkeys (see `hotkeys`): accelerators such as
+    /// `CommandOrControl+Shift+V`; empty = unbound.
+    #[serde(default = "default_hotkey_toggle_visibility")]
+    pub hotkey_toggle_visibility: String,
+    #[serde(default = "default_hotkey_toggle_lock")]
+    pub hotkey_toggle_lock: String,
+    #[serde(default = "default_hotkey_open_settings")]
+    pub hotkey_open_settings: String,
+    #[serde(default = "default_hotkey_toggle_recording")]
+    pub hotkey_toggle_recording: String,
+    #[serde(default)]
+    pub hotkey_toggle_pause: String,
+    #[serde(default)]
+    pub hotkey_toggle_keyboard: String,
+    #[serde(default)]
+    pub hotkey_select_region: String,
+    #[serde(default)]
+    pub hotkey_cycle_shape: String,
+    #[serde(default = "default_hotkey_next_profile")]
+    pub hotkey_next_profile: String,
+    /// Momentary: the camera is shown only while this is held.
+    #[serde(default)]
+    pub hotkey_hold_to_show: String,
+    /// Momentary: recording runs while this is held, paused otherwise.
+    #[serde(default)]
+    pub hotkey_hold_to_record: String,
+    /// Publish the styled camera as a virtual webcam (see `camera::output`).
+    /// Off by default.
+    #[serde(default)]
+    pub virtual_camera_enabled: bool,
+    /// Loopback device to write to; `None` = the first one found.
+    #[serde(default)]
+    pub virtual_camera_device: Option<String>,
+    #[serde(default = "default_virtual_camera_width")]
+    pub virtual_camera_width: u32,
+    #[serde(default = "default_virtual_camera_height")]
+    pub virtual_camera_height: u32,
+    #[serde(default = "default_virtual_camera_fps")]
+    pub virtual_camera_fps: u32,
+}
+
+fn default_recording_fps() -> u32 {
+    30
+}
+
+fn default_recording_enabled() -> bool {
+    true
+}
+
+fn default_recording_cursor_overlay() -> bool {
+    true
+}
+
+fn default_recording_zoom_factor() -> f64 {
+    2.0
+}
+
+fn default_cursor_trail_enabled() -> bool {
+    true
+}
+
+fn default_keyboard_scale() -> f64 {
+    1.0
+}
+
+fn default_keyboard_fade_out() -> u64 {
+    2000
+}
+
+fn default_keyboard_width() -> f64 {
+    800.0
+}
+
+fn default_virtual_camera_width() -> u32 {
+    1280
+}
+
+fn default_virtual_camera_height() -> u32 {
+    720
+}
+
+fn default_virtual_camera_fps() -> u32 {
+    30
+}
+
+fn default_control_api_port() -> u16 {
+    crate::control::DEFAULT_PORT
+}
+
+fn default_hotkey_toggle_visibility() -> String {
+    "CommandOrControl+Shift+V".into()
+}
+
+fn default_hotkey_toggle_lock() -> String {
+    "CommandOrControl+Shift+L".into()
+}
+
+fn default_hotkey_open_settings() -> String {
+    "CommandOrControl+Shift+,".into()
+}
+
+fn default_hotkey_toggle_recording() -> String {
+    "CommandOrControl+Shift+R".into()
+}
+
+fn default_hotkey_next_profile() -> String {
+    "CommandOrControl+Shift+P".into()
+}
+
+fn default_beauty_smoothness() -> f64 {
+    30.0
+}
+
+fn default_beauty_brightness() -> f64 {
+    50.0
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            settings_version: migrate::CURRENT_VERSION,
+            selected_camera_id: None,
+            camera_priority: Vec::new(),
+            camera_overrides: Vec::new(),
+            shape: ShapePreset::Circle,
+            scale: 1.0,
+            mirror: true,
+            always_on_top: true,
+            click_through: false,
+            locked: false,
+            quality_mode: QualityMode::Auto,
+            beauty: false,
+            beauty_smoothness: 30.0,
+            beauty_brightness: 50.0,
+            locale: Locale::default(),
+            theme: Theme::System,
+            keyboard_display_enabled: true,
+            keyboard_display_position: KeyboardDisplayPosition::BottomCenter,
+            keyboard_display_scale: 1.0,
+            keyboard_display_fade_out: 2000,
+            keyboard_display_width: 800.0,
+            keyboard_display_style: KeyboardDisplayStyle::Dark,
+            recording_enabled: true,
+            recording_fps: 30,
+            recording_cursor_overlay: true,
+            recording_auto_zoom: false,
+            recording_zoom_factor: 2.0,
+            cursor_effect_style: CursorEffectStyle::Ripple,
+            cursor_trail_enabled: true,
+            recording_output_dir: None,
+            drafts_dir: None,
+            control_api_enabled: false,
+            control_api_port: crate::control::DEFAULT_PORT,
+            control_api_obs_compat: false,
+            hotkey_toggle_visibility: default_hotkey_toggle_visibility(),
+            hotkey_toggle_lock: default_hotkey_toggle_lock(),
+            hotkey_open_settings: default_hotkey_open_settings(),
+            hotkey_toggle_recording: default_hotkey_toggle_recording(),
+            hotkey_toggle_pause: String::new(),
+            hotkey_toggle_keyboard: String::new(),
+            hotkey_select_region: String::new(),
+            hotkey_cycle_shape: String::new(),
+            hotkey_next_profile: default_hotkey_next_profile(),
+            hotkey_hold_to_show: String::new(),
+            hotkey_hold_to_record: String::new(),
+            virtual_camera_enabled: false,
+            virtual_camera_device: None,
+            virtual_camera_width: 1280,
+            virtual_camera_height: 720,
+            virtual_camera_fps: 30,
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Partial settings updates.
+//!
+//! A patch is a JSON object of camelCase keys to new values, merged over the
+//! current settings key by key (a one-level JSON merge patch: lists, such
+//! as the camera overrides, are replaced whole). `null` clears an optional
+//! setting. The merged result is parsed and validated exactly like a full
+//! save, so a patch can never store anything `save_app_settings` would
+//! reject.
+
+use serde_json::{Map, Value};
+
+use super::validate::{self, FieldError, SettingsError};
+use super::AppSettings;
+
+/// Not settable through a patch: stamped by the backend on every write.
+const READ_ONLY: &[&str] = &["settingsVersion"];
+
+fn to_map(settings: &AppSettings) -> Map<String, Value> {
+    match serde_json::to_value(settings) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Merge `patch` over `current`. Unknown or read-only keys, and values that
+/// do not parse or are out of range, are all reported by name.
+pub fn apply(current: &AppSettings, patch: &Value) -> Result<AppSettings, SettingsError> {
+    let Some(patch) = patch.as_object() else {
+        return Err("settings patch must be a JSON object".to_string().into());
+    };
+    let mut merged = to_map(current);
+    let unknown: Vec<FieldError> = patch
+        .keys()
+        .filter(|key| READ_ONLY.contains(&key.as_str()) || !merged.contains_key(*key))
+        .map(|key| FieldError {
+            field: key.clone(),
+            message: "not a setting that can be changed".into(),
+        })
+        .collect();
+    if !unknown.is_empty() {
+        return Err(SettingsError::fields(unknown));
+    }
+    merged.extend(patch.clone());
+    validate::parse(&Value::Object(merged))
+}
+
+/// camelCase keys whose value differs between `before` and `after`, sorted.
+pub fn changed_keys(before: &AppSettings, after: &AppSettings) -> Vec<String> {
+    let before = to_map(before);
+    let mut keys: Vec<String> = to_map(after)
+        .into_iter()
+        .filter(|(key, value)| !READ_ONLY.contains(&key.as_str()) && before.get(key) != Some(value))
+        .map(|(key, _)| key)
+        .collect();
+    keys.sort();
+    keys
+}
+
+/// Whether a settings write that changes no setting is still broadcast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Broadcast {
+    /// Only writes that change something; a no-op save stays quiet.
+    IfChanged,
+    /// Every write, e.g. a profile switch: it also moves windows and the
+    /// recording region, which windows hear about through the broadcast.
+    Always,
+}
+
+impl Broadcast {
+    /// Whether a write that changed `changed` is broadcast.
+    pub fn wanted(self, changed: &[String]) -> bool {
+        self == Broadcast::Always || !changed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merges_only_the_given_keys() {
+        let current = AppSettings {
+            always_on_top: false,
+            recording_fps: 60,
+            selected_camera_id: Some("cam".into()),
+            ..AppSettings::default()
+        };
+        let next = apply(&current, &json!({ "alwaysOnTop": true, "selectedCameraId": null })).unwrap();
+        assert!(next.always_on_top);
+        assert_eq!(next.selected_camera_id, None);
+        assert_eq!(next.recording_fps, 60);
+        assert_eq!(changed_keys(&current, &next), ["alwaysOnTop", "selectedCameraId"]);
+        assert!(changed_keys(&next, &next).is_empty());
+    }
+
+    #[test]
+    fn rejects_unknown_read_only_and_invalid_keys() {
+        let current = AppSettings::default();
+        let err = apply(&current, &json!({ "alwaysOnTopp": true, "settingsVersion": 9 })).unwrap_err();
+        let mut fields: Vec<_> = err.fields.iter().map(|f| f.field.as_str()).collect();
+        fields.sort();
+        assert_eq!(fields, ["alwaysOnTopp", "settingsVersion"]);
+
+        let err = apply(&current, &json!({ "recordingFps": 500, "theme": "neon" })).unwrap_err();
+        let mut fields: Vec<_> = err.fields.iter().map(|f| f.field.as_str()).collect();
+        fields.sort();
+        assert_eq!(fields, ["recordingFps", "theme"]);
+
+        assert!(apply(&current, &json!([1, 2])).is_err());
+    }
+}
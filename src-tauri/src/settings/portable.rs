@@ -0,0 +1,373 @@
+//! Portable settings files: the live setup (a profile [`Snapshot`]) written
+//! to JSON or TOML so it can be carried to another machine and read back.
+//!
+//! Reading is tolerant in the same way as loading the store: the settings
+//! go through `migrate::load`, so a file exported by an older build
+//! upgrades and bad fields fall back with a warning. Values that only make
+//! sense on the machine that wrote the file (camera ids, directories,
+//! window positions) are checked by [`localize`] and replaced by the
+//! current machine's values when they do not fit.
+
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::migrate::FieldWarning;
+use super::profiles::{Snapshot, WindowPosition, WindowPositions};
+use crate::RecordingRegion;
+
+/// Written as `format`; a file without it is not a settings export.
+pub const FORMAT_TAG: &str = "floaty-settings";
+/// Layout of the file around the settings blob (which has its own version).
+pub const FILE_VERSION: u32 = 1;
+
+/// Size assumed for a window when checking whether a saved origin is still
+/// on a screen; matches the fallback used when restoring positions.
+const NOMINAL_WINDOW_SIZE: f64 = 100.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Json,
+    Toml,
+}
+
+impl FileFormat {
+    /// TOML for a `.toml` extension, JSON for anything else.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("toml") => FileFormat::Toml,
+            _ => FileFormat::Json,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportFile<'a> {
+    format: &'static str,
+    file_version: u32,
+    app_version: &'a str,
+    #[serde(flatten)]
+    snapshot: &'a Snapshot,
+}
+
+pub fn encode(snapshot: &Snapshot, app_version: &str, format: FileFormat) -> Result<String, String> {
+    let file = ExportFile {
+        format: FORMAT_TAG,
+        file_version: FILE_VERSION,
+        app_version,
+        snapshot,
+    };
+    match format {
+        FileFormat::Json => serde_json::to_string_pretty(&file).map_err(|e| e.to_string()),
+        FileFormat::Toml => toml::to_string_pretty(&file).map_err(|e| e.to_string()),
+    }
+}
+
+/// A decoded file, before [`localize`].
+#[derive(Debug, Clone)]
+pub struct Decoded {
+    pub snapshot: Snapshot,
+    pub warnings: Vec<FieldWarning>,
+}
+
+/// Parse a settings file. Fails only when the text is not a settings export
+/// at all; problems with individual values are returned as warnings.
+pub fn decode(text: &str, format: FileFormat) -> Result<Decoded, String> {
+    let value: Value = match format {
+        FileFormat::Json => serde_json::from_str(text).map_err(|e| e.to_string())?,
+        FileFormat::Toml => toml::from_str(text).map_err(|e| e.to_string())?,
+    };
+    let Some(map) = value.as_object() else {
+        return Err("not a Floaty settings file".into());
+    };
+    if map.get("format").and_then(Value::as_str) != Some(FORMAT_TAG) {
+        return Err("not a Floaty settings file".into());
+    }
+    let Some(settings) = map.get("settings") else {
+        return Err("settings file has no settings".into());
+    };
+
+    let mut warnings = Vec::new();
+    if let Some(v) = map.get("fileVersion").and_then(Value::as_u64) {
+        if v > FILE_VERSION as u64 {
+            warnings.push(FieldWarning {
+                field: "fileVersion".into(),
+                message: format!("written by a newer version ({} > {}); unknown values are ignored", v, FILE_VERSION),
+            });
+        }
+    }
+    let loaded = super::migrate::load(settings);
+    warnings.extend(loaded.warnings);
+    let positions: WindowPositions = optional(map, "positions", &mut warnings).unwrap_or_default();
+    let recording_region: Option<RecordingRegion> = optional(map, "recordingRegion", &mut warnings);
+
+    Ok(Decoded {
+        snapshot: Snapshot {
+            settings: loaded.settings,
+            positions,
+            recording_region,
+        },
+        warnings,
+    })
+}
+
+/// Deserialize an optional part of the file; one that does not parse is
+/// dropped with a warning.
+fn optional<T: serde::de::DeserializeOwned>(
+    map: &Map<String, Value>,
+    key: &str,
+    warnings: &mut Vec<FieldWarning>,
+) -> Option<T> {
+    let value = map.get(key)?.clone();
+    serde_json::from_value(value)
+        .map_err(|e| {
+            warnings.push(FieldWarning {
+                field: key.into(),
+                message: e.to_string(),
+            })
+        })
+        .ok()
+}
+
+/// What the importing machine has, for [`localize`].
+pub struct Machine<'a> {
+    /// Camera device ids currently available.
+    pub camera_ids: &'a [String],
+    pub dir_exists: &'a dyn Fn(&str) -> bool,
+    /// Whether a rectangle (x, y, width, height) overlaps any screen.
+    pub on_screen: &'a dyn Fn(f64, f64, f64, f64) -> bool,
+}
+
+/// Replace the machine-specific values in `incoming` that do not fit this
+/// machine with the live ones from `current`, and say which. The drafts
+/// directory is never imported: changing it means moving the drafts. Nor is
+/// the virtual camera device: the same `/dev/videoN` may be any camera on
+/// this machine.
+pub fn localize(incoming: &mut Snapshot, current: &Snapshot, machine: &Machine) -> Vec<FieldWarning> {
+    let mut warnings = Vec::new();
+    let mut keep = |field: &str, message: String| {
+        warnings.push(FieldWarning {
+            field: field.into(),
+            message: format!("{}; keeping this machine's value", message),
+        })
+    };
+
+    let s = &mut incoming.settings;
+    if s.drafts_dir != current.settings.drafts_dir {
+        if let Some(dir) = &s.drafts_dir {
+            keep("draftsDir", format!("drafts directory {} is not imported", dir));
+        }
+        s.drafts_dir = current.settings.drafts_dir.clone();
+    }
+    if s.virtual_camera_device != current.settings.virtual_camera_device {
+        if let Some(device) = &s.virtual_camera_device {
+            keep("virtualCameraDevice", format!("virtual camera device {} is not imported", device));
+        }
+        s.virtual_camera_device = current.settings.virtual_camera_device.clone();
+    }
+    if let Some(id) = &s.selected_camera_id {
+        if !machine.camera_ids.contains(id) {
+            keep("selectedCameraId", format!("camera {} is not connected", id));
+            s.selected_camera_id = current.settings.selected_camera_id.clone();
+        }
+    }
+    if let Some(dir) = &s.recording_output_dir {
+        if !(machine.dir_exists)(dir) {
+            keep("recordingOutputDir", format!("folder {} does not exist", dir));
+            s.recording_output_dir = current.settings.recording_output_dir.clone();
+        }
+    }
+
+    let positions = [
+        ("positions.main", &mut incoming.positions.main, current.positions.main),
+        ("positions.keyboard", &mut incoming.positions.keyboard, current.positions.keyboard),
+        ("positions.recording", &mut incoming.positions.recording, current.positions.recording),
+    ];
+    for (field, pos, current_pos) in positions {
+        if let Some(WindowPosition { x, y }) = *pos {
+            if !(machine.on_screen)(x, y, NOMINAL_WINDOW_SIZE, NOMINAL_WINDOW_SIZE) {
+                keep(field, format!("({}, {}) is off-screen", x, y));
+                *pos = current_pos;
+            }
+        }
+    }
+    if let Some(r) = incoming.recording_region {
+        let (x, y, w, h) = (r.x as f64, r.y as f64, r.width as f64, r.height as f64);
+        if !(machine.on_screen)(x, y, w, h) {
+            keep("recordingRegion", format!("{}x{} at ({}, {}) is off-screen", r.width, r.height, r.x, r.y));
+            incoming.recording_region = current.recording_region;
+        }
+    }
+    warnings
+}
+
+/// One value an import would change, for the preview.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Change {
+    /// camelCase settings key, or `positions.<window>` / `recordingRegion`.
+    pub key: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+/// Every value that differs between `current` and `incoming`, settings
+/// first in stored key order.
+pub fn diff(current: &Snapshot, incoming: &Snapshot) -> Vec<Change> {
+    let mut changes = Vec::new();
+    let mut push = |key: String, from: Value, to: Value| {
+        if from != to {
+            changes.push(Change { key, from, to });
+        }
+    };
+
+    let as_map = |s: &Snapshot| match serde_json::to_value(&s.settings) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    };
+    let (from, mut to) = (as_map(current), as_map(incoming));
+    for (key, old) in from {
+        if key == "settingsVersion" {
+            continue;
+        }
+        let new = to.remove(&key).unwrap_or(Value::Null);
+        push(key, old, new);
+    }
+
+    for (name, old, new) in [
+        ("main", current.positions.main, incoming.positions.main),
+        ("keyboard", current.positions.keyboard, incoming.positions.keyboard),
+        ("recording", current.positions.recording, incoming.positions.recording),
+    ] {
+        push(format!("positions.{}", name), to_json(&old), to_json(&new));
+    }
+    push(
+        "recordingRegion".into(),
+        to_json(&current.recording_region),
+        to_json(&incoming.recording_region),
+    );
+    changes
+}
+
+fn to_json<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{AppSettings, Theme};
+
+    fn snapshot() -> Snapshot {
+        Snapshot {
+            settings: AppSettings {
+                theme: Theme::Dark,
+                recording_fps: 60,
+                selected_camera_id: Some("cam-a".into()),
+                recording_output_dir: Some("/home/a/Videos".into()),
+                drafts_dir: Some("/home/a/drafts".into()),
+                virtual_camera_device: Some("/dev/video10".into()),
+                ..AppSettings::default()
+            },
+            positions: WindowPositions {
+                main: Some(WindowPosition { x: 100.0, y: 80.0 }),
+                keyboard: Some(WindowPosition { x: 3000.0, y: 200.0 }),
+                recording: None,
+            },
+            recording_region: Some(RecordingRegion {
+                x: 0,
+                y: 0,
+                width: 1280,
+                height: 720,
+            }),
+        }
+    }
+
+    #[test]
+    fn round_trips_json_and_toml() {
+        for format in [FileFormat::Json, FileFormat::Toml] {
+            let text = encode(&snapshot(), "0.3.1", format).unwrap();
+            let decoded = decode(&text, format).unwrap();
+            assert!(decoded.warnings.is_empty(), "{:?}", decoded.warnings);
+            assert!(diff(&snapshot(), &decoded.snapshot).is_empty(), "{:?}", format);
+        }
+        assert_eq!(FileFormat::from_path(Path::new("a/b.TOML")), FileFormat::Toml);
+        assert_eq!(FileFormat::from_path(Path::new("a/b.txt")), FileFormat::Json);
+    }
+
+    #[test]
+    fn rejects_other_files_and_tolerates_bad_values() {
+        assert!(decode("{\"theme\": \"dark\"}", FileFormat::Json).is_err());
+        assert!(decode("not json", FileFormat::Json).is_err());
+
+        // An unversioned settings blob upgrades; bad parts are dropped.
+        let text = r#"{
+            "format": "floaty-settings",
+            "settings": { "theme": "neon", "recordingFps": 45, "qualityMode": "high" },
+            "positions": { "main": "top-left" }
+        }"#;
+        let decoded = decode(text, FileFormat::Json).unwrap();
+        let fields: Vec<_> = decoded.warnings.iter().map(|w| w.field.as_str()).collect();
+        assert_eq!(fields, ["theme", "positions"]);
+        let s = &decoded.snapshot.settings;
+        assert_eq!(s.recording_fps, 45);
+        assert_eq!(s.theme, Theme::System);
+        assert_eq!(decoded.snapshot.positions, WindowPositions::default());
+    }
+
+    #[test]
+    fn localize_keeps_values_that_do_not_fit() {
+        let current = Snapshot {
+            settings: AppSettings {
+                selected_camera_id: Some("cam-b".into()),
+                drafts_dir: None,
+                virtual_camera_device: Some("/dev/video4".into()),
+                ..AppSettings::default()
+            },
+            positions: WindowPositions {
+                keyboard: Some(WindowPosition { x: 500.0, y: 500.0 }),
+                ..WindowPositions::default()
+            },
+            recording_region: None,
+        };
+        let cameras = ["cam-b".to_string()];
+        let machine = Machine {
+            camera_ids: &cameras,
+            dir_exists: &|dir| dir == "/home/a/Videos",
+            // A single 1920x1080 screen.
+            on_screen: &|x, y, w, h| x < 1920.0 && x + w > 0.0 && y < 1080.0 && y + h > 0.0,
+        };
+        let mut incoming = snapshot();
+        let warnings = localize(&mut incoming, &current, &machine);
+        let fields: Vec<_> = warnings.iter().map(|w| w.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            ["draftsDir", "virtualCameraDevice", "selectedCameraId", "positions.keyboard"]
+        );
+
+        let s = &incoming.settings;
+        assert_eq!(s.drafts_dir, None);
+        assert_eq!(s.virtual_camera_device.as_deref(), Some("/dev/video4"));
+        assert_eq!(s.selected_camera_id.as_deref(), Some("cam-b"));
+        assert_eq!(s.recording_output_dir.as_deref(), Some("/home/a/Videos"));
+        assert_eq!(incoming.positions.main, Some(WindowPosition { x: 100.0, y: 80.0 }));
+        assert_eq!(incoming.positions.keyboard, current.positions.keyboard);
+        assert!(incoming.recording_region.is_some());
+    }
+
+    #[test]
+    fn diff_lists_changed_values_only() {
+        let current = Snapshot {
+            settings: AppSettings::default(),
+            positions: WindowPositions::default(),
+            recording_region: None,
+        };
+        let keys: Vec<_> = diff(&current, &snapshot()).into_iter().map(|c| c.key).collect();
+        for key in ["theme", "recordingFps", "positions.main", "positions.keyboard", "recordingRegion"] {
+            assert!(keys.iter().any(|k| k == key), "{} missing from {:?}", key, keys);
+        }
+        assert!(!keys.iter().any(|k| k == "scale" || k == "positions.recording" || k == "settingsVersion"));
+    }
+}
@@ -0,0 +1,372 @@
+//! Named settings profiles ("live stream", "tutorial", "meeting", ...).
+//!
+//! A profile is a [`Snapshot`] of everything that makes up a setup: the full
+//! `AppSettings`, the overlay window positions and the recording region.
+//! The live store keys always hold the *active* profile's state; switching
+//! first saves the live state back into the outgoing profile, so edits made
+//! while a profile is active are never lost. This module only does the
+//! bookkeeping — reading and applying the live state happens in `lib.rs`.
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::AppSettings;
+use crate::RecordingRegion;
+
+/// Id of the profile created implicitly from the existing setup the first
+/// time profiles are used.
+pub const DEFAULT_PROFILE_ID: &str = "default";
+
+const MAX_NAME_LEN: usize = 64;
+
+/// A window origin in physical pixels, as saved under the position keys.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct WindowPosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Saved origins of the windows that remember where they were.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowPositions {
+    #[serde(default)]
+    pub main: Option<WindowPosition>,
+    #[serde(default)]
+    pub keyboard: Option<WindowPosition>,
+    #[serde(default)]
+    pub recording: Option<WindowPosition>,
+}
+
+/// Everything a profile restores.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    /// Loaded through `migrate::load`, so a profile saved by an older build
+    /// upgrades like the live settings instead of failing the whole list.
+    #[serde(deserialize_with = "deserialize_settings")]
+    pub settings: AppSettings,
+    #[serde(default)]
+    pub positions: WindowPositions,
+    #[serde(default)]
+    pub recording_region: Option<RecordingRegion>,
+}
+
+impl Snapshot {
+    /// The settings restoring this snapshot makes live over `live`. The
+    /// drafts directory is left as it is: changing it means moving the
+    /// drafts (`move_drafts_dir`), same as for imports (`portable::localize`).
+    pub fn settings_over(&self, live: &AppSettings) -> AppSettings {
+        AppSettings {
+            drafts_dir: live.drafts_dir.clone(),
+            ..self.settings.clone()
+        }
+    }
+}
+
+fn deserialize_settings<'de, D: Deserializer<'de>>(d: D) -> Result<AppSettings, D::Error> {
+    let value = serde_json::Value::deserialize(d)?;
+    Ok(super::migrate::load(&value).settings)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+    pub snapshot: Snapshot,
+}
+
+/// All profiles plus which one the live state belongs to. Persisted as one
+/// value under its own store key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profiles {
+    #[serde(default)]
+    pub active: Option<String>,
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+}
+
+/// What the frontend and tray need: names and the active id, no snapshots.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilesSummary {
+    pub active: Option<String>,
+    pub profiles: Vec<ProfileSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSummary {
+    pub id: String,
+    pub name: String,
+}
+
+impl Profiles {
+    /// Make sure there is at least one profile and that one is active: the
+    /// first time profiles are used, the current setup (only captured then)
+    /// becomes the active "Default" profile.
+    pub fn ensure_default<E>(&mut self, current: impl FnOnce() -> Result<Snapshot, E>) -> Result<(), E> {
+        if self.profiles.is_empty() {
+            self.profiles.push(Profile {
+                id: DEFAULT_PROFILE_ID.into(),
+                name: "Default".into(),
+                snapshot: current()?,
+            });
+            self.active = Some(DEFAULT_PROFILE_ID.into());
+        }
+        if self.active.as_deref().is_none_or(|id| self.get(id).is_none()) {
+            self.active = self.profiles.first().map(|p| p.id.clone());
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.id == id)
+    }
+
+    fn get_mut(&mut self, id: &str) -> Result<&mut Profile, String> {
+        self.profiles
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| format!("no profile with id {}", id))
+    }
+
+    fn check_name(&self, name: &str, except: Option<&str>) -> Result<String, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("profile name must not be empty".into());
+        }
+        if name.chars().count() > MAX_NAME_LEN {
+            return Err(format!("profile name is longer than {} characters", MAX_NAME_LEN));
+        }
+        let taken = self
+            .profiles
+            .iter()
+            .any(|p| Some(p.id.as_str()) != except && p.name.to_lowercase() == name.to_lowercase());
+        if taken {
+            return Err(format!("a profile named \"{}\" already exists", name));
+        }
+        Ok(name.to_string())
+    }
+
+    /// Overwrite the active profile's snapshot with the live state.
+    pub fn store_active(&mut self, current: Snapshot) {
+        if let Some(id) = self.active.clone() {
+            if let Ok(profile) = self.get_mut(&id) {
+                profile.snapshot = current;
+            }
+        }
+    }
+
+    /// Add a profile holding `snapshot`. It does not become active.
+    pub fn create(&mut self, id: String, name: &str, snapshot: Snapshot) -> Result<(), String> {
+        let name = self.check_name(name, None)?;
+        if self.get(&id).is_some() {
+            return Err(format!("profile id {} is taken", id));
+        }
+        self.profiles.push(Profile { id, name, snapshot });
+        Ok(())
+    }
+
+    /// Copy `source`'s snapshot into a new profile. Call [`store_active`]
+    /// first when duplicating the active profile so the copy is current.
+    ///
+    /// [`store_active`]: Profiles::store_active
+    pub fn duplicate(&mut self, source: &str, id: String, name: &str) -> Result<(), String> {
+        let snapshot = self
+            .get(source)
+            .map(|p| p.snapshot.clone())
+            .ok_or_else(|| format!("no profile with id {}", source))?;
+        self.create(id, name, snapshot)
+    }
+
+    pub fn rename(&mut self, id: &str, name: &str) -> Result<(), String> {
+        let name = self.check_name(name, Some(id))?;
+        self.get_mut(id)?.name = name;
+        Ok(())
+    }
+
+    /// Remove a profile. The active profile cannot be deleted: switch away
+    /// first, so the live state always belongs to some profile.
+    pub fn delete(&mut self, id: &str) -> Result<(), String> {
+        if self.active.as_deref() == Some(id) {
+            return Err("cannot delete the active profile".into());
+        }
+        let before = self.profiles.len();
+        self.profiles.retain(|p| p.id != id);
+        if self.profiles.len() == before {
+            return Err(format!("no profile with id {}", id));
+        }
+        Ok(())
+    }
+
+    /// Switch to `id`: `current` (the live state) is saved into the
+    /// outgoing profile, and the snapshot to restore is returned.
+    pub fn activate(&mut self, id: &str, current: Snapshot) -> Result<Snapshot, String> {
+        let target = self
+            .get(id)
+            .map(|p| p.snapshot.clone())
+            .ok_or_else(|| format!("no profile with id {}", id))?;
+        if self.active.as_deref() == Some(id) {
+            self.store_active(current.clone());
+            return Ok(current);
+        }
+        self.store_active(current);
+        self.active = Some(id.to_string());
+        Ok(target)
+    }
+
+    /// The profile after the active one, wrapping around (for the hotkey).
+    pub fn next_id(&self) -> Option<String> {
+        let pos = self
+            .active
+            .as_deref()
+            .and_then(|id| self.profiles.iter().position(|p| p.id == id))
+            .map_or(0, |i| i + 1);
+        self.profiles
+            .get(pos % self.profiles.len().max(1))
+            .map(|p| p.id.clone())
+    }
+
+    pub fn summary(&self) -> ProfilesSummary {
+        ProfilesSummary {
+            active: self.active.clone(),
+            profiles: self
+                .profiles
+                .iter()
+                .map(|p| ProfileSummary {
+                    id: p.id.clone(),
+                    name: p.name.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::patch::{changed_keys, Broadcast};
+
+    fn snapshot(fps: u32) -> Snapshot {
+        Snapshot {
+            settings: AppSettings {
+                recording_fps: fps,
+                ..AppSettings::default()
+            },
+            positions: WindowPositions {
+                main: Some(WindowPosition { x: 10.0, y: 20.0 }),
+                ..WindowPositions::default()
+            },
+            recording_region: None,
+        }
+    }
+
+    fn setup() -> Profiles {
+        let mut p = Profiles::default();
+        p.ensure_default(|| Ok::<_, String>(snapshot(30))).unwrap();
+        p.create("live".into(), "Live stream", snapshot(60)).unwrap();
+        p
+    }
+
+    #[test]
+    fn first_use_creates_active_default() {
+        let p = setup();
+        assert_eq!(p.active.as_deref(), Some(DEFAULT_PROFILE_ID));
+        assert_eq!(p.summary().profiles.len(), 2);
+    }
+
+    #[test]
+    fn activate_saves_outgoing_live_state() {
+        let mut p = setup();
+        // The user changed fps to 45 while "default" was active.
+        let target = p.activate("live", snapshot(45)).unwrap();
+        assert_eq!(target.settings.recording_fps, 60);
+        assert_eq!(p.active.as_deref(), Some("live"));
+        assert_eq!(p.get(DEFAULT_PROFILE_ID).unwrap().snapshot.settings.recording_fps, 45);
+
+        // Re-activating the active profile keeps the live state.
+        let same = p.activate("live", snapshot(50)).unwrap();
+        assert_eq!(same.settings.recording_fps, 50);
+        assert!(p.activate("missing", snapshot(30)).is_err());
+    }
+
+    #[test]
+    fn names_are_trimmed_and_unique() {
+        let mut p = setup();
+        assert!(p.create("x".into(), "  live STREAM ", snapshot(30)).is_err());
+        assert!(p.create("x".into(), "   ", snapshot(30)).is_err());
+        p.rename("live", " Streaming ").unwrap();
+        assert_eq!(p.get("live").unwrap().name, "Streaming");
+        // Renaming to its own name (different case) is fine.
+        p.rename("live", "streaming").unwrap();
+        assert!(p.rename(DEFAULT_PROFILE_ID, "Streaming").is_err());
+    }
+
+    #[test]
+    fn duplicate_and_delete() {
+        let mut p = setup();
+        p.duplicate("live", "copy".into(), "Live copy").unwrap();
+        assert_eq!(p.get("copy").unwrap().snapshot.settings.recording_fps, 60);
+        assert!(p.delete(DEFAULT_PROFILE_ID).is_err());
+        p.delete("copy").unwrap();
+        assert!(p.delete("copy").is_err());
+    }
+
+    #[test]
+    fn stored_profiles_survive_bad_settings() {
+        let mut value = serde_json::to_value(setup()).unwrap();
+        value["profiles"][1]["snapshot"]["settings"]["theme"] = serde_json::json!("neon");
+        let p: Profiles = serde_json::from_value(value).unwrap();
+        let live = &p.get("live").unwrap().snapshot.settings;
+        assert_eq!(live.theme, crate::settings::Theme::System);
+        assert_eq!(live.recording_fps, 60);
+    }
+
+    #[test]
+    fn restoring_keeps_the_drafts_directory() {
+        let mut saved = snapshot(60);
+        saved.settings.drafts_dir = Some("/old/drafts".into());
+        let live = AppSettings {
+            drafts_dir: Some("/new/drafts".into()),
+            ..AppSettings::default()
+        };
+        let restored = saved.settings_over(&live);
+        assert_eq!(restored.drafts_dir.as_deref(), Some("/new/drafts"));
+        assert_eq!(restored.recording_fps, 60);
+    }
+
+    #[test]
+    fn switching_to_identical_settings_still_broadcasts() {
+        let mut p = Profiles::default();
+        p.ensure_default(|| Ok::<_, String>(snapshot(30))).unwrap();
+        let mut elsewhere = snapshot(30);
+        elsewhere.positions.main = Some(WindowPosition { x: 300.0, y: 400.0 });
+        p.create("same".into(), "Same settings", elsewhere).unwrap();
+
+        let live = snapshot(30);
+        let target = p.activate("same", live.clone()).unwrap();
+        assert_eq!(target.positions.main, Some(WindowPosition { x: 300.0, y: 400.0 }));
+        let changed = changed_keys(&live.settings, &target.settings_over(&live.settings));
+        assert!(changed.is_empty());
+        assert!(Broadcast::Always.wanted(&changed));
+        assert!(!Broadcast::IfChanged.wanted(&changed));
+    }
+
+    #[test]
+    fn next_wraps_around() {
+        let mut p = setup();
+        assert_eq!(p.next_id().as_deref(), Some("live"));
+        p.activate("live", snapshot(30)).unwrap();
+        assert_eq!(p.next_id().as_deref(), Some(DEFAULT_PROFILE_ID));
+    }
+
+    #[test]
+    fn dangling_active_is_repaired() {
+        let mut p = setup();
+        p.active = Some("gone".into());
+        p.ensure_default(|| Err("not captured again")).unwrap();
+        assert_eq!(p.active.as_deref(), Some(DEFAULT_PROFILE_ID));
+    }
+}
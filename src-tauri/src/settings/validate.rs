@@ -0,0 +1,298 @@
+//! The single validation pass for `AppSettings`.
+//!
+//! Enum-valued settings are checked by their types; this module covers the
+//! numeric ranges, which mirror the sliders in `SettingsWindow.tsx`, the
+//! per-camera overrides (same ranges), the locale (which must have a
+//! locale file, see `i18n`) and the hotkey bindings (see
+//! `hotkeys::resolve`). Saving
+//! rejects out-of-range values with field-level errors; loading resets them
+//! to their defaults (see `migrate::load`).
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::AppSettings;
+
+/// One invalid setting, keyed by its camelCase name as stored.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Error returned by settings writes. `fields` is empty for failures that
+/// are not about a particular setting (e.g. the store could not be saved).
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsError {
+    pub message: String,
+    pub fields: Vec<FieldError>,
+    /// Set when the write was based on an older revision than the stored
+    /// settings and was rejected; the caller should reload and retry.
+    pub current_revision: Option<u64>,
+}
+
+impl SettingsError {
+    pub fn fields(fields: Vec<FieldError>) -> Self {
+        let message = fields
+            .iter()
+            .map(|f| format!("{}: {}", f.field, f.message))
+            .collect::<Vec<_>>()
+            .join("; ");
+        Self {
+            message,
+            fields,
+            current_revision: None,
+        }
+    }
+
+    pub fn stale(expected: u64, current: u64) -> Self {
+        Self {
+            message: format!("settings changed since revision {} (now {})", expected, current),
+            fields: Vec::new(),
+            current_revision: Some(current),
+        }
+    }
+}
+
+impl From<String> for SettingsError {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            fields: Vec::new(),
+            current_revision: None,
+        }
+    }
+}
+
+impl From<SettingsError> for String {
+    fn from(err: SettingsError) -> Self {
+        err.message
+    }
+}
+
+impl std::fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+struct Range {
+    field: &'static str,
+    min: f64,
+    max: f64,
+    get: fn(&AppSettings) -> f64,
+}
+
+const RANGES: &[Range] = &[
+    Range { field: "scale", min: 0.6, max: 1.8, get: |s| s.scale },
+    Range { field: "beautySmoothness", min: 0.0, max: 100.0, get: |s| s.beauty_smoothness },
+    Range { field: "beautyBrightness", min: 0.0, max: 100.0, get: |s| s.beauty_brightness },
+    Range { field: "keyboardDisplayScale", min: 0.5, max: 2.0, get: |s| s.keyboard_display_scale },
+    Range { field: "keyboardDisplayFadeOut", min: 500.0, max: 5000.0, get: |s| s.keyboard_display_fade_out as f64 },
+    Range { field: "keyboardDisplayWidth", min: 400.0, max: 1400.0, get: |s| s.keyboard_display_width },
+    Range { field: "recordingFps", min: 15.0, max: 60.0, get: |s| s.recording_fps as f64 },
+    Range { field: "recordingZoomFactor", min: 1.5, max: 4.0, get: |s| s.recording_zoom_factor },
+    // Below 1024 needs elevated rights on most systems.
+    Range { field: "controlApiPort", min: 1024.0, max: 65535.0, get: |s| s.control_api_port as f64 },
+    Range { field: "virtualCameraWidth", min: 320.0, max: 1920.0, get: |s| s.virtual_camera_width as f64 },
+    Range { field: "virtualCameraHeight", min: 240.0, max: 1080.0, get: |s| s.virtual_camera_height as f64 },
+    Range { field: "virtualCameraFps", min: 5.0, max: 60.0, get: |s| s.virtual_camera_fps as f64 },
+];
+
+/// `maxHeight` of a camera override: 240p to 4K.
+const MAX_HEIGHTS: std::ops::RangeInclusive<u32> = 240..=2160;
+
+/// The camera overrides' problems, one message per bad entry (1-based).
+fn camera_override_errors(settings: &AppSettings) -> Vec<String> {
+    settings
+        .camera_overrides
+        .iter()
+        .enumerate()
+        .filter_map(|(i, o)| {
+            let out_of_range = [("beautySmoothness", o.beauty_smoothness), ("beautyBrightness", o.beauty_brightness)]
+                .into_iter()
+                .find_map(|(name, v)| Some((name, v?)).filter(|(_, v)| !(0.0..=100.0).contains(v)));
+            let problem = if o.pattern.trim().is_empty() {
+                Some("match is empty".to_string())
+            } else if let Some((name, v)) = out_of_range {
+                Some(format!("{} {} is outside 0..=100", name, v))
+            } else {
+                o.max_height
+                    .filter(|h| !MAX_HEIGHTS.contains(h))
+                    .map(|h| format!("maxHeight {} is outside {:?}", h, MAX_HEIGHTS))
+            };
+            problem.map(|p| format!("entry {}: {}", i + 1, p))
+        })
+        .collect()
+}
+
+/// Check every numeric setting against its range, the camera overrides,
+/// the locale against the catalog, and the hotkeys for bad or duplicate
+/// shortcuts. Empty = valid.
+pub fn validate(settings: &AppSettings) -> Vec<FieldError> {
+    let mut errors: Vec<FieldError> = RANGES
+        .iter()
+        .filter_map(|r| {
+            let v = (r.get)(settings);
+            (!(r.min..=r.max).contains(&v)).then(|| FieldError {
+                field: r.field.into(),
+                message: format!("{} is outside {}..={}", v, r.min, r.max),
+            })
+        })
+        .collect();
+    // YUYV carries chroma per pixel pair (see `camera::output`).
+    if !settings.virtual_camera_width.is_multiple_of(2) {
+        errors.push(FieldError {
+            field: "virtualCameraWidth".into(),
+            message: format!("{} is not even", settings.virtual_camera_width),
+        });
+    }
+    let overrides = camera_override_errors(settings);
+    if !overrides.is_empty() {
+        errors.push(FieldError {
+            field: "cameraOverrides".into(),
+            message: overrides.join("; "),
+        });
+    }
+    let locale = settings.locale.as_str();
+    if !crate::i18n::is_locale(locale) {
+        errors.push(FieldError {
+            field: "locale".into(),
+            message: format!(
+                "{:?} is not one of {}",
+                locale,
+                crate::i18n::locales().collect::<Vec<_>>().join(", ")
+            ),
+        });
+    }
+    errors.extend(crate::hotkeys::resolve(settings).1);
+    errors
+}
+
+/// Parse a settings payload from the frontend and validate it. Unlike
+/// loading, nothing falls back: every field that does not parse or is out
+/// of range is reported, and nothing is returned unless all are valid.
+/// Missing fields take their defaults, as when loading.
+pub fn parse(payload: &Value) -> Result<AppSettings, SettingsError> {
+    let Some(map) = payload.as_object() else {
+        return Err("settings must be a JSON object".to_string().into());
+    };
+    let (settings, type_errors) = super::migrate::merge_over_defaults(map.clone());
+    let mut errors: Vec<FieldError> = type_errors
+        .into_iter()
+        .map(|w| FieldError {
+            field: w.field,
+            message: w.message,
+        })
+        .collect();
+    // Range checks on the defaults substituted for bad fields are moot.
+    let range_errors: Vec<FieldError> = validate(&settings)
+        .into_iter()
+        .filter(|e| !errors.iter().any(|t| t.field == e.field))
+        .collect();
+    errors.extend(range_errors);
+    if errors.is_empty() {
+        Ok(settings)
+    } else {
+        Err(SettingsError::fields(errors))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_are_valid() {
+        assert!(validate(&AppSettings::default()).is_empty());
+    }
+
+    #[test]
+    fn reports_each_out_of_range_field() {
+        let s = AppSettings {
+            recording_fps: 240,
+            keyboard_display_fade_out: 0,
+            recording_zoom_factor: 1.5,
+            ..AppSettings::default()
+        };
+        let fields: Vec<_> = validate(&s).into_iter().map(|e| e.field).collect();
+        assert_eq!(fields, ["keyboardDisplayFadeOut", "recordingFps"]);
+    }
+
+    #[test]
+    fn parse_reports_type_and_range_errors() {
+        let mut payload = serde_json::to_value(AppSettings::default()).unwrap();
+        payload["theme"] = Value::from("neon");
+        payload["scale"] = Value::from(9.0);
+        let err = parse(&payload).unwrap_err();
+        let mut fields: Vec<_> = err.fields.iter().map(|e| e.field.as_str()).collect();
+        fields.sort();
+        assert_eq!(fields, ["scale", "theme"]);
+        assert!(err.message.contains("theme"));
+
+        payload["theme"] = Value::from("dark");
+        payload["scale"] = Value::from(1.0);
+        let s = parse(&payload).unwrap();
+        assert_eq!(s.theme, super::super::Theme::Dark);
+    }
+
+    #[test]
+    fn locale_needs_a_locale_file() {
+        let mut s = AppSettings {
+            locale: "zh-CN".into(),
+            ..AppSettings::default()
+        };
+        assert!(validate(&s).is_empty());
+        s.locale = "tlh".into();
+        let errors = validate(&s);
+        assert_eq!(errors[0].field, "locale");
+        assert!(errors[0].message.contains("en, zh-CN"), "{}", errors[0].message);
+    }
+
+    #[test]
+    fn camera_overrides_use_the_same_ranges() {
+        use super::super::CameraOverride;
+        let mut s = AppSettings {
+            camera_overrides: vec![CameraOverride {
+                pattern: "Logitech*".into(),
+                beauty_smoothness: Some(40.0),
+                max_height: Some(720),
+                ..CameraOverride::default()
+            }],
+            ..AppSettings::default()
+        };
+        assert!(validate(&s).is_empty());
+        s.camera_overrides.push(CameraOverride {
+            pattern: "FaceTime*".into(),
+            beauty_brightness: Some(150.0),
+            ..CameraOverride::default()
+        });
+        s.camera_overrides.push(CameraOverride::default());
+        let errors = validate(&s);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "cameraOverrides");
+        assert_eq!(
+            errors[0].message,
+            "entry 2: beautyBrightness 150 is outside 0..=100; entry 3: match is empty"
+        );
+    }
+
+    #[test]
+    fn virtual_camera_width_is_even() {
+        let s = AppSettings {
+            virtual_camera_width: 641,
+            virtual_camera_fps: 120,
+            ..AppSettings::default()
+        };
+        let fields: Vec<_> = validate(&s).into_iter().map(|e| e.field).collect();
+        assert_eq!(fields, ["virtualCameraFps", "virtualCameraWidth"]);
+    }
+
+    #[test]
+    fn parse_rejects_non_objects() {
+        let err = parse(&Value::Null).unwrap_err();
+        assert!(err.fields.is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+//! Pick up edits made to `settings.json` outside the app, by hand or by
+//! provisioning scripts.
+//!
+//! [`spawn`] watches the file's directory (editors and scripts usually
+//! replace the file rather than write it in place) and calls back once per
+//! burst of changes. [`classify`] then compares the settings blob in the
+//! file with the one the store holds in memory; that is also how the app's
+//! own saves are told apart from external edits, since right after a save
+//! the two are equal. A changed blob is loaded like the stored one at
+//! startup ([`migrate::load`]): older versions are upgraded and unusable
+//! fields fall back one by one. Only the settings blob is reloaded: other
+//! keys (window positions, profiles) are owned by the running app.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use notify::{RecursiveMode, Watcher};
+use serde_json::Value;
+
+use super::migrate::{self, Loaded};
+use super::AppSettings;
+
+/// Changes closer together than this are handled as one.
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+// Built once per file change and consumed straight away; boxing the
+// settings would buy nothing.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub enum ExternalEdit {
+    /// The file holds the blob the store already has (e.g. our own save).
+    Unchanged,
+    /// The file is not a JSON object; the live settings are kept.
+    Unreadable(String),
+    /// The blob changed: loaded through [`migrate::load`].
+    Settings(Loaded),
+}
+
+/// Decide what a change to the store file means. `in_memory` is the blob
+/// the store holds under `settings_key`, if any. A file without the blob
+/// resets to the defaults, as on a fresh install.
+pub fn classify(text: &str, in_memory: Option<&Value>, settings_key: &str) -> ExternalEdit {
+    let file = match serde_json::from_str::<Value>(text) {
+        Ok(Value::Object(map)) => map,
+        Ok(_) => return ExternalEdit::Unreadable("settings file is not a JSON object".into()),
+        Err(e) => return ExternalEdit::Unreadable(e.to_string()),
+    };
+    let blob = file.get(settings_key);
+    if blob == in_memory {
+        return ExternalEdit::Unchanged;
+    }
+    match blob {
+        Some(value) => ExternalEdit::Settings(migrate::load(value)),
+        None => ExternalEdit::Settings(Loaded {
+            settings: AppSettings::default(),
+            warnings: Vec::new(),
+            from_version: migrate::CURRENT_VERSION,
+        }),
+    }
+}
+
+/// Watch `path` on a background thread and call `on_change` after each
+/// burst of writes to it. Runs for the life of the process.
+pub fn spawn(path: PathBuf, on_change: impl Fn() + Send + 'static) -> Result<(), String> {
+    let dir = path
+        .parent()
+        .ok_or("settings file has no parent directory")?
+        .to_path_buf();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(|e| e.to_string())?;
+    watcher
+        .watch(&dir, RecursiveMode::NonRecursive)
+        .map_err(|e| e.to_string())?;
+    std::thread::spawn(move || {
+        // Dropping the watcher would end the watch.
+        let _watcher = watcher;
+        while let Ok(event) = rx.recv() {
+            if !touches(&event, &path) {
+                continue;
+            }
+            // Let the rest of the burst (write + rename, several writes) land.
+            while rx.recv_timeout(DEBOUNCE).is_ok() {}
+            on_change();
+        }
+    });
+    Ok(())
+}
+
+/// Whether `event` may have changed `path`. Compared by file name, since
+/// the directory can be reported through a different (e.g. symlinked) path.
+fn touches(event: &notify::Result<notify::Event>, path: &Path) -> bool {
+    match event {
+        Ok(event) => {
+            !event.kind.is_access() && event.paths.iter().any(|p| p.file_name() == path.file_name())
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const KEY: &str = "app_settings";
+
+    fn file(blob: &Value) -> String {
+        json!({ KEY: blob, "window_position": { "x": 1, "y": 2 } }).to_string()
+    }
+
+    #[test]
+    fn own_saves_are_unchanged() {
+        let blob = serde_json::to_value(AppSettings::default()).unwrap();
+        assert!(matches!(classify(&file(&blob), Some(&blob), KEY), ExternalEdit::Unchanged));
+        // Only the settings blob matters.
+        let other = json!({ KEY: blob, "window_position": null }).to_string();
+        assert!(matches!(classify(&other, Some(&blob), KEY), ExternalEdit::Unchanged));
+    }
+
+    #[test]
+    fn edits_fall_back_field_by_field() {
+        let stored = serde_json::to_value(AppSettings::default()).unwrap();
+        let mut edited = stored.clone();
+        edited["recordingFps"] = json!(60);
+        match classify(&file(&edited), Some(&stored), KEY) {
+            ExternalEdit::Settings(loaded) => {
+                assert_eq!(loaded.settings.recording_fps, 60);
+                assert!(loaded.warnings.is_empty());
+            }
+            other => panic!("{:?}", other),
+        }
+
+        edited["recordingFps"] = json!(999);
+        edited["theme"] = json!("dark");
+        match classify(&file(&edited), Some(&stored), KEY) {
+            ExternalEdit::Settings(loaded) => {
+                assert_eq!(loaded.settings.recording_fps, AppSettings::default().recording_fps);
+                assert_eq!(loaded.settings.theme, crate::settings::Theme::Dark);
+                assert_eq!(loaded.warnings[0].field, "recordingFps");
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn older_blobs_are_upgraded() {
+        let stored = serde_json::to_value(AppSettings::default()).unwrap();
+        let old: Value = serde_json::from_str(include_str!("fixtures/v0-recording.json")).unwrap();
+        match classify(&file(&old), Some(&stored), KEY) {
+            ExternalEdit::Settings(loaded) => {
+                assert_eq!(loaded.from_version, 0);
+                assert!(loaded.warnings.is_empty(), "{:?}", loaded.warnings);
+                assert_eq!(loaded.settings.recording_fps, 60);
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn broken_or_missing_blob() {
+        let stored = serde_json::to_value(AppSettings::default()).unwrap();
+        assert!(matches!(classify("{ \"app_settings\": ", Some(&stored), KEY), ExternalEdit::Unreadable(_)));
+        assert!(matches!(classify("[]", Some(&stored), KEY), ExternalEdit::Unreadable(_)));
+        assert!(matches!(classify("{}", Some(&stored), KEY), ExternalEdit::Settings(_)));
+        assert!(matches!(classify("{}", None, KEY), ExternalEdit::Unchanged));
+    }
+}
@@ -0,0 +1,186 @@
+//! What the tray shows: item labels (from the message catalog), the shape,
+//! size, camera and recent recording submenus, and the recording item and
+//! icon for the pipeline's state. The menu itself is built in `lib.rs`;
+//! this module only decides its text, so it can be tested without a tray.
+
+use crate::i18n;
+use crate::settings::{Locale, ShapePreset};
+
+/// Settings the menu's structure depends on (submenu check marks included):
+/// a change to any of them rebuilds it. Other state is applied in place.
+pub const MENU_KEYS: &[&str] = &["locale", "shape", "scale", "selectedCameraId"];
+
+pub const SHAPES: [ShapePreset; 3] = [ShapePreset::Circle, ShapePreset::RoundedSquare, ShapePreset::Mickey];
+
+/// The window sizes offered in the tray; the settings window's slider
+/// allows anything from 0.6 to 1.8.
+pub const SCALES: [f64; 4] = [0.75, 1.0, 1.25, 1.5];
+
+/// Tray labels in one locale, from the message catalog (`tray_*` keys).
+pub struct TrayText {
+    pub show_camera: &'static str,
+    pub open_settings: &'static str,
+    pub lock_drag: &'static str,
+    pub show_keys: &'static str,
+    pub click_through: &'static str,
+    pub always_on_top: &'static str,
+    pub start_recording: &'static str,
+    pub stop_recording: &'static str,
+    /// With `{elapsed}` where the time goes.
+    pub recording_elapsed: &'static str,
+    pub paused_elapsed: &'static str,
+    pub saving: &'static str,
+    pub profiles: &'static str,
+    pub shape: &'static str,
+    pub shape_circle: &'static str,
+    pub shape_rounded_square: &'static str,
+    pub shape_mickey: &'static str,
+    pub size: &'static str,
+    pub camera: &'static str,
+    pub no_cameras: &'static str,
+    pub recent_recordings: &'static str,
+    pub no_recordings: &'static str,
+    pub open: &'static str,
+    pub show_in_folder: &'static str,
+    pub quit: &'static str,
+}
+
+impl TrayText {
+    pub fn new(locale: &Locale) -> Self {
+        let t = |key| i18n::t(locale.as_str(), key);
+        Self {
+            show_camera: t("tray_show_camera"),
+            open_settings: t("tray_open_settings"),
+            lock_drag: t("tray_lock_drag"),
+            show_keys: t("tray_show_keys"),
+            click_through: t("tray_click_through"),
+            always_on_top: t("tray_always_on_top"),
+            start_recording: t("tray_start_recording"),
+            stop_recording: t("tray_stop_recording"),
+            recording_elapsed: t("tray_recording_elapsed"),
+            paused_elapsed: t("tray_paused_elapsed"),
+            saving: t("tray_saving"),
+            profiles: t("tray_profiles"),
+            shape: t("tray_shape"),
+            shape_circle: t("tray_shape_circle"),
+            shape_rounded_square: t("tray_shape_rounded_square"),
+            shape_mickey: t("tray_shape_mickey"),
+            size: t("tray_size"),
+            camera: t("tray_camera"),
+            no_cameras: t("tray_no_cameras"),
+            recent_recordings: t("tray_recent_recordings"),
+            no_recordings: t("tray_no_recordings"),
+            open: t("tray_open"),
+            show_in_folder: t("tray_show_in_folder"),
+            quit: t("tray_quit"),
+        }
+    }
+
+    pub fn shape_name(&self, shape: &ShapePreset) -> &'static str {
+        match shape {
+            ShapePreset::Circle => self.shape_circle,
+            ShapePreset::RoundedSquare => self.shape_rounded_square,
+            ShapePreset::Mickey => self.shape_mickey,
+        }
+    }
+}
+
+/// A size preset as shown, e.g. `125%`.
+pub fn scale_label(scale: f64) -> String {
+    format!("{}%", (scale * 100.0).round())
+}
+
+/// Whether the stored `scale` is the preset (the slider stores two
+/// decimals).
+pub fn is_scale(scale: f64, preset: f64) -> bool {
+    (scale - preset).abs() < 0.005
+}
+
+/// A recent recording's menu label: its file name.
+pub fn recording_label(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Which tray icon to show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Icon {
+    Idle,
+    Recording,
+    Paused,
+}
+
+/// The tray's view of the recording pipeline, from its last `RECORDING_UI`
+/// broadcast (`status` as in `RecordingUi`) and `RECORDING_STATUS`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    /// The recording item's label, e.g. `Stop Recording (1:23)`.
+    pub label: String,
+    /// Saving cannot be interrupted, so the item is disabled meanwhile.
+    pub enabled: bool,
+    pub icon: Icon,
+}
+
+impl Recording {
+    pub fn new(text: &TrayText, status: &str, active: bool, elapsed: f64) -> Self {
+        let (label, enabled, icon) = match status {
+            "countdown" => (text.stop_recording.to_string(), true, Icon::Recording),
+            "recording" if active => (
+                text.recording_elapsed.replace("{elapsed}", &format_elapsed(elapsed)),
+                true,
+                Icon::Recording,
+            ),
+            "paused" if active => (
+                text.paused_elapsed.replace("{elapsed}", &format_elapsed(elapsed)),
+                true,
+                Icon::Paused,
+            ),
+            "saving" => (text.saving.to_string(), false, Icon::Idle),
+            _ => (text.start_recording.to_string(), true, Icon::Idle),
+        };
+        Self { label, enabled, icon }
+    }
+}
+
+/// `m:ss` below an hour, `h:mm:ss` from then on.
+pub fn format_elapsed(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (h, m, s) = (total / 3600, total / 60 % 60, total % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn submenu_labels() {
+        assert_eq!(scale_label(1.25), "125%");
+        assert_eq!(scale_label(0.75), "75%");
+        assert!(is_scale(1.0, 1.0) && is_scale(1.249, 1.25) && !is_scale(1.3, 1.25));
+        assert_eq!(recording_label("/home/me/Videos/Floaty/floaty-1.mp4"), "floaty-1.mp4");
+        assert_eq!(TrayText::new(&Locale::default()).shape_name(&SHAPES[1]), "Rounded Square");
+    }
+
+    #[test]
+    fn recording_item_follows_the_pipeline() {
+        let text = TrayText::new(&Locale::default());
+        let idle = Recording::new(&text, "idle", false, 0.0);
+        assert_eq!((idle.label.as_str(), idle.icon), ("Start Recording", Icon::Idle));
+        assert_eq!(Recording::new(&text, "", false, 0.0), idle);
+
+        let recording = Recording::new(&text, "recording", true, 83.0);
+        assert_eq!((recording.label.as_str(), recording.icon), ("Stop Recording (1:23)", Icon::Recording));
+        let paused = Recording::new(&text, "paused", true, 3725.0);
+        assert_eq!((paused.label.as_str(), paused.icon), ("Stop Recording (Paused 1:02:05)", Icon::Paused));
+
+        assert!(!Recording::new(&text, "saving", false, 90.0).enabled);
+        assert_eq!(Recording::new(&text, "countdown", false, 0.0).icon, Icon::Recording);
+    }
+}
@@ -1,10 +1,10 @@
 import { useCallback, useEffect, useRef, useState } from "react";
 import { emit, listen } from "@tauri-apps/api/event";
 import { currentMonitor, primaryMonitor } from "@tauri-apps/api/window";
-import { EVT } from "../lib/events";
+import { EVT, type SettingsUpdatedPayload } from "../lib/events";
 import type { MousePayload, RecordingCmdPayload, RecordingUiPayload } from "../lib/events";
 import { computeCropRect } from "../lib/coords";
-import { getAppSettings, getRecordingRegion, openEditorWindow, saveRecordingDraft, saveRecordingMeta, setCursorOverlay, setMouseTrackingEnabled } from "../lib/tauri";
+import { checkDraftsSpace, getAppSettings, getRecordingRegion, openEditorWindow, saveRecordingDraft, saveRecordingMeta, setCursorOverlay, setMouseTrackingEnabled } from "../lib/tauri";
 import type { AppSettings, CursorSample, RecordingMeta, RecordingRegion } from "../types/app";
 
 export type RecordingStatus = "idle" | "countdown" | "recording" | "paused" | "saving";
@@ -103,6 +103,8 @@ export function useRecordingPipeline(): PipelineApi {
   const settingsRef = useRef<AppSettings | null>(null);
   const timerRef = useRef<ReturnType<typeof setInterval> | null>(null);
   const countdownTimerRef = useRef<ReturnType<typeof setInterval> | null>(null);
+  // Set by pause() during the countdown; the recorder then starts paused.
+  const pauseAfterCountdownRef = useRef(false);
   const startTimeRef = useRef<number>(0);
   // Latest status for use inside long-lived listeners (hotkey/tray) whose
   // effect runs once on mount; otherwise they would capture a stale status.
@@ -162,9 +164,9 @@ export function useRecordingPipeline(): PipelineApi {
   // React to settings / region / hotkey broadcasts. The hotkey & tray both
   // fan out via `app://hotkey-triggered { action: "toggle_recording" }`.
   useEffect(() => {
-    const unlisten1 = listen<AppSettings>(EVT.SETTINGS_UPDATED, (e) => {
-      setSettings(e.payload);
-      if (e.payload.locale) setLocale(e.payload.locale.startsWith("zh") ? "zh" : "en");
+    const unlisten1 = listen<SettingsUpdatedPayload>(EVT.SETTINGS_UPDATED, (e) => {
+      setSettings(e.payload.settings);
+      if (e.payload.settings.locale) setLocale(e.payload.settings.locale.startsWith("zh") ? "zh" : "en");
     });
     const unlisten2 = listen<RecordingRegion | null>(EVT.REGION_SELECTED, (e) => {
       setRegion(e.payload);
@@ -209,6 +211,14 @@ export function useRecordingPipeline(): PipelineApi {
   const start = useCallback(async () => {
     const s = settingsRef.current;
     const fps = s?.recordingFps ?? 30;
+    // Fail before the share picker rather than after a long take: the draft
+    // is written to the drafts volume when recording stops.
+    try {
+      await checkDraftsSpace();
+    } catch (err) {
+      setInfo(`${err}`);
+      return;
+    }
     // The capture contract is "primary monitor at physical pixels" (mouse
     // coords are converted with the primary scale factor too). The control
     // bar may sit on a secondary monitor, so currentMonitor() would size the
@@ -448,7 +458,15 @@ export function useRecordingPipeline(): PipelineApi {
       if (s?.recordingCursorOverlay) {
         void setCursorOverlay(true);
       }
-      void emit(EVT.RECORDING_STATUS, { active: true });
+      // A hold-to-record hotkey was released during the countdown: start
+      // paused, so nothing is captured until it is held again.
+      if (pauseAfterCountdownRef.current) {
+        pauseAfterCountdownRef.current = false;
+        recorder.pause();
+        setStatus("paused");
+        stopTimer();
+      }
+      void emit(EVT.RECORDING_STATUS, { active: true, paused: recorder.state === "paused" });
     }, 1000);
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [cleanupStream, locale]);
@@ -457,6 +475,7 @@ export function useRecordingPipeline(): PipelineApi {
     // Cancelling mid-countdown: the recorder never started, so just tear
     // down the stream and go back to idle without entering "saving".
     if (statusRef.current === "countdown") {
+      pauseAfterCountdownRef.current = false;
       cleanupStream();
       recorderRef.current = null;
       cursorTrailRef.current = [];
@@ -496,7 +515,7 @@ export function useRecordingPipeline(): PipelineApi {
 
       setStatus("idle");
       setElapsed(0);
-      void emit(EVT.RECORDING_STATUS, { active: false });
+      void emit(EVT.RECORDING_STATUS, { active: false, paused: false });
       if (settingsRef.current?.recordingCursorOverlay) {
         // Hiding the overlay also turns mouse tracking off internally.
         void setCursorOverlay(false);
@@ -509,7 +528,7 @@ export function useRecordingPipeline(): PipelineApi {
         // Hand the raw capture to the editor window (trim/export happens
         // there); the save dialog now lives in the editor's export flow.
         const buf = new Uint8Array(await blob.arrayBuffer());
-        const draftPath = await saveRecordingDraft(buf, extensionForMime(mime));
+        const draftId = await saveRecordingDraft(buf, extensionForMime(mime));
         // Persist the metadata sidecar so the editor can replay zoom. A
         // failure here is non-fatal — the video is already saved; the editor
         // just falls back to a no-zoom preview.
@@ -524,7 +543,7 @@ export function useRecordingPipeline(): PipelineApi {
             contentScale: contentScaleSnap,
             debug: captureDebugRef.current ?? undefined,
           };
-          await saveRecordingMeta(draftPath, meta).catch(() => undefined);
+          await saveRecordingMeta(draftId, meta).catch(() => undefined);
         }
         setInfo(locale === "zh" ? "正在打开编辑器…" : "Opening editor…");
         await openEditorWindow();
@@ -538,23 +557,40 @@ export function useRecordingPipeline(): PipelineApi {
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, [cleanupStream, locale, stopTimer]);
 
-  const togglePause = useCallback(() => {
-    const recorder = recorderRef.current;
-    if (!recorder) return;
-    if (recorder.state === "recording") {
-      recorder.pause();
-      setStatus("paused");
-      stopTimer();
-    } else if (recorder.state === "paused") {
-      startTimeRef.current = Date.now() - elapsedRef.current * 1000;
-      recorder.resume();
-      setStatus("recording");
-      timerRef.current = setInterval(() => {
-        setElapsed(Math.floor((Date.now() - startTimeRef.current) / 1000));
-      }, 500);
+  const pause = useCallback(() => {
+    if (statusRef.current === "countdown") {
+      pauseAfterCountdownRef.current = true;
+      return;
     }
+    const recorder = recorderRef.current;
+    if (recorder?.state !== "recording") return;
+    recorder.pause();
+    setStatus("paused");
+    stopTimer();
+    void emit(EVT.RECORDING_STATUS, { active: true, paused: true });
   }, [stopTimer]);
 
+  const resume = useCallback(() => {
+    pauseAfterCountdownRef.current = false;
+    const recorder = recorderRef.current;
+    if (recorder?.state !== "paused") return;
+    startTimeRef.current = Date.now() - elapsedRef.current * 1000;
+    recorder.resume();
+    setStatus("recording");
+    timerRef.current = setInterval(() => {
+      setElapsed(Math.floor((Date.now() - startTimeRef.current) / 1000));
+    }, 500);
+    void emit(EVT.RECORDING_STATUS, { active: true, paused: false });
+  }, []);
+
+  const togglePause = useCallback(() => {
+    if (recorderRef.current?.state === "paused") {
+      resume();
+    } else {
+      pause();
+    }
+  }, [pause, resume]);
+
   const toggle = useCallback(async () => {
     const current = statusRef.current;
     if (current === "recording" || current === "paused" || current === "countdown") {
@@ -599,6 +635,12 @@ export function useRecordingPipeline(): PipelineApi {
         case "toggle-pause":
           togglePause();
           break;
+        case "pause":
+          pause();
+          break;
+        case "resume":
+          resume();
+          break;
         case "clear-info":
           clearInfo();
           break;
@@ -617,7 +659,7 @@ export function useRecordingPipeline(): PipelineApi {
     return () => {
       unlisten.then((fn) => fn());
     };
-  }, [toggle, togglePause, clearInfo]);
+  }, [toggle, togglePause, pause, resume, clearInfo]);
 
   return {
     status,
@@ -0,0 +1,27 @@
+import { describe, it, expect } from "vitest";
+import { LOCALES, fallbackChain, getMessages, matchLocale } from "./index";
+import en from "./locales/en.json";
+
+const files = import.meta.glob<Record<string, string>>("./locales/*.json", { eager: true, import: "default" });
+
+describe("locale catalog", () => {
+  it("has every key in every locale", () => {
+    const keys = Object.keys(en).sort();
+    for (const [path, messages] of Object.entries(files)) {
+      expect(Object.keys(messages).sort(), path).toEqual(keys);
+    }
+    expect(LOCALES).toContain("en");
+  });
+
+  it("falls back to the base language, then English", () => {
+    expect(fallbackChain("fr-CA")).toEqual(["en"]);
+    expect(getMessages("fr-CA").tray_quit).toBe("Quit");
+    expect(getMessages("zh-CN").tray_quit).toBe("退出");
+  });
+
+  it("matches browser languages to locale files", () => {
+    expect(matchLocale("zh-CN")).toBe("zh-CN");
+    expect(matchLocale("zh-TW")).toBe("zh-CN");
+    expect(matchLocale("de-DE")).toBe("en");
+  });
+});
@@ -1,23 +1,61 @@
+/**
+ * UI messages. Each language is one flat JSON file in `locales/`, named by
+ * its tag (`en.json`, `zh-CN.json`). The Rust side reads the same files
+ * (`src-tauri/src/i18n.rs`) for the tray, window titles and dialogs, so a
+ * new language is just a new file.
+ */
 import { createContext, useContext } from "react";
-import { zhCN } from "./locales/zh-CN";
-import { en } from "./locales/en";
+import en from "./locales/en.json";
 
-export type Locale = "zh-CN" | "en";
-type Messages = typeof zhCN;
+/** A locale file's tag, e.g. `en` or `zh-CN`. */
+export type Locale = string;
+export type Messages = typeof en;
 
-const locales: Record<Locale, Messages> = { "zh-CN": zhCN, en };
+const FALLBACK = "en";
+
+const files = import.meta.glob<Partial<Messages>>("./locales/*.json", { eager: true, import: "default" });
+
+const catalog: Record<Locale, Partial<Messages>> = Object.fromEntries(
+  Object.entries(files).map(([path, messages]) => [path.replace(/^.*\/(.+)\.json$/, "$1"), messages]),
+);
+
+/** Every available locale, sorted by tag. */
+export const LOCALES: Locale[] = Object.keys(catalog).sort();
+
+/**
+ * The locales `locale` looks messages up in, most specific first: itself,
+ * its base language (`zh-TW` → `zh`), English. Mirrors `fallback_chain` in
+ * `i18n.rs`.
+ */
+export function fallbackChain(locale: Locale): Locale[] {
+  const chain = [locale, locale.split("-")[0], FALLBACK];
+  return chain.filter((tag, i) => tag in catalog && chain.indexOf(tag) === i);
+}
+
+/** The best available locale for a browser language tag. */
+export function matchLocale(tag: string): Locale {
+  if (tag in catalog) return tag;
+  const base = tag.split("-")[0];
+  return LOCALES.find((l) => l === base || l.startsWith(`${base}-`)) ?? FALLBACK;
+}
 
 export function detectLocale(): Locale {
-  const lang = navigator.language;
-  if (lang.startsWith("zh")) return "zh-CN";
-  return "en";
+  return matchLocale(navigator.language);
 }
 
+const resolved = new Map<Locale, Messages>();
+
 export function getMessages(locale: Locale): Messages {
-  return locales[locale] ?? locales.en;
+  let messages = resolved.get(locale);
+  if (!messages) {
+    const chain = fallbackChain(locale).reverse();
+    messages = Object.assign({}, ...chain.map((tag) => catalog[tag])) as Messages;
+    resolved.set(locale, messages);
+  }
+  return messages;
 }
 
-const I18nContext = createContext<Messages>(zhCN);
+const I18nContext = createContext<Messages>(en);
 
 export const I18nProvider = I18nContext.Provider;
 
@@ -1,129 +0,0 @@
-export const en = {
-  init_camera: "Initializing camera...",
-  no_camera_detected: "No camera detected",
-  media_devices_unavailable: "mediaDevices not supported, cannot access camera",
-  camera_connected: "Camera connected",
-  camera_access_denied: "Cannot access camera, please check permissions",
-  retry_camera: "Retry camera",
-  unlock_drag: "Unlock drag",
-  lock_drag: "Lock Drag",
-  mirror_on: "Mirror",
-  mirror_off: "Unmirror",
-  open_settings: "Open Settings",
-
-  settings_subtitle: "Adjust camera window shape, behavior, and visual feedback.",
-  camera: "Camera",
-  device_select: "Device",
-  select_camera_placeholder: "Select a camera",
-  shape_circle_desc: "Classic circle avatar",
-  shape_rounded_desc: "Rounded square, more stable",
-  shape_mickey_desc: "Main circle + ears, high recognition",
-  window_size: "Window Size",
-  current_prefix: "Current:",
-  behavior: "Behavior",
-  mirror: "Mirror",
-  mirror_hint: "Matches most streamers' expected viewpoint",
-  always_on_top: "Always on Top",
-  always_on_top_hint: "Stay above other windows during recording",
-  click_through: "Click Through",
-  click_through_hint: "Won't block mouse interaction when enabled",
-  lock_drag_hint: "Prevent accidental moves",
-  beauty: "Beauty",
-  enable_beauty: "Enable Beauty",
-  beauty_hint: "Smooth skin & brighten for streaming",
-  smoothness: "Smoothness",
-  brightness: "Brightness",
-  brightness_original_hint: "(50% is original brightness)",
-  hotkeys: "Hotkeys",
-  hotkey_toggle_visibility: "Show/Hide main window",
-  hotkey_toggle_lock: "Lock/Unlock drag",
-  hotkey_open_settings: "Open settings window",
-  focus_settings: "Focus Settings",
-  saving: "Saving...",
-  auto_saved: "Auto-saved",
-  appearance: "Appearance",
-  theme: "Theme",
-  theme_hint: "Follow the system or pin light/dark",
-  theme_system: "System",
-  theme_light: "Light",
-  theme_dark: "Dark",
-  language: "Language",
-  keyboard_display: "Keyboard Display",
-  keyboard_show: "Show Keys",
-  keyboard_show_hint: "Display pressed keys on screen",
-  keyboard_hide: "Hide Keys",
-  keyboard_open_settings: "Open Settings",
-  keyboard_fade_out: "Fade Delay",
-  keyboard_fade_out_hint: "How long keys stay after release (ms)",
-  keyboard_width: "Display Width",
-  keyboard_width_hint: "Width of the keyboard display window (px)",
-  keyboard_scale: "Key Size",
-  keyboard_style: "Key Style",
-  keyboard_style_dark: "Dark",
-  keyboard_style_light: "Light",
-  keyboard_style_glass: "Glass",
-  keyboard_style_outline: "Outline",
-  recording: "Screen Recording",
-  recording_show: "Show Recording Bar",
-  recording_show_hint: "Floating control bar to start/stop screen recording",
-  recording_fps: "Frame Rate",
-  recording_region: "Recording Region",
-  recording_region_current: "Current region",
-  recording_region_full: "Full screen",
-  recording_region_pick: "Select Region",
-  recording_region_reset: "Reset to Full Screen",
-  recording_output_dir: "Output Directory",
-  recording_output_dir_pick: "Choose…",
-  recording_output_dir_reset: "Reset",
-  recording_output_default: "~/Movies/Floaty (default)",
-  recording_auto_zoom: "Auto Zoom",
-  recording_auto_zoom_hint: "Replay a cursor-following zoom in the post-capture editor by default (non-destructive; retunable per clip)",
-  recording_zoom_factor: "Zoom Factor",
-  cursor_overlay: "Cursor Effects",
-  cursor_overlay_hint: "Show click ripples & trail while recording",
-  cursor_style: "Cursor Style",
-  cursor_style_ripple: "Ripple",
-  cursor_style_ring: "Ring",
-  cursor_style_spark: "Spark",
-  cursor_style_none: "None",
-  cursor_trail: "Cursor Trail",
-  hotkey_toggle_recording: "Start/Stop recording",
-
-  editor_loading: "Loading…",
-  editor_no_draft: "No draft to edit",
-  editor_trim: "Trim",
-  editor_resolution: "Resolution",
-  editor_original: "Original",
-  editor_format: "Format",
-  editor_zoom: "Zoom",
-  editor_on: "On",
-  editor_off: "Off",
-  editor_no_trail: "No trail",
-  editor_play: "Play",
-  editor_pause: "Pause",
-  editor_export: "Export",
-  editor_exporting: "Exporting",
-  editor_discard: "Discard",
-  editor_saved: "Saved to",
-  editor_cancelled: "Cancelled",
-  editor_failed: "Export failed",
-  editor_aspect: "Screen",
-  editor_aspect_original: "Original",
-  editor_aspect_landscape: "PC 16:9",
-  editor_aspect_portrait: "Phone 9:16",
-  editor_aspect_square: "Square 1:1",
-
-  recording_rec: "REC",
-  recording_record: "Record",
-  recording_stop: "Stop",
-  recording_pause: "Pause",
-  recording_resume: "Resume",
-  recording_saving: "Saving…",
-
-  keyboard_accessibility_hint: "⚠️ Grant access in System Settings → Privacy → Accessibility",
-  keyboard_input_monitoring_hint: "⚠️ Grant access in System Settings → Privacy → Input Monitoring",
-
-  region_hint: "Click and drag to select the recording area",
-  region_confirm: "Confirm",
-  region_cancel: "Cancel",
-};
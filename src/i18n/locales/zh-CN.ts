@@ -1,129 +0,0 @@
-export const zhCN = {
-  init_camera: "初始化摄像头...",
-  no_camera_detected: "未检测到摄像头设备",
-  media_devices_unavailable: "当前环境不支持 mediaDevices，无法访问摄像头",
-  camera_connected: "摄像头已连接",
-  camera_access_denied: "无法访问摄像头，请检查权限或占用情况",
-  retry_camera: "重新尝试摄像头",
-  unlock_drag: "解锁拖拽",
-  lock_drag: "锁定拖拽",
-  mirror_on: "镜像画面",
-  mirror_off: "取消镜像",
-  open_settings: "打开设置",
-
-  settings_subtitle: "调节摄像头窗口造型、行为和视觉反馈。",
-  camera: "摄像头",
-  device_select: "设备选择",
-  select_camera_placeholder: "选择摄像头设备",
-  shape_circle_desc: "经典圆形头像窗",
-  shape_rounded_desc: "圆角方形更稳重",
-  shape_mickey_desc: "主圆+双耳，强识别度",
-  window_size: "窗口尺寸",
-  current_prefix: "当前:",
-  behavior: "行为",
-  mirror: "镜像画面",
-  mirror_hint: "符合多数主播预期视角",
-  always_on_top: "始终置顶",
-  always_on_top_hint: "录屏时避免被其他窗口覆盖",
-  click_through: "点击穿透",
-  click_through_hint: "打开后不阻挡鼠标操作",
-  lock_drag_hint: "避免误触移动",
-  beauty: "美颜",
-  enable_beauty: "开启美颜",
-  beauty_hint: "柔肤提亮，直播更上镜",
-  smoothness: "磨皮程度",
-  brightness: "亮度调节",
-  brightness_original_hint: "（50% 为原始亮度）",
-  hotkeys: "快捷键",
-  hotkey_toggle_visibility: "显示/隐藏主窗口",
-  hotkey_toggle_lock: "锁定/解锁拖拽",
-  hotkey_open_settings: "打开设置窗口",
-  focus_settings: "聚焦设置窗口",
-  saving: "保存中...",
-  auto_saved: "已自动保存",
-  appearance: "外观",
-  theme: "主题",
-  theme_hint: "界面配色跟随系统或固定明暗",
-  theme_system: "跟随系统",
-  theme_light: "亮色",
-  theme_dark: "暗色",
-  language: "语言",
-  keyboard_display: "按键展示",
-  keyboard_show: "显示按键",
-  keyboard_show_hint: "在屏幕上展示按下的键",
-  keyboard_hide: "隐藏按键",
-  keyboard_open_settings: "打开设置",
-  keyboard_fade_out: "消失延迟",
-  keyboard_fade_out_hint: "按键松开后停留时间（毫秒）",
-  keyboard_width: "展示宽度",
-  keyboard_width_hint: "按键展示窗口的宽度（像素）",
-  keyboard_scale: "按键大小",
-  keyboard_style: "按键样式",
-  keyboard_style_dark: "暗色",
-  keyboard_style_light: "亮色",
-  keyboard_style_glass: "玻璃",
-  keyboard_style_outline: "描边",
-  recording: "屏幕录制",
-  recording_show: "显示录制控制条",
-  recording_show_hint: "浮动控制条，用于开始/停止屏幕录制",
-  recording_fps: "帧率",
-  recording_region: "录制区域",
-  recording_region_current: "当前区域",
-  recording_region_full: "全屏",
-  recording_region_pick: "框选区域",
-  recording_region_reset: "重置为全屏",
-  recording_output_dir: "输出目录",
-  recording_output_dir_pick: "选择…",
-  recording_output_dir_reset: "重置",
-  recording_output_default: "~/Movies/Floaty（默认）",
-  recording_auto_zoom: "自动缩放",
-  recording_auto_zoom_hint: "在录后编辑器中默认回放跟随鼠标的缩放（非破坏性，每条录制可单独调）",
-  recording_zoom_factor: "缩放倍数",
-  cursor_overlay: "光标特效",
-  cursor_overlay_hint: "录制时显示点击涟漪与拖尾",
-  cursor_style: "光标样式",
-  cursor_style_ripple: "涟漪",
-  cursor_style_ring: "圆环",
-  cursor_style_spark: "火花",
-  cursor_style_none: "无",
-  cursor_trail: "光标拖尾",
-  hotkey_toggle_recording: "开始/停止录制",
-
-  editor_loading: "加载中…",
-  editor_no_draft: "没有可编辑的草稿",
-  editor_trim: "裁剪",
-  editor_resolution: "分辨率",
-  editor_original: "原始",
-  editor_format: "格式",
-  editor_zoom: "缩放",
-  editor_on: "开",
-  editor_off: "关",
-  editor_no_trail: "无轨迹",
-  editor_play: "播放",
-  editor_pause: "暂停",
-  editor_export: "导出",
-  editor_exporting: "导出中",
-  editor_discard: "丢弃",
-  editor_saved: "已保存到",
-  editor_cancelled: "已取消",
-  editor_failed: "导出失败",
-  editor_aspect: "屏幕画幅",
-  editor_aspect_original: "原始",
-  editor_aspect_landscape: "电脑 16:9",
-  editor_aspect_portrait: "手机竖屏 9:16",
-  editor_aspect_square: "方形 1:1",
-
-  recording_rec: "开始",
-  recording_record: "开始录制",
-  recording_stop: "停止录制",
-  recording_pause: "暂停",
-  recording_resume: "继续",
-  recording_saving: "正在保存…",
-
-  keyboard_accessibility_hint: "⚠️ 请在 系统设置 → 隐私与安全 → 辅助功能 中授权本应用",
-  keyboard_input_monitoring_hint: "⚠️ 请在 系统设置 → 隐私与安全 → 输入监控 中授权本应用",
-
-  region_hint: "点击并拖拽框选录制区域",
-  region_confirm: "确认",
-  region_cancel: "取消",
-};
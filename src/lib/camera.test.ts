@@ -0,0 +1,56 @@
+import { describe, it, expect } from "vitest";
+import { applyCameraOverride, capTiers, matchesCamera, withCameraSetting } from "./camera";
+import { defaultSettings, type AppSettings, type CameraDevice } from "../types/app";
+
+const builtin: CameraDevice = { deviceId: "a1", label: "FaceTime HD Camera" };
+const brio: CameraDevice = { deviceId: "b2", label: "Logitech BRIO (046d:085e)" };
+
+const docked: AppSettings = {
+  ...defaultSettings,
+  mirror: true,
+  beauty: true,
+  cameraOverrides: [{ match: "logitech*", mirror: false, beautySmoothness: 10, maxHeight: 720 }],
+};
+
+describe("camera matching", () => {
+  it("matches ids and label patterns like the Rust side", () => {
+    expect(matchesCamera("b2", brio)).toBe(true);
+    expect(matchesCamera("Logitech*", brio)).toBe(true);
+    expect(matchesCamera("*brio (*)", brio)).toBe(true);
+    expect(matchesCamera("Logitech", brio)).toBe(false);
+    expect(matchesCamera("BRIO*", brio)).toBe(false);
+    // Regex characters in labels are literal.
+    expect(matchesCamera("Logitech BRIO (046d:085e)", brio)).toBe(true);
+    expect(matchesCamera("Logitech.BRIO*", brio)).toBe(false);
+  });
+});
+
+describe("camera overrides", () => {
+  it("apply only to the matching camera", () => {
+    const view = applyCameraOverride(docked, brio);
+    expect(view.mirror).toBe(false);
+    expect(view.beautySmoothness).toBe(10);
+    expect(view.beauty).toBe(true);
+    expect(applyCameraOverride(docked, builtin)).toBe(docked);
+    expect(applyCameraOverride(docked, undefined)).toBe(docked);
+  });
+
+  it("take edits to the values they set", () => {
+    const flipped = withCameraSetting(docked, brio, "mirror", true);
+    expect(flipped.mirror).toBe(true);
+    expect(flipped.cameraOverrides[0].mirror).toBe(true);
+    expect(docked.cameraOverrides[0].mirror).toBe(false);
+
+    const general = withCameraSetting(docked, brio, "beauty", false);
+    expect(general.beauty).toBe(false);
+    expect(general.cameraOverrides).toBe(docked.cameraOverrides);
+
+    expect(withCameraSetting(docked, builtin, "mirror", false).cameraOverrides[0].mirror).toBe(false);
+  });
+
+  it("cap the capture resolution", () => {
+    expect(capTiers().length).toBe(3);
+    expect(capTiers(720).map((tier) => (tier.height as ConstrainULongRange).ideal)).toEqual([720, 480]);
+    expect(capTiers(240)).toEqual([{ height: { ideal: 240 }, frameRate: { ideal: 30, max: 30 } }]);
+  });
+});
@@ -1,4 +1,4 @@
-import type { CameraDevice } from "../types/app";
+import type { AppSettings, CameraDevice, CameraMode, CameraOverride, CameraOverrideKey } from "../types/app";
 
 function getMediaDevices(): MediaDevices | null {
   if (typeof navigator === "undefined" || !navigator.mediaDevices) {
@@ -23,14 +23,98 @@ export async function listBrowserCameras(): Promise<CameraDevice[]> {
     }));
 }
 
+/**
+ * The natively found device behind a webview camera. The webview's ids are
+ * opaque, so they are matched by label (both come from the driver).
+ */
+export function findNativeCamera(native: CameraDevice[], label: string | undefined): CameraDevice | undefined {
+  return label ? native.find((device) => device.label === label) : undefined;
+}
+
+/** The largest mode, at its highest frame rate. */
+export function bestMode(modes: CameraMode[] = []): CameraMode | undefined {
+  return modes.reduce<CameraMode | undefined>(
+    (best, mode) => (!best || mode.width * mode.height > best.width * best.height ? mode : best),
+    undefined,
+  );
+}
+
+/**
+ * Whether `pattern` (a `cameraPriority` entry or an override's `match`)
+ * names `device`: its id exactly, or its label ignoring case, where `*`
+ * stands for any run of characters. Mirrors `camera::matches` in Rust.
+ */
+export function matchesCamera(pattern: string, device: CameraDevice): boolean {
+  if (device.deviceId === pattern) return true;
+  const escaped = pattern.toLowerCase().split("*").map((part) => part.replace(/[.*+?^${}()|[\]\\]/g, "\\$&"));
+  return new RegExp(`^${escaped.join(".*")}$`, "s").test(device.label.toLowerCase());
+}
+
+/** The override for `device`: the first in `settings` that matches it. */
+export function cameraOverride(settings: AppSettings, device: CameraDevice | undefined): CameraOverride | undefined {
+  return device ? settings.cameraOverrides.find((o) => matchesCamera(o.match, device)) : undefined;
+}
+
+/** `settings` as they apply while `device` is in use. */
+export function applyCameraOverride(settings: AppSettings, device: CameraDevice | undefined): AppSettings {
+  const override = cameraOverride(settings, device);
+  if (!override) return settings;
+  const values = Object.entries(override).filter(
+    ([key, value]) => value !== undefined && key !== "match" && key !== "maxHeight",
+  );
+  return { ...settings, ...Object.fromEntries(values) };
+}
+
+/**
+ * Change a setting for the camera in use: in its override when that sets
+ * the value, otherwise the general setting, so flipping mirror on the
+ * docked webcam leaves the built-in camera's alone.
+ */
+export function withCameraSetting<K extends CameraOverrideKey & keyof AppSettings>(
+  settings: AppSettings,
+  device: CameraDevice | undefined,
+  key: K,
+  value: AppSettings[K],
+): AppSettings {
+  const override = cameraOverride(settings, device);
+  if (!override || override[key] === undefined) return { ...settings, [key]: value };
+  return {
+    ...settings,
+    cameraOverrides: settings.cameraOverrides.map((o) => (o === override ? { ...o, [key]: value } : o)),
+  };
+}
+
+/** The CSS filter for the beauty settings, or `undefined` while beauty is
+ *  off. Shared by the window's `<video>` and the virtual camera canvas. */
+export function beautyFilter(view: AppSettings): string | undefined {
+  if (!view.beauty) return undefined;
+  const smoothness = view.beautySmoothness / 100;
+  const brightness = 1 + (view.beautyBrightness - 50) / 200;
+  const blur = smoothness * 1.2;
+  const contrast = 1 - smoothness * 0.06;
+  const saturate = 1 + smoothness * 0.12;
+  return `blur(${blur}px) brightness(${brightness}) contrast(${contrast}) saturate(${saturate})`;
+}
+
 const QUALITY_TIERS: MediaTrackConstraints[] = [
   { width: { ideal: 1920 }, height: { ideal: 1080 }, frameRate: { ideal: 30, max: 30 } },
   { width: { ideal: 1280 }, height: { ideal: 720 }, frameRate: { ideal: 30, max: 30 } },
   { width: { ideal: 854 }, height: { ideal: 480 }, frameRate: { ideal: 30, max: 30 } },
 ];
 
+/** The quality tiers no taller than `maxHeight`; below the lowest tier,
+ *  just `maxHeight` itself. */
+export function capTiers(maxHeight?: number): MediaTrackConstraints[] {
+  if (!maxHeight) return QUALITY_TIERS;
+  const tiers = QUALITY_TIERS.filter((tier) => ((tier.height as ConstrainULongRange).ideal ?? 0) <= maxHeight);
+  return tiers.length > 0 ? tiers : [{ height: { ideal: maxHeight }, frameRate: { ideal: 30, max: 30 } }];
+}
+
+/** Open a camera at the best quality tier it accepts, no taller than
+ *  `maxHeight` when given (a camera override). */
 export async function startAdaptiveStream(
   cameraId?: string,
+  maxHeight?: number,
   retries = 0,
 ): Promise<MediaStream> {
   const mediaDevices = getMediaDevices();
@@ -41,8 +125,10 @@ export async function startAdaptiveStream(
   let lastError: unknown;
   const deviceConstraint = cameraId ? { deviceId: { exact: cameraId } } : {};
 
+  const tiers = capTiers(maxHeight);
+
   // 1) Try each quality tier with the requested device
-  for (const tier of QUALITY_TIERS) {
+  for (const tier of tiers) {
     try {
       return await mediaDevices.getUserMedia({
         video: { ...tier, ...deviceConstraint },
@@ -75,7 +161,7 @@ export async function startAdaptiveStream(
   if (retries < 3) {
     const delayMs = 350 * 2 ** retries;
     await new Promise((resolve) => setTimeout(resolve, delayMs));
-    return startAdaptiveStream(cameraId, retries + 1);
+    return startAdaptiveStream(cameraId, maxHeight, retries + 1);
   }
 
   throw lastError;
@@ -10,14 +10,35 @@
  * The Rust side mirrors these names in `src-tauri/src/events.rs`.
  */
 
+import type { AppSettings } from "../types/app";
+
 export const EVT = {
   // Settings
+  /** Any settings write that changed something. Payload:
+   *  `SettingsUpdatedPayload`. */
   SETTINGS_UPDATED: "app://settings-updated",
+  /** Stored settings had fields that no longer load; they were reset to
+   *  their defaults. Payload: `SettingsWarningPayload`. */
+  SETTINGS_WARNING: "app://settings-warning",
+  /** `settings.json` was edited outside the app and the edit was rejected;
+   *  the previous settings stay live. Payload: `SettingsError`. */
+  SETTINGS_ERROR: "app://settings-error",
+  /** The profile list or active profile changed. Payload: `ProfilesSummary`. */
+  PROFILES_UPDATED: "app://profiles-updated",
   // Hotkey / tray action fan-out
   HOTKEY_TRIGGERED: "app://hotkey-triggered",
+  /** The OS refused to register a hotkey binding (usually because another
+   *  app holds the shortcut). Payload: `HotkeyError`. */
+  HOTKEY_ERROR: "app://hotkey-error",
   // Camera
   CAMERA_ERROR: "app://camera-error",
+  /** Re-open the camera: another window released it, or the selected
+   *  camera is connected again. No payload. */
   CAMERA_REACQUIRE: "app://camera-reacquire",
+  /** A camera was plugged in / unplugged (Linux only). Payload: the native
+   *  `CameraDevice`, whose id is its device node, not a webview id. */
+  CAMERA_ADDED: "app://camera-added",
+  CAMERA_REMOVED: "app://camera-removed",
   // Keyboard display (macOS event tap)
   KEY_PRESSED: "app://key-pressed",
   KEY_RELEASED: "app://key-released",
@@ -50,6 +71,26 @@ export interface HotkeyPayload {
   action: string;
 }
 
+export interface HotkeyError {
+  /** The `hotkey*` setting whose shortcut was refused. */
+  field: string;
+  shortcut: string;
+  message: string;
+}
+
+export interface SettingsUpdatedPayload {
+  /** Pass back to `patchAppSettings` to have stale writes rejected. */
+  revision: number;
+  /** camelCase keys that changed in this write. */
+  changed: string[];
+  settings: AppSettings;
+}
+
+export interface SettingsWarningPayload {
+  /** camelCase setting key (empty = the whole blob) and why it was reset. */
+  warnings: { field: string; message: string }[];
+}
+
 export interface CameraErrorPayload {
   message: string;
 }
@@ -72,12 +113,14 @@ export interface MousePayload {
 
 export interface RecordingStatusPayload {
   active: boolean;
+  /** Recording but paused (e.g. a hold-to-record hotkey is up). */
+  paused: boolean;
 }
 
 export interface RecordingCmdPayload {
   /** `sync` asks the pipeline to re-broadcast its current UI state (used by
    *  the control bar on mount, since it may open after state was set). */
-  action: "toggle" | "toggle-pause" | "clear-info" | "sync";
+  action: "toggle" | "toggle-pause" | "pause" | "resume" | "clear-info" | "sync";
 }
 
 export interface RecordingUiPayload {
@@ -357,6 +357,9 @@ export async function renderExport(opts: ExportOptions): Promise<Uint8Array> {
   if (!(end > start)) throw new Error("empty trim range");
 
   const video = document.createElement("video");
+  // `src` may be a `floaty-draft://` URL (another origin); without CORS the
+  // canvas would be tainted and `captureStream` would produce nothing.
+  video.crossOrigin = "anonymous";
   video.src = src;
   video.playsInline = true;
   video.preload = "auto";
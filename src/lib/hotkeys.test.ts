@@ -0,0 +1,33 @@
+import { describe, it, expect } from "vitest";
+import { acceleratorFromEvent, formatAccelerator } from "./hotkeys";
+
+const key = (code: string, mods: Partial<Record<"ctrlKey" | "metaKey" | "altKey" | "shiftKey", boolean>> = {}) => ({
+  code,
+  ctrlKey: false,
+  metaKey: false,
+  altKey: false,
+  shiftKey: false,
+  ...mods,
+});
+
+describe("acceleratorFromEvent", () => {
+  it("records the primary modifier as CommandOrControl", () => {
+    expect(acceleratorFromEvent(key("KeyV", { ctrlKey: true, shiftKey: true }), false)).toBe("CommandOrControl+Shift+V");
+    expect(acceleratorFromEvent(key("KeyV", { metaKey: true, shiftKey: true }), true)).toBe("CommandOrControl+Shift+V");
+    expect(acceleratorFromEvent(key("Digit1", { ctrlKey: true, metaKey: true }), true)).toBe("CommandOrControl+Control+1");
+    expect(acceleratorFromEvent(key("Comma", { metaKey: true, altKey: true }), false)).toBe("Super+Alt+Comma");
+  });
+
+  it("waits for a key and refuses plain keys other than function keys", () => {
+    expect(acceleratorFromEvent(key("ShiftLeft", { shiftKey: true }), false)).toBeNull();
+    expect(acceleratorFromEvent(key("KeyR"), false)).toBeNull();
+    expect(acceleratorFromEvent(key("F9"), false)).toBe("F9");
+  });
+});
+
+describe("formatAccelerator", () => {
+  it("uses platform names and key symbols", () => {
+    expect(formatAccelerator("CommandOrControl+Shift+,", true)).toBe("Cmd + Shift + ,");
+    expect(formatAccelerator("CommandOrControl+Alt+Comma", false)).toBe("Ctrl + Alt + ,");
+  });
+});
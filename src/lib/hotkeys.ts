@@ -0,0 +1,83 @@
+/**
+ * Hotkey bindings as edited in the settings window.
+ *
+ * A binding is an accelerator string such as `CommandOrControl+Shift+V`,
+ * stored in one of the `hotkey*` settings and registered by the Rust side
+ * (`src-tauri/src/hotkeys.rs`). Keys are recorded by their physical
+ * `KeyboardEvent.code`, so a binding means the same key on any layout.
+ */
+import type { AppSettings } from "../types/app";
+
+export type HotkeyField = Extract<keyof AppSettings, `hotkey${string}`>;
+
+/** Every binding, in the order the settings window lists them. */
+export const HOTKEY_FIELDS: HotkeyField[] = [
+  "hotkeyToggleVisibility",
+  "hotkeyToggleLock",
+  "hotkeyOpenSettings",
+  "hotkeyToggleRecording",
+  "hotkeyTogglePause",
+  "hotkeyToggleKeyboard",
+  "hotkeySelectRegion",
+  "hotkeyCycleShape",
+  "hotkeyNextProfile",
+  "hotkeyHoldToShow",
+  "hotkeyHoldToRecord",
+];
+
+type KeyInput = Pick<KeyboardEvent, "code" | "ctrlKey" | "metaKey" | "altKey" | "shiftKey">;
+
+const MODIFIER_CODES = /^(Control|Shift|Alt|Meta|OS)(Left|Right)?$/;
+
+/**
+ * The accelerator for a key press, or `null` while it is incomplete (only
+ * modifiers so far) or would swallow a plain key everywhere (no modifier,
+ * other than for function keys).
+ */
+export function acceleratorFromEvent(e: KeyInput, mac: boolean): string | null {
+  if (MODIFIER_CODES.test(e.code)) return null;
+  const key = e.code.replace(/^(Key|Digit)/, "");
+  const mods: string[] = [];
+  if (mac ? e.metaKey : e.ctrlKey) mods.push("CommandOrControl");
+  if (mac && e.ctrlKey) mods.push("Control");
+  if (!mac && e.metaKey) mods.push("Super");
+  if (e.altKey) mods.push("Alt");
+  if (e.shiftKey) mods.push("Shift");
+  if (mods.length === 0 && !/^F\d+$/.test(key)) return null;
+  return [...mods, key].join("+");
+}
+
+const KEY_LABELS: Record<string, string> = {
+  Comma: ",",
+  Period: ".",
+  Slash: "/",
+  Semicolon: ";",
+  Quote: "'",
+  BracketLeft: "[",
+  BracketRight: "]",
+  Backslash: "\\",
+  Backquote: "`",
+  Minus: "-",
+  Equal: "=",
+  ArrowUp: "↑",
+  ArrowDown: "↓",
+  ArrowLeft: "←",
+  ArrowRight: "→",
+};
+
+/** How a binding is shown, e.g. `Cmd + Shift + V` on macOS. */
+export function formatAccelerator(accelerator: string, mac: boolean): string {
+  return accelerator
+    .split("+")
+    .map((part) => {
+      if (part === "CommandOrControl") return mac ? "Cmd" : "Ctrl";
+      if (part === "Control") return "Ctrl";
+      if (part === "Alt") return mac ? "Option" : "Alt";
+      return KEY_LABELS[part] ?? part;
+    })
+    .join(" + ");
+}
+
+export function isMacPlatform(): boolean {
+  return typeof navigator !== "undefined" && /Mac/i.test(navigator.platform);
+}
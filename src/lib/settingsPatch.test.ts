@@ -0,0 +1,20 @@
+import { describe, it, expect } from "vitest";
+import { settingsPatch } from "./settingsPatch";
+import { defaultSettings } from "../types/app";
+
+describe("settingsPatch", () => {
+  it("contains only changed keys", () => {
+    const next = { ...defaultSettings, mirror: !defaultSettings.mirror, recordingFps: 60 };
+    expect(settingsPatch(defaultSettings, next)).toEqual({ mirror: next.mirror, recordingFps: 60 });
+    expect(settingsPatch(defaultSettings, { ...defaultSettings })).toEqual({});
+  });
+
+  it("sends cleared optional settings as null", () => {
+    const prev = { ...defaultSettings, selectedCameraId: "cam" };
+    expect(settingsPatch(prev, { ...prev, selectedCameraId: undefined })).toEqual({ selectedCameraId: null });
+  });
+
+  it("never includes the settings version", () => {
+    expect(settingsPatch(defaultSettings, { ...defaultSettings, settingsVersion: 99 })).toEqual({});
+  });
+});
@@ -0,0 +1,28 @@
+/**
+ * Partial settings writes.
+ *
+ * Windows used to save the whole `AppSettings` object, so two windows
+ * saving at once could silently undo each other's changes. Instead they
+ * send only the keys they changed to `patchAppSettings`, which merges them
+ * over the stored settings under a lock on the Rust side.
+ */
+import type { AppSettings } from "../types/app";
+
+/**
+ * The keys whose values differ between `prev` and `next`, with their new
+ * values. A cleared optional setting is sent as `null`, which is how the
+ * backend's merge patch clears it.
+ */
+export function settingsPatch(prev: AppSettings, next: AppSettings): Partial<Record<keyof AppSettings, unknown>> {
+  const patch: Partial<Record<keyof AppSettings, unknown>> = {};
+  const keys = new Set([...Object.keys(prev), ...Object.keys(next)]) as Set<keyof AppSettings>;
+  for (const key of keys) {
+    if (key === "settingsVersion") continue;
+    const before = prev[key] ?? null;
+    const after = next[key] ?? null;
+    if (JSON.stringify(before) !== JSON.stringify(after)) {
+      patch[key] = after;
+    }
+  }
+  return patch;
+}
@@ -1,14 +1,144 @@
-import { invoke } from "@tauri-apps/api/core";
-import type { AppSettings, RecordingMeta, RecordingRegion, ShapePreset } from "../types/app";
+import { convertFileSrc, invoke } from "@tauri-apps/api/core";
+import type { HotkeyError, SettingsUpdatedPayload } from "./events";
+import type {
+  AppAction,
+  AppSettings,
+  CameraDevice,
+  ControlApiInfo,
+  EditDecisions,
+  EditState,
+  ProfilesSummary,
+  RecordingMeta,
+  RecordingRegion,
+  RevisionedSettings,
+  SettingsImportPreview,
+  ShapePreset,
+  VirtualCameraInfo,
+} from "../types/app";
 
 export async function getAppSettings(): Promise<AppSettings> {
   return invoke<AppSettings>("get_app_settings");
 }
 
+/**
+ * Persist settings. Rejects with a `SettingsError` naming every invalid
+ * field; nothing is saved in that case.
+ */
 export async function saveAppSettings(payload: AppSettings): Promise<void> {
   await invoke("save_app_settings", { payload });
 }
 
+export async function getAppSettingsRevisioned(): Promise<RevisionedSettings> {
+  return invoke<RevisionedSettings>("get_app_settings_revisioned");
+}
+
+/**
+ * Change only the given settings (see `settingsPatch`). With `revision`,
+ * the write is rejected (`SettingsError.currentRevision` set) if another
+ * window saved since that revision. Resolves with the stored result, the
+ * same payload `app://settings-updated` carries.
+ */
+export async function patchAppSettings(
+  patch: Partial<Record<keyof AppSettings, unknown>>,
+  revision?: number,
+): Promise<SettingsUpdatedPayload> {
+  return invoke<SettingsUpdatedPayload>("patch_app_settings", { patch, revision });
+}
+
+export async function listSettingsProfiles(): Promise<ProfilesSummary> {
+  return invoke<ProfilesSummary>("list_settings_profiles");
+}
+
+/** Save the current setup (settings, window positions, region) as a new profile. */
+export async function createSettingsProfile(name: string): Promise<ProfilesSummary> {
+  return invoke<ProfilesSummary>("create_settings_profile", { name });
+}
+
+export async function duplicateSettingsProfile(id: string, name: string): Promise<ProfilesSummary> {
+  return invoke<ProfilesSummary>("duplicate_settings_profile", { id, name });
+}
+
+export async function renameSettingsProfile(id: string, name: string): Promise<ProfilesSummary> {
+  return invoke<ProfilesSummary>("rename_settings_profile", { id, name });
+}
+
+/** Delete a profile. The active profile cannot be deleted. */
+export async function deleteSettingsProfile(id: string): Promise<ProfilesSummary> {
+  return invoke<ProfilesSummary>("delete_settings_profile", { id });
+}
+
+/**
+ * Switch to a profile. The live setup is saved into the outgoing profile
+ * first; emits `app://settings-updated` with the new settings.
+ */
+export async function activateSettingsProfile(id: string): Promise<ProfilesSummary> {
+  return invoke<ProfilesSummary>("activate_settings_profile", { id });
+}
+
+/** Switch to the next profile in list order (wrapping). */
+export async function activateNextSettingsProfile(): Promise<ProfilesSummary> {
+  return invoke<ProfilesSummary>("activate_next_settings_profile");
+}
+
+/**
+ * Write settings, window positions and the recording region to a JSON or
+ * TOML file picked via the save dialog. Resolves `null` if cancelled.
+ */
+export async function exportSettingsFile(): Promise<string | null> {
+  return invoke<string | null>("export_settings_file");
+}
+
+/**
+ * Pick a settings file and describe what importing it would change; nothing
+ * is applied until `applySettingsImport`. `cameraIds` are the devices this
+ * machine has, so an imported camera id can be checked. Resolves `null` if
+ * cancelled.
+ */
+export async function previewSettingsImport(cameraIds: string[]): Promise<SettingsImportPreview | null> {
+  return invoke<SettingsImportPreview | null>("preview_settings_import", { cameraIds });
+}
+
+/** Apply the last previewed import; emits `app://settings-updated`. */
+export async function applySettingsImport(): Promise<void> {
+  await invoke("apply_settings_import");
+}
+
+export async function cancelSettingsImport(): Promise<void> {
+  await invoke("cancel_settings_import");
+}
+
+/** Run an action through the same dispatcher as the tray and hotkeys. */
+export async function runAction(action: AppAction): Promise<unknown> {
+  return invoke("run_action", { action });
+}
+
+/** Hotkey bindings the OS refused at the last (re-)registration. */
+export async function getHotkeyErrors(): Promise<HotkeyError[]> {
+  return invoke<HotkeyError[]>("get_hotkey_errors");
+}
+
+export async function getControlApiInfo(): Promise<ControlApiInfo> {
+  return invoke<ControlApiInfo>("get_control_api_info");
+}
+
+/** New control API token; clients using the old one are disconnected. */
+export async function regenerateControlApiToken(): Promise<ControlApiInfo> {
+  return invoke<ControlApiInfo>("regenerate_control_api_token");
+}
+
+/** Where the virtual camera publishes, or why it does not. */
+export async function getVirtualCameraInfo(): Promise<VirtualCameraInfo> {
+  return invoke<VirtualCameraInfo>("get_virtual_camera_info");
+}
+
+/** Publish one `width`×`height` RGBA frame. The bytes go as the raw
+ *  invoke body; wrapped in an object they would be sent as JSON numbers. */
+export async function pushVirtualCameraFrame(frame: Uint8Array, width: number, height: number): Promise<void> {
+  await invoke("push_virtual_camera_frame", frame, {
+    headers: { "x-frame-width": String(width), "x-frame-height": String(height) },
+  });
+}
+
 export async function applyWindowShape(payload: ShapePreset): Promise<void> {
   await invoke("apply_window_shape", { payload });
 }
@@ -41,6 +171,25 @@ export async function openCameraPrivacySettings(): Promise<void> {
   await invoke("open_camera_privacy_settings");
 }
 
+/** Cameras found natively, with their modes; empty where unsupported. */
+export async function listCameras(): Promise<CameraDevice[]> {
+  return invoke<CameraDevice[]>("list_cameras");
+}
+
+/** Tell the tray which cameras the camera window can see. Rust answers
+ *  with `CAMERA_REACQUIRE` when the camera to use changes: the selected
+ *  one is back, or a preferred fallback was plugged in or unplugged. */
+export async function reportCameras(cameras: CameraDevice[]): Promise<void> {
+  await invoke("report_cameras", { cameras });
+}
+
+/** The camera to open out of `cameras`: the selected one, or while it is
+ *  missing, the first connected `cameraPriority` entry. `null` = try the
+ *  selection as is (nothing listed yet). */
+export async function resolveCamera(cameras: CameraDevice[]): Promise<string | null> {
+  return invoke<string | null>("resolve_camera", { cameras });
+}
+
 // --- Screen recording commands ---
 
 /** Show or hide the floating recording control bar window. */
@@ -105,6 +254,15 @@ export async function saveRecording(bytes: Uint8Array, suggestedName: string): P
  * `recordingOutputDir`.
  */
 export async function pickRecordingOutputDir(): Promise<string | null> {
+  return pickDirectory();
+}
+
+/** Native directory picker for the drafts location (see `moveDraftsDir`). */
+export async function pickDraftsDir(): Promise<string | null> {
+  return pickDirectory();
+}
+
+async function pickDirectory(): Promise<string | null> {
   const { open } = await import("@tauri-apps/plugin-dialog");
   const selected = await open({ directory: true, multiple: false });
   if (typeof selected !== "string" || selected.length === 0) {
@@ -115,39 +273,72 @@ export async function pickRecordingOutputDir(): Promise<string | null> {
 
 // --- Recording editor commands ---
 
-/** Persist a fresh recording as a draft for the editor. Returns the draft path. */
+/**
+ * Check the drafts volume has room for a recording. Resolves with the free
+ * byte count; rejects with a readable message when space is too low.
+ */
+export async function checkDraftsSpace(): Promise<number> {
+  return invoke<number>("check_drafts_space");
+}
+
+export interface DraftsMigrationReport {
+  moved: number;
+  /** File names left in the old location because the target already had them. */
+  skipped: string[];
+}
+
+/**
+ * Move the drafts directory (null = back to the default app data dir),
+ * migrating existing drafts and sidecars. Emits `app://settings-updated`.
+ */
+export async function moveDraftsDir(target: string | null): Promise<DraftsMigrationReport> {
+  return invoke<DraftsMigrationReport>("move_drafts_dir", { target });
+}
+
+/**
+ * Persist a fresh recording as a draft for the editor. Returns the draft's
+ * opaque id; every other draft command takes that id, never a path.
+ */
 export async function saveRecordingDraft(bytes: Uint8Array, ext: "mp4" | "webm"): Promise<string> {
   return invoke<string>("save_recording_draft", { bytes, ext });
 }
 
-/** The draft path the editor window should load. */
-export async function getEditorDraftPath(): Promise<string | null> {
-  return invoke<string | null>("get_editor_draft_path");
+/** The draft id the editor window should load. */
+export async function getEditorDraftId(): Promise<string | null> {
+  return invoke<string | null>("get_editor_draft_id");
+}
+
+/**
+ * URL that streams a draft from disk through the `floaty-draft://` scheme
+ * (HTTP Range aware, so `<video>` can seek without loading the whole file).
+ */
+export function draftStreamUrl(draftId: string): string {
+  return convertFileSrc(draftId, "floaty-draft");
 }
 
 /**
  * Read a draft recording back as bytes. The Rust side returns a raw binary
  * response; normalize the handful of shapes the IPC layer may hand us.
  */
-export async function readRecordingFile(path: string): Promise<Uint8Array> {
-  const res = await invoke<ArrayBuffer | Uint8Array | number[]>("read_recording_file", { path });
+export async function readRecordingFile(id: string): Promise<Uint8Array> {
+  const res = await invoke<ArrayBuffer | Uint8Array | number[]>("read_recording_file", { id });
   if (res instanceof Uint8Array) return res;
   if (res instanceof ArrayBuffer) return new Uint8Array(res);
   return new Uint8Array(res);
 }
 
-/** Delete a draft (after export, or when discarded). Also removes its sidecar. */
-export async function deleteRecordingDraft(path: string): Promise<void> {
-  await invoke("delete_recording_draft", { path });
+/** Delete a draft (after export, or when discarded). Also removes its sidecar and edits. */
+export async function deleteRecordingDraft(id: string): Promise<void> {
+  await invoke("delete_recording_draft", { id });
 }
 
 /**
  * Persist the metadata sidecar (`draft-<id>.json`) for a draft recording.
- * The draft path comes from `saveRecordingDraft`; the sidecar is written
- * next to it with the `.json` extension.
+ * The draft id comes from `saveRecordingDraft`; the sidecar is written next
+ * to the draft with the `.json` extension.
  */
-export async function saveRecordingMeta(draftPath: string, meta: RecordingMeta): Promise<void> {
-  await invoke("save_recording_meta", { draftPath, meta });
+export async function saveRecordingMeta(draftId: string, meta: RecordingMeta): Promise<void> {
+  await invoke("save_recording_meta", { draftId, meta });
 }
 
 /**
@@ -155,8 +346,58 @@ export async function saveRecordingMeta(draftPath: string, meta: RecordingMeta):
  * exists (e.g. recordings made before sidecars existed) so callers can
  * gracefully disable zoom preview.
  */
-export async function readRecordingMeta(draftPath: string): Promise<RecordingMeta | null> {
-  return invoke<RecordingMeta | null>("read_recording_meta", { draftPath });
+export async function readRecordingMeta(draftId: string): Promise<RecordingMeta | null> {
+  return invoke<RecordingMeta | null>("read_recording_meta", { draftId });
+}
+
+/**
+ * Read the persisted edit decision list (`draft-<id>.edits.json`) for a
+ * draft. Null when the draft has never been edited.
+ */
+export async function readRecordingEdits(draftId: string): Promise<EditState | null> {
+  return invoke<EditState | null>("read_recording_edits", { draftId });
+}
+
+/**
+ * Record a new edit decision list for a draft; the previous one becomes an
+ * undo step. Resolves with the normalized state as saved.
+ */
+export async function saveRecordingEdits(draftId: string, edits: EditDecisions): Promise<EditState> {
+  return invoke<EditState>("save_recording_edits", { draftId, edits });
+}
+
+/** Step a draft's edit history back. Null when the draft has no edits. */
+export async function undoRecordingEdits(draftId: string): Promise<EditState | null> {
+  return invoke<EditState | null>("undo_recording_edits", { draftId });
+}
+
+/** Step a draft's edit history forward. Null when the draft has no edits. */
+export async function redoRecordingEdits(draftId: string): Promise<EditState | null> {
+  return invoke<EditState | null>("redo_recording_edits", { draftId });
+}
+
+/**
+ * Pack a draft into a `.floaty` project bundle (video, metadata sidecar,
+ * saved edit decisions, poster frame) via the native save dialog. Returns
+ * the bundle path, or null if the user cancelled. The poster is passed as a
+ * `Uint8Array` directly, same as `saveRecording`.
+ */
+export async function exportProjectBundle(draftId: string, posterPng: Uint8Array | null): Promise<string | null> {
+  return invoke<string | null>("export_project_bundle", { draftId, posterPng });
+}
+
+export interface ImportedProject {
+  draftId: string;
+  /** Whether the bundle's edits were restored (readable via `readRecordingEdits`). */
+  hasEdits: boolean;
+}
+
+/**
+ * Open a `.floaty` bundle via the native open dialog and unpack it as a new
+ * draft (which becomes the editor's current draft). Null if cancelled.
+ */
+export async function importProjectBundle(): Promise<ImportedProject | null> {
+  return invoke<ImportedProject | null>("import_project_bundle");
 }
 
 /** Open (or replace) the post-capture editor window. */
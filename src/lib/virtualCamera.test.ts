@@ -0,0 +1,14 @@
+import { describe, it, expect } from "vitest";
+import { coverCrop, shapeBox } from "./virtualCamera";
+
+describe("virtual camera layout", () => {
+  it("centers the shape on the shorter side", () => {
+    expect(shapeBox(1280, 720)).toEqual({ x: 280, y: 0, size: 720 });
+    expect(shapeBox(480, 640)).toEqual({ x: 0, y: 80, size: 480 });
+  });
+
+  it("crops the camera to a centered square", () => {
+    expect(coverCrop(1920, 1080)).toEqual({ sx: 420, sy: 0, sw: 1080, sh: 1080 });
+    expect(coverCrop(640, 640)).toEqual({ sx: 0, sy: 0, sw: 640, sh: 640 });
+  });
+});
@@ -0,0 +1,127 @@
+import type { AppSettings, ShapePreset } from "../types/app";
+import { beautyFilter } from "./camera";
+import { getVirtualCameraInfo, pushVirtualCameraFrame } from "./tauri";
+
+/** A square area of the output frame, in pixels. */
+export interface Box {
+  x: number;
+  y: number;
+  size: number;
+}
+
+/** Source rectangle of the video to draw. */
+export interface Crop {
+  sx: number;
+  sy: number;
+  sw: number;
+  sh: number;
+}
+
+/** Where the shape sits in a `width`×`height` frame: centered, as large as
+ *  the shorter side, like the camera window itself. */
+export function shapeBox(width: number, height: number): Box {
+  const size = Math.min(width, height);
+  return { x: (width - size) / 2, y: (height - size) / 2, size };
+}
+
+/** The centered square of a `videoWidth`×`videoHeight` picture, which fills
+ *  the shape the way `object-fit: cover` fills the window. */
+export function coverCrop(videoWidth: number, videoHeight: number): Crop {
+  const side = Math.min(videoWidth, videoHeight);
+  return { sx: (videoWidth - side) / 2, sy: (videoHeight - side) / 2, sw: side, sh: side };
+}
+
+/** Trace the window's shape mask into `box`; see `.shape-*` in styles.css
+ *  and the `#mickeyMask` clip path. */
+export function traceShape(ctx: CanvasRenderingContext2D, shape: ShapePreset, box: Box) {
+  const { x, y, size } = box;
+  ctx.beginPath();
+  if (shape === "roundedSquare") {
+    // 34px on the default 320px window.
+    ctx.roundRect(x, y, size, size, (size * 34) / 320);
+  } else if (shape === "mickey") {
+    for (const [cx, cy, r] of [[0.5, 0.58, 0.38], [0.24, 0.19, 0.18], [0.76, 0.19, 0.18]]) {
+      ctx.moveTo(x + (cx + r) * size, y + cy * size);
+      ctx.arc(x + cx * size, y + cy * size, r * size, 0, Math.PI * 2);
+    }
+  } else {
+    ctx.arc(x + size / 2, y + size / 2, size / 2, 0, Math.PI * 2);
+  }
+}
+
+/** Draw one frame: black outside the shape, the styled camera inside. */
+export function drawFrame(ctx: CanvasRenderingContext2D, video: HTMLVideoElement, view: AppSettings) {
+  const { width, height } = ctx.canvas;
+  const box = shapeBox(width, height);
+  const crop = coverCrop(video.videoWidth, video.videoHeight);
+  ctx.save();
+  ctx.fillStyle = "#000";
+  ctx.fillRect(0, 0, width, height);
+  traceShape(ctx, view.shape, box);
+  ctx.clip();
+  if (view.mirror) {
+    ctx.translate(width, 0);
+    ctx.scale(-1, 1);
+  }
+  ctx.filter = beautyFilter(view) ?? "none";
+  ctx.drawImage(video, crop.sx, crop.sy, crop.sw, crop.sh, box.x, box.y, box.size, box.size);
+  ctx.restore();
+}
+
+/**
+ * Render the camera window's picture into a `width`×`height` canvas `fps`
+ * times a second and push each frame to the virtual camera. A frame is
+ * skipped while the previous push is still in flight. Nothing is pushed
+ * while the virtual camera reports an error (no loopback device, not
+ * Linux, the device went away; the settings window shows it): a settings
+ * change clears it and restarts the feed. Returns a stop function.
+ */
+export function startVirtualCamera(
+  video: () => HTMLVideoElement | null,
+  view: () => AppSettings,
+  width: number,
+  height: number,
+  fps: number,
+): () => void {
+  const canvas = document.createElement("canvas");
+  canvas.width = width;
+  canvas.height = height;
+  const ctx = canvas.getContext("2d", { willReadFrequently: true });
+  if (!ctx) return () => {};
+
+  let timer: ReturnType<typeof setInterval> | undefined;
+  let stopped = false;
+  const stop = () => {
+    stopped = true;
+    clearInterval(timer);
+  };
+  // Whether the output is still there after a failed push; a frame from
+  // before a resolution change fails once without that.
+  const checkOutput = () =>
+    getVirtualCameraInfo()
+      .then((info) => {
+        if (info.error) stop();
+      })
+      .catch(stop);
+
+  let busy = false;
+  const tick = () => {
+    const el = video();
+    if (busy || !el || el.readyState < HTMLMediaElement.HAVE_CURRENT_DATA) return;
+    drawFrame(ctx, el, view());
+    const { data } = ctx.getImageData(0, 0, width, height);
+    busy = true;
+    pushVirtualCameraFrame(new Uint8Array(data.buffer), width, height)
+      .catch(checkOutput)
+      .finally(() => {
+        busy = false;
+      });
+  };
+
+  void getVirtualCameraInfo()
+    .then((info) => {
+      if (!stopped && !info.error) timer = setInterval(tick, 1000 / fps);
+    })
+    .catch(() => undefined);
+  return stop;
+}
@@ -1,6 +1,11 @@
 export type ShapePreset = "circle" | "roundedSquare" | "mickey";
 export type KeyboardDisplayStyle = "dark" | "light" | "glass" | "outline";
 export type CursorEffectStyle = "ripple" | "ring" | "spark" | "none";
+export type KeyboardDisplayPosition = "bottom-center" | "top-center" | "bottom-left" | "bottom-right";
+export type Theme = "system" | "light" | "dark";
+/** UI language: the tag of a locale file in `src/i18n/locales` (see
+ *  `LOCALES`); mirrors `Locale` in `src-tauri/src/settings/mod.rs`. */
+export type AppLocale = string;
 
 /**
  * Recording region in physical screen pixels, sharing the same coordinate
@@ -73,14 +78,204 @@ export interface RecordingMeta {
   debug?: Record<string, unknown>;
 }
 
+/** A manual zoom point; mirrors `ZoomKeyframe` in `recording/edits.rs`. */
+export interface ZoomKeyframe {
+  /** Seconds from the start of the source. */
+  t: number;
+  factor: number;
+  /** Zoom centre, normalized to the frame (0..1). Omitted = frame centre. */
+  x?: number | null;
+  y?: number | null;
+}
+
+/** A span removed from the middle of the clip, in source seconds. */
+export interface CutSegment {
+  start: number;
+  end: number;
+}
+
+/**
+ * The editor's choices for one draft: what `renderExport` needs to
+ * reproduce an export. Persisted per draft (`draft-<id>.edits.json`, with
+ * undo history) and carried inside `.floaty` project bundles (`edits.json`)
+ * so a teammate opening the project gets the same cut. Mirrors
+ * `EditDecisionList` in `recording/edits.rs`.
+ */
+export interface EditDecisions {
+  /** Trim in/out points in seconds. */
+  trim: [number, number];
+  zoomEnabled: boolean;
+  zoomFactor: number;
+  /** Mirrors `AspectPreset` in `lib/exportVideo.ts`. */
+  aspect: "original" | "landscape" | "portrait" | "square";
+  /** Export height in px; `null` = source resolution. */
+  targetHeight: number | null;
+  format: "mp4" | "webm";
+  /** Sorted by `t`; empty = cursor-following auto zoom only. */
+  zoomKeyframes: ZoomKeyframe[];
+  /** Sorted, non-overlapping, inside `trim`. */
+  cuts: CutSegment[];
+}
+
+/** An edit list plus whether undo/redo are available for it. */
+export interface EditState {
+  edits: EditDecisions;
+  canUndo: boolean;
+  canRedo: boolean;
+}
+
+/**
+ * Named settings profiles (names only; snapshots stay in Rust). Each holds
+ * a full `AppSettings` plus window positions and the recording region;
+ * `active` is the one the live settings belong to.
+ */
+export interface ProfilesSummary {
+  active: string | null;
+  profiles: { id: string; name: string }[];
+}
+
+/** One value a settings file import would change. */
+export interface SettingsChange {
+  /** camelCase setting key, `positions.<window>` or `recordingRegion`. */
+  key: string;
+  from: unknown;
+  to: unknown;
+}
+
+/**
+ * Result of `previewSettingsImport`. `warnings` lists values reset while
+ * reading the file and machine-specific values (camera, folders, off-screen
+ * positions) that keep this machine's value instead.
+ */
+export interface SettingsImportPreview {
+  path: string;
+  changes: SettingsChange[];
+  warnings: SettingsFieldError[];
+}
+
+/** One rejected setting from `saveAppSettings`, keyed by its camelCase name. */
+export interface SettingsFieldError {
+  field: string;
+  message: string;
+}
+
+/**
+ * Rejection value of settings writes. `fields` lists every invalid setting
+ * (bad type/enum value or out of range); empty for non-field failures.
+ */
+export interface SettingsError {
+  message: string;
+  fields: SettingsFieldError[];
+  /** Set when a `patchAppSettings` call was based on an older revision
+   *  than the stored settings; reload and retry. */
+  currentRevision: number | null;
+}
+
+/** Settings plus the revision they were read at (see `patchAppSettings`). */
+export interface RevisionedSettings {
+  revision: number;
+  settings: AppSettings;
+}
+
+/** Something the user can trigger from the tray, a hotkey, a `floaty://`
+ *  link, the CLI or the control API; run from a window with `runAction`.
+ *  Mirrors `Action` in `src-tauri/src/action.rs`. */
+export type AppAction =
+  | { action: "toggleVisibility" }
+  | { action: "setCameraVisible"; visible: boolean }
+  | { action: "toggleLock" }
+  | { action: "toggleKeyboard" }
+  | { action: "setKeyboardVisible"; visible: boolean }
+  | { action: "toggleClickThrough" }
+  | { action: "toggleAlwaysOnTop" }
+  | { action: "startRecording" }
+  | { action: "stopRecording" }
+  | { action: "toggleRecording" }
+  | { action: "togglePause" }
+  | { action: "pauseRecording" }
+  | { action: "resumeRecording" }
+  | { action: "selectRegion"; region?: RecordingRegion | null }
+  | { action: "resetRegion" }
+  | { action: "setShape"; shape: ShapePreset }
+  | { action: "cycleShape" }
+  | { action: "setScale"; scale: number }
+  | { action: "selectCamera"; id: string }
+  | { action: "openRecording"; path: string }
+  | { action: "revealRecording"; path: string }
+  | { action: "openSettings" }
+  | { action: "activateProfile"; id: string }
+  | { action: "activateNextProfile" }
+  | { action: "getStatus" }
+  | { action: "quit" };
+
+/** Result of `getControlApiInfo`: where the control API listens and the
+ *  token clients must send. */
+export interface ControlApiInfo {
+  /** `null` while the API is off or could not start. */
+  port: number | null;
+  token: string;
+  /** Why it is not running although enabled (e.g. the port is taken). */
+  error: string | null;
+}
+
+/** Result of `getVirtualCameraInfo`. */
+export interface VirtualCameraInfo {
+  /** Device being published to; `null` while off or not started. */
+  device: string | null;
+  /** Why it is not running although enabled (e.g. no loopback device). */
+  error: string | null;
+}
+
+/** A camera as the webview lists it, or as `listCameras` finds it natively
+ *  (which adds the fields below). Mirrors `src-tauri/src/camera/mod.rs`. */
 export interface CameraDevice {
   deviceId: string;
   label: string;
   groupId?: string;
+  /** Device node, e.g. `/dev/video0`. */
+  path?: string;
+  driver?: string;
+  /** Largest first within each pixel format. */
+  modes?: CameraMode[];
+  /** Another app is streaming from it. */
+  busy?: boolean;
+}
+
+export interface CameraMode {
+  /** FourCC, e.g. `YUYV` or `MJPG`. */
+  pixelFormat: string;
+  width: number;
+  height: number;
+  /** Highest first. */
+  frameRates: number[];
 }
 
+/** Settings that replace the general ones while a particular camera is in
+ *  use; unset fields keep the general setting. */
+export interface CameraOverride {
+  /** Device id or label pattern, as in `cameraPriority`. */
+  match: string;
+  mirror?: boolean;
+  beauty?: boolean;
+  beautySmoothness?: number;
+  beautyBrightness?: number;
+  /** Tallest capture resolution to ask the camera for, e.g. 720. */
+  maxHeight?: number;
+}
+
+export type CameraOverrideKey = Exclude<keyof CameraOverride, "match">;
+
 export interface AppSettings {
+  /** Schema version of the stored blob; Rust upgrades older blobs on read
+   *  and stamps the current version on save. */
+  settingsVersion: number;
   selectedCameraId?: string;
+  /** Fallbacks, in order, while the selected camera is not connected: each
+   *  a device id or a label pattern (see `matchesCamera`). */
+  cameraPriority: string[];
+  /** Per-camera settings; the first entry matching the camera in use
+   *  applies. */
+  cameraOverrides: CameraOverride[];
   shape: ShapePreset;
   scale: number;
   mirror: boolean;
@@ -91,10 +286,11 @@ export interface AppSettings {
   beauty: boolean;
   beautySmoothness: number;
   beautyBrightness: number;
-  locale: string;
-  theme: "system" | "light" | "dark";
+  /** `""` only in `defaultSettings`, before the persisted settings load. */
+  locale: AppLocale | "";
+  theme: Theme;
   keyboardDisplayEnabled: boolean;
-  keyboardDisplayPosition: "bottom-center" | "top-center" | "bottom-left" | "bottom-right";
+  keyboardDisplayPosition: KeyboardDisplayPosition;
   keyboardDisplayScale: number;
   keyboardDisplayFadeOut: number;
   keyboardDisplayWidth: number;
@@ -107,6 +303,36 @@ export interface AppSettings {
   cursorEffectStyle: CursorEffectStyle;
   cursorTrailEnabled: boolean;
   recordingOutputDir?: string;
+  /** Drafts location; unset = per-user app data dir. Change it only via
+   *  `moveDraftsDir`, which migrates existing drafts with it. */
+  draftsDir?: string;
+  /** Serve the local control API on 127.0.0.1:`controlApiPort`. */
+  controlApiEnabled: boolean;
+  controlApiPort: number;
+  /** Also serve a subset of obs-websocket v5 at `/` on that port. */
+  controlApiObsCompat: boolean;
+  /** Publish the styled camera as a webcam (v4l2loopback on Linux). */
+  virtualCameraEnabled: boolean;
+  /** Loopback device such as `/dev/video10`; unset = the first one found. */
+  virtualCameraDevice?: string;
+  virtualCameraWidth: number;
+  virtualCameraHeight: number;
+  virtualCameraFps: number;
+  /** Global hotkeys: accelerators such as `CommandOrControl+Shift+V`,
+   *  registered on the Rust side; `""` = unbound. See `lib/hotkeys.ts`. */
+  hotkeyToggleVisibility: string;
+  hotkeyToggleLock: string;
+  hotkeyOpenSettings: string;
+  hotkeyToggleRecording: string;
+  hotkeyTogglePause: string;
+  hotkeyToggleKeyboard: string;
+  hotkeySelectRegion: string;
+  hotkeyCycleShape: string;
+  hotkeyNextProfile: string;
+  /** Momentary: the camera is shown only while held. */
+  hotkeyHoldToShow: string;
+  /** Momentary: recording runs while held and pauses on release. */
+  hotkeyHoldToRecord: string;
 
   // NOTE: `recordingRegion` deliberately does NOT live here. The recording
   // region is stored under its own store key (RECORDING_REGION_KEY) and
@@ -123,7 +349,10 @@ export interface RuntimeState {
 }
 
 export const defaultSettings: AppSettings = {
+  settingsVersion: 1,
   selectedCameraId: undefined,
+  cameraPriority: [],
+  cameraOverrides: [],
   shape: "circle",
   scale: 1,
   mirror: true,
@@ -150,6 +379,26 @@ export const defaultSettings: AppSettings = {
   cursorEffectStyle: "ripple",
   cursorTrailEnabled: true,
   recordingOutputDir: undefined,
+  draftsDir: undefined,
+  controlApiEnabled: false,
+  controlApiPort: 7373,
+  controlApiObsCompat: false,
+  virtualCameraEnabled: false,
+  virtualCameraDevice: undefined,
+  virtualCameraWidth: 1280,
+  virtualCameraHeight: 720,
+  virtualCameraFps: 30,
+  hotkeyToggleVisibility: "CommandOrControl+Shift+V",
+  hotkeyToggleLock: "CommandOrControl+Shift+L",
+  hotkeyOpenSettings: "CommandOrControl+Shift+,",
+  hotkeyToggleRecording: "CommandOrControl+Shift+R",
+  hotkeyTogglePause: "",
+  hotkeyToggleKeyboard: "",
+  hotkeySelectRegion: "",
+  hotkeyCycleShape: "",
+  hotkeyNextProfile: "CommandOrControl+Shift+P",
+  hotkeyHoldToShow: "",
+  hotkeyHoldToRecord: "",
 };
 
 export interface KeyEvent {
@@ -1,6 +1,6 @@
 import { useEffect, useRef, useState } from "react";
 import { listen } from "@tauri-apps/api/event";
-import { EVT } from "../lib/events";
+import { EVT, type SettingsUpdatedPayload } from "../lib/events";
 import { gsap } from "gsap";
 import { getAppSettings } from "../lib/tauri";
 import { physicalToCss } from "../lib/coords";
@@ -45,9 +45,9 @@ export default function CursorOverlayWindow() {
       setStyle(s.cursorEffectStyle ?? "ripple");
       setTrailOn(s.cursorTrailEnabled ?? true);
     });
-    const unlistenP = listen<AppSettings>(EVT.SETTINGS_UPDATED, (e) => {
-      setStyle(e.payload.cursorEffectStyle ?? "ripple");
-      setTrailOn(e.payload.cursorTrailEnabled ?? true);
+    const unlistenP = listen<SettingsUpdatedPayload>(EVT.SETTINGS_UPDATED, (e) => {
+      setStyle(e.payload.settings.cursorEffectStyle ?? "ripple");
+      setTrailOn(e.payload.settings.cursorTrailEnabled ?? true);
     });
     return () => { void unlistenP.then((fn) => fn()); };
   }, []);
@@ -4,16 +4,22 @@ import { listen } from "@tauri-apps/api/event";
 import { Slider } from "../components/ui/slider";
 import { PlaybackBar, fmtTime } from "../components/editor/PlaybackBar";
 import { I18nProvider, getMessages, useI18n, detectLocale, type Locale } from "../i18n";
-import { EVT } from "../lib/events";
+import { EVT, type SettingsUpdatedPayload } from "../lib/events";
 import {
   deleteRecordingDraft,
+  draftStreamUrl,
+  exportProjectBundle,
   getAppSettings,
-  getEditorDraftPath,
-  readRecordingFile,
+  getEditorDraftId,
+  importProjectBundle,
+  readRecordingEdits,
   readRecordingMeta,
+  redoRecordingEdits,
   saveRecording,
+  saveRecordingEdits,
+  undoRecordingEdits,
 } from "../lib/tauri";
-import type { AppSettings, RecordingMeta } from "../types/app";
+import type { AppSettings, CutSegment, EditDecisions, EditState, RecordingMeta, ZoomKeyframe } from "../types/app";
 import {
   ASPECT_RATIOS,
   computeFrameWindow,
@@ -38,16 +44,19 @@ type Format = "mp4" | "webm";
  * re-encode via `renderExport`. Deliberately small in scope (Screen-Studio-
  * style fine-tune, not a full NLE): trim + zoom + resolution + format.
  *
- * The draft file lives in the OS temp dir (written by `save_recording_draft`
- * when the pipeline stops), alongside a `draft-<id>.json` sidecar holding the
- * recording metadata + cursor trail. Both are deleted after a successful
- * export or when the user discards.
+ * The draft file lives in the drafts directory (per-user app data by
+ * default; written by `save_recording_draft` when the pipeline stops),
+ * alongside a `draft-<id>.json` sidecar holding the
+ * recording metadata + cursor trail, and a `draft-<id>.edits.json` edit
+ * decision list that is autosaved (with undo history) as the user edits and
+ * restored when the draft is reopened. All three are deleted after a
+ * successful export or when the user discards.
  */
 function EditorContent() {
   const t = useI18n();
   const [videoUrl, setVideoUrl] = useState<string | null>(null);
   const [meta, setMeta] = useState<RecordingMeta | null>(null);
-  const [draftPath, setDraftPath] = useState<string | null>(null);
+  const [draftId, setDraftId] = useState<string | null>(null);
   const [duration, setDuration] = useState(0);
   const [range, setRange] = useState<[number, number]>([0, 0]);
   const [currentTime, setCurrentTime] = useState(0);
@@ -62,39 +71,109 @@ function EditorContent() {
   const [progress, setProgress] = useState(0);
   const [info, setInfo] = useState("");
   const [videoSize, setVideoSize] = useState<{ w: number; h: number }>({ w: 0, h: 0 });
+  const [history, setHistory] = useState({ canUndo: false, canRedo: false });
 
   const videoRef = useRef<HTMLVideoElement | null>(null);
   const canvasRef = useRef<HTMLCanvasElement | null>(null);
   const rafRef = useRef<number | null>(null);
+  // Trim from restored edit decisions; applied once the clip's duration is
+  // known (onLoadedMetadata), since the range must be clamped to it.
+  const pendingTrimRef = useRef<[number, number] | null>(null);
+  // Manual zoom keyframes and cuts have no controls here yet; carry them
+  // through unchanged so saving never drops what another editor wrote.
+  const keyframesRef = useRef<ZoomKeyframe[]>([]);
+  const cutsRef = useRef<CutSegment[]>([]);
+  // Key of the edit list last persisted (or restored), so autosave skips
+  // no-op writes — including the re-render after an undo/redo.
+  const lastSavedRef = useRef<string | null>(null);
 
   const hasTrail = !!(meta?.cursor?.length && meta.crop);
 
+  const currentEdits = (): EditDecisions => ({
+    trim: range,
+    zoomEnabled,
+    zoomFactor,
+    aspect,
+    targetHeight,
+    format,
+    zoomKeyframes: keyframesRef.current,
+    cuts: cutsRef.current,
+  });
+
+  // `immediate` applies the trim straight away (undo/redo on a loaded
+  // clip); otherwise it waits for onLoadedMetadata to clamp it.
+  const applyEdits = (e: EditDecisions, immediate = false) => {
+    setZoomEnabled(e.zoomEnabled);
+    setZoomFactor(e.zoomFactor);
+    setAspect(e.aspect);
+    setTargetHeight(e.targetHeight);
+    setFormat(e.format);
+    keyframesRef.current = e.zoomKeyframes ?? [];
+    cutsRef.current = e.cuts ?? [];
+    if (immediate) {
+      setRange(e.trim);
+    } else {
+      pendingTrimRef.current = e.trim;
+    }
+  };
+
+  const applyEditState = (st: EditState, immediate = false) => {
+    lastSavedRef.current = editsKey(st.edits);
+    setHistory({ canUndo: st.canUndo, canRedo: st.canRedo });
+    applyEdits(st.edits, immediate);
+  };
+
+  // Persist the current edit list unless it is what was last saved. The
+  // previous list becomes an undo step on the Rust side.
+  const persistEdits = async (id: string) => {
+    const edits = currentEdits();
+    const key = editsKey(edits);
+    if (key === lastSavedRef.current) return;
+    lastSavedRef.current = key;
+    const st = await saveRecordingEdits(id, edits);
+    setHistory({ canUndo: st.canUndo, canRedo: st.canRedo });
+  };
+
+  // Point the editor at a draft: stream the video, load its metadata
+  // sidecar, then either restore its saved edit list or fall back to the
+  // capture-time zoom defaults from settings.
+  const loadDraft = async (id: string, s: AppSettings) => {
+    setDraftId(id);
+    setDuration(0);
+    setRange([0, 0]);
+    try {
+      const [m, saved] = await Promise.all([readRecordingMeta(id), readRecordingEdits(id)]);
+      // Streamed from disk with Range support — no whole-file IPC read.
+      setVideoUrl(draftStreamUrl(id));
+      setMeta(m);
+      if (saved) {
+        applyEditState(saved);
+      } else {
+        lastSavedRef.current = null;
+        keyframesRef.current = [];
+        cutsRef.current = [];
+        setHistory({ canUndo: false, canRedo: false });
+        // Default the zoom toggle to whatever the user had set at capture time;
+        // only meaningful when there's actually a trail to replay.
+        const wantZoom = !!m?.cursor?.length && (s.recordingAutoZoom ?? false);
+        setZoomEnabled(wantZoom);
+        setZoomFactor(s.recordingZoomFactor ?? 2);
+      }
+    } catch (err) {
+      setInfo(`${(err as Error)?.message ?? err}`);
+    }
+  };
+
   // Load settings + the draft (and its metadata sidecar) the pipeline saved.
   useEffect(() => {
     void (async () => {
-      const [s, path] = await Promise.all([getAppSettings(), getEditorDraftPath()]);
+      const [s, id] = await Promise.all([getAppSettings(), getEditorDraftId()]);
       setFps(s.recordingFps ?? 30);
-      if (!path) {
+      if (!id) {
         setInfo(t.editor_no_draft);
         return;
       }
-      setDraftPath(path);
-      try {
-        const [bytes, m] = await Promise.all([
-          readRecordingFile(path),
-          readRecordingMeta(path),
-        ]);
-        const type = path.endsWith(".webm") ? "video/webm" : "video/mp4";
-        setVideoUrl(URL.createObjectURL(new Blob([bytes], { type })));
-        setMeta(m);
-        // Default the zoom toggle to whatever the user had set at capture time;
-        // only meaningful when there's actually a trail to replay.
-        const wantZoom = !!m?.cursor?.length && (s.recordingAutoZoom ?? false);
-        setZoomEnabled(wantZoom);
-        setZoomFactor(s.recordingZoomFactor ?? 2);
-      } catch (err) {
-        setInfo(`${(err as Error)?.message ?? err}`);
-      }
+      await loadDraft(id, s);
     })();
     // eslint-disable-next-line react-hooks/exhaustive-deps
   }, []);
@@ -109,11 +188,59 @@ function EditorContent() {
     }
     if (isFinite(d) && d > 0) {
       setDuration(d);
-      setRange([0, d]);
+      const trim = pendingTrimRef.current;
+      pendingTrimRef.current = null;
+      if (trim && trim[1] > trim[0]) {
+        const start = Math.min(Math.max(trim[0], 0), d);
+        setRange([start, Math.min(Math.max(trim[1], start), d)]);
+      } else {
+        setRange([0, d]);
+      }
     }
     setVideoSize({ w: video.videoWidth, h: video.videoHeight });
   };
 
+  // Autosave: once the clip is loaded, persist edits after a short pause so
+  // a slider drag becomes one undo step rather than dozens.
+  useEffect(() => {
+    if (!draftId || !duration || exporting) return;
+    const timer = setTimeout(() => {
+      persistEdits(draftId).catch((err) => setInfo(`${(err as Error)?.message ?? err}`));
+    }, 400);
+    return () => clearTimeout(timer);
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [draftId, duration, exporting, range, zoomEnabled, zoomFactor, aspect, targetHeight, format]);
+
+  const stepHistory = async (redo: boolean) => {
+    if (!draftId || !duration || exporting) return;
+    try {
+      // Flush a pending autosave first so undo steps back from what the
+      // user actually sees.
+      await persistEdits(draftId);
+      const st = await (redo ? redoRecordingEdits(draftId) : undoRecordingEdits(draftId));
+      if (st) applyEditState(st, true);
+    } catch (err) {
+      setInfo(`${(err as Error)?.message ?? err}`);
+    }
+  };
+
+  // Cmd/Ctrl+Z undoes, Shift+Cmd/Ctrl+Z (or Ctrl+Y) redoes.
+  useEffect(() => {
+    const onKeyDown = (e: KeyboardEvent) => {
+      if (!(e.metaKey || e.ctrlKey)) return;
+      const key = e.key.toLowerCase();
+      if (key === "z") {
+        e.preventDefault();
+        void stepHistory(e.shiftKey);
+      } else if (key === "y") {
+        e.preventDefault();
+        void stepHistory(true);
+      }
+    };
+    window.addEventListener("keydown", onKeyDown);
+    return () => window.removeEventListener("keydown", onKeyDown);
+  });
+
   const seekTo = (v: number) => {
     const video = videoRef.current;
     if (!video) return;
@@ -263,7 +390,7 @@ function EditorContent() {
       });
       const saved = await saveRecording(bytes, `floaty-${recordingStamp()}.${format}`);
       if (saved) {
-        if (draftPath) void deleteRecordingDraft(draftPath);
+        if (draftId) void deleteRecordingDraft(draftId);
         setInfo(`${t.editor_saved}: ${saved}`);
       } else {
         setInfo(t.editor_cancelled);
@@ -275,8 +402,36 @@ function EditorContent() {
     }
   };
 
+  const onSaveProject = async () => {
+    if (!draftId || exporting) return;
+    setInfo("");
+    try {
+      // The bundle carries the saved edit list, so flush any pending edit.
+      await persistEdits(draftId);
+      const poster = videoRef.current ? await capturePoster(videoRef.current) : null;
+      const saved = await exportProjectBundle(draftId, poster);
+      setInfo(saved ? `${t.editor_saved}: ${saved}` : t.editor_cancelled);
+    } catch (err) {
+      setInfo(`${t.editor_failed}: ${(err as Error)?.message ?? err}`);
+    }
+  };
+
+  const onOpenProject = async () => {
+    if (exporting) return;
+    setInfo("");
+    try {
+      if (draftId && duration) await persistEdits(draftId);
+      const imported = await importProjectBundle();
+      if (!imported) return;
+      const s = await getAppSettings();
+      await loadDraft(imported.draftId, s);
+    } catch (err) {
+      setInfo(`${(err as Error)?.message ?? err}`);
+    }
+  };
+
   const onDiscard = async () => {
-    if (draftPath) await deleteRecordingDraft(draftPath).catch(() => undefined);
+    if (draftId) await deleteRecordingDraft(draftId).catch(() => undefined);
     void getCurrentWindow().close();
   };
 
@@ -320,6 +475,9 @@ function EditorContent() {
             <video
               ref={videoRef}
               src={videoUrl}
+              // The draft scheme is a separate origin; request CORS so the
+              // overlay canvas isn't tainted when it draws this video.
+              crossOrigin="anonymous"
               playsInline
               className={overlayActive ? "covered" : undefined}
               onLoadedMetadata={() => void onLoadedMetadata()}
@@ -462,6 +620,33 @@ function EditorContent() {
           <button type="button" className="editor-btn" onClick={() => void onDiscard()} disabled={exporting}>
             {t.editor_discard}
           </button>
+          <button
+            type="button"
+            className="editor-btn"
+            onClick={() => void stepHistory(false)}
+            disabled={!history.canUndo || exporting}
+          >
+            {t.editor_undo}
+          </button>
+          <button
+            type="button"
+            className="editor-btn"
+            onClick={() => void stepHistory(true)}
+            disabled={!history.canRedo || exporting}
+          >
+            {t.editor_redo}
+          </button>
+          <button type="button" className="editor-btn" onClick={() => void onOpenProject()} disabled={exporting}>
+            {t.editor_open_project}
+          </button>
+          <button
+            type="button"
+            className="editor-btn"
+            onClick={() => void onSaveProject()}
+            disabled={!draftId || !duration || exporting}
+          >
+            {t.editor_save_project}
+          </button>
           <button
             type="button"
             className="editor-btn primary"
@@ -492,9 +677,9 @@ export default function EditorWindow() {
   }, []);
 
   useEffect(() => {
-    const unlistenPromise = listen<AppSettings>(EVT.SETTINGS_UPDATED, (event) => {
-      if (event.payload.locale) {
-        setLocale(event.payload.locale as Locale);
+    const unlistenPromise = listen<SettingsUpdatedPayload>(EVT.SETTINGS_UPDATED, (event) => {
+      if (event.payload.settings.locale) {
+        setLocale(event.payload.settings.locale as Locale);
       }
     });
     return () => { void unlistenPromise.then((unlisten) => unlisten()); };
@@ -509,6 +694,34 @@ export default function EditorWindow() {
   );
 }
 
+/** Stable comparison key for an edit list (field order fixed here). */
+function editsKey(e: EditDecisions): string {
+  return JSON.stringify([
+    e.trim,
+    e.zoomEnabled,
+    e.zoomFactor,
+    e.aspect,
+    e.targetHeight,
+    e.format,
+    e.zoomKeyframes ?? [],
+    e.cuts ?? [],
+  ]);
+}
+
+/** Grab the current frame as a small PNG for the project bundle's poster. */
+async function capturePoster(video: HTMLVideoElement): Promise<Uint8Array | null> {
+  if (!video.videoWidth || !video.videoHeight) return null;
+  const scale = Math.min(1, 480 / video.videoWidth);
+  const canvas = document.createElement("canvas");
+  canvas.width = Math.round(video.videoWidth * scale);
+  canvas.height = Math.round(video.videoHeight * scale);
+  const ctx = canvas.getContext("2d");
+  if (!ctx) return null;
+  ctx.drawImage(video, 0, 0, canvas.width, canvas.height);
+  const blob = await new Promise<Blob | null>((resolve) => canvas.toBlob(resolve, "image/png"));
+  return blob ? new Uint8Array(await blob.arrayBuffer()) : null;
+}
+
 /** Read a usable duration out of a clip whose metadata lacks one (WebM). */
 function probeDuration(video: HTMLVideoElement): Promise<number> {
   return new Promise((resolve) => {
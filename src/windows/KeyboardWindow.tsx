@@ -1,10 +1,10 @@
 import { useEffect, useRef, useState } from "react";
 import { listen } from "@tauri-apps/api/event";
-import { EVT } from "../lib/events";
+import { EVT, type SettingsUpdatedPayload } from "../lib/events";
 import { invoke } from "@tauri-apps/api/core";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { LogicalSize } from "@tauri-apps/api/dpi";
-import type { AppSettings, KeyEvent, KeyboardDisplayStyle } from "../types/app";
+import type { KeyEvent, KeyboardDisplayStyle } from "../types/app";
 import { getAppSettings } from "../lib/tauri";
 import { I18nProvider, getMessages, useI18n, detectLocale, type Locale } from "../i18n";
 
@@ -83,14 +83,15 @@ function KeyboardContent() {
   }, []);
 
   useEffect(() => {
-    const unlisten = listen<AppSettings>(EVT.SETTINGS_UPDATED, (event) => {
-      fadeOutMsRef.current = event.payload.keyboardDisplayFadeOut || DEFAULT_FADE_OUT_MS;
-      const width = event.payload.keyboardDisplayWidth || 800;
-      const scale = event.payload.keyboardDisplayScale ?? 1.0;
+    const unlisten = listen<SettingsUpdatedPayload>(EVT.SETTINGS_UPDATED, (event) => {
+      const settings = event.payload.settings;
+      fadeOutMsRef.current = settings.keyboardDisplayFadeOut || DEFAULT_FADE_OUT_MS;
+      const width = settings.keyboardDisplayWidth || 800;
+      const scale = settings.keyboardDisplayScale ?? 1.0;
       const height = Math.round(80 * scale);
       getCurrentWindow().setSize(new LogicalSize(width, height));
-      setKeyScale(event.payload.keyboardDisplayScale ?? 1.0);
-      setKeyStyle(event.payload.keyboardDisplayStyle ?? "dark");
+      setKeyScale(settings.keyboardDisplayScale ?? 1.0);
+      setKeyStyle(settings.keyboardDisplayStyle ?? "dark");
     });
     return () => { unlisten.then((fn) => fn()); };
   }, []);
@@ -266,9 +267,9 @@ export default function KeyboardWindow() {
   }, []);
 
   useEffect(() => {
-    const unlistenPromise = listen<AppSettings>(EVT.SETTINGS_UPDATED, (event) => {
-      if (event.payload.locale) {
-        setLocale(event.payload.locale as Locale);
+    const unlistenPromise = listen<SettingsUpdatedPayload>(EVT.SETTINGS_UPDATED, (event) => {
+      if (event.payload.settings.locale) {
+        setLocale(event.payload.settings.locale as Locale);
       }
     });
     return () => { void unlistenPromise.then((unlisten) => unlisten()); };
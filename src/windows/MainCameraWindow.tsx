@@ -1,18 +1,19 @@
 import { emit, listen } from "@tauri-apps/api/event";
-import { EVT } from "../lib/events";
-import { register, unregisterAll } from "@tauri-apps/plugin-global-shortcut";
+import { EVT, type SettingsUpdatedPayload } from "../lib/events";
 import { Lock, LockOpen, FlipHorizontal2, Settings, Keyboard } from "lucide-react";
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 import { defaultSettings, type AppSettings, type CameraDevice, type RuntimeState } from "../types/app";
-import { listBrowserCameras, startAdaptiveStream, stopStream } from "../lib/camera";
+import { applyCameraOverride, beautyFilter, cameraOverride, listBrowserCameras, startAdaptiveStream, stopStream, withCameraSetting } from "../lib/camera";
+import { settingsPatch } from "../lib/settingsPatch";
+import { startVirtualCamera } from "../lib/virtualCamera";
 import {
   getAppSettings,
-  openSettingsWindow,
   openCameraPrivacySettings,
-  saveAppSettings,
+  patchAppSettings,
+  reportCameras,
+  resolveCamera,
+  runAction,
   startDragMainWindow,
-  toggleKeyboardWindow,
-  toggleMainWindowVisibility,
 } from "../lib/tauri";
 import { I18nProvider, getMessages, useI18n, detectLocale, type Locale } from "../i18n";
 import { useRecordingPipeline } from "../hooks/useRecordingPipeline";
@@ -34,6 +35,8 @@ function MainCameraContent() {
   const [settings, setSettings] = useState<AppSettings>(defaultSettings);
   const [runtime, setRuntime] = useState<RuntimeState>(defaultRuntime);
   const [devices, setDevices] = useState<CameraDevice[]>([]);
+  // Webview id of the camera on screen; its override (if any) applies.
+  const [liveId, setLiveId] = useState<string | undefined>(undefined);
   const [status, setStatus] = useState(t.init_camera);
   const [statusHidden, setStatusHidden] = useState(false);
   const [ctxMenu, setCtxMenu] = useState<CtxMenu | null>(null);
@@ -56,9 +59,13 @@ function MainCameraContent() {
   const devicesRef = useRef(devices);
   devicesRef.current = devices;
 
+  /** The webview id of the camera on screen, if any. */
+  const liveCameraId = () => streamRef.current?.getVideoTracks()[0]?.getSettings().deviceId;
+
   const syncSettings = useCallback(async (next: AppSettings) => {
+    const patch = settingsPatch(settingsRef.current, next);
     setSettings(next);
-    await saveAppSettings(next);
+    await patchAppSettings(patch);
   }, []);
 
   const connectCamera = useCallback(async (cameraId?: string) => {
@@ -71,12 +78,17 @@ function MainCameraContent() {
 
     const gen = ++connectGenRef.current;
     try {
+      // Without an explicit camera, Rust picks one: the selected camera, or
+      // a `cameraPriority` fallback while it is unplugged.
       const targetId = cameraId
+        || (await resolveCamera(devicesRef.current).catch(() => null))
         || settingsRef.current.selectedCameraId
         || (devicesRef.current.length > 0 ? devicesRef.current[0].deviceId : undefined);
+      const target = devicesRef.current.find((d) => d.deviceId === targetId);
+      const maxHeight = cameraOverride(settingsRef.current, target)?.maxHeight;
       // Acquire BEFORE touching the live stream: a failed or hung re-acquire
       // must leave the current picture on screen instead of going black.
-      const stream = await startAdaptiveStream(targetId);
+      const stream = await startAdaptiveStream(targetId, maxHeight);
       if (gen !== connectGenRef.current) {
         // A newer connect superseded this one while we were acquiring.
         stopStream(stream);
@@ -85,6 +97,7 @@ function MainCameraContent() {
       stopStream(streamRef.current);
       streamRef.current = stream;
       if (videoRef.current) videoRef.current.srcObject = stream;
+      setLiveId(liveCameraId());
       // If the OS kills the track (device yanked, capture session revoked),
       // re-acquire instead of sitting on a black frame.
       const track = stream.getVideoTracks()[0];
@@ -149,14 +162,17 @@ function MainCameraContent() {
     const bootstrap = async () => {
       if (!navigator.mediaDevices) return;
 
-      const persisted = await getAppSettings();
-      if (!mounted) return;
-      setSettings(persisted);
-
+      // List cameras first so the first connect can fall back from a
+      // selected camera that is not plugged in.
       const cameraList = await listBrowserCameras();
       if (!mounted) return;
+      devicesRef.current = cameraList;
       setDevices(cameraList);
 
+      const persisted = await getAppSettings();
+      if (!mounted) return;
+      setSettings(persisted);
+
       // With a persisted camera, the selectedCameraId effect owns the
       // connect; acquiring here too would just double-open the device.
       if (!persisted.selectedCameraId) {
@@ -164,20 +180,31 @@ function MainCameraContent() {
         if (!mounted) return;
       }
 
-      // Re-enumerate after permission granted (labels become available)
+      // Re-enumerate after permission granted (labels become available),
+      // which can match a `cameraPriority` label the first list could not.
       const updatedList = await listBrowserCameras();
-      if (mounted) setDevices(updatedList);
+      if (!mounted) return;
+      devicesRef.current = updatedList;
+      setDevices(updatedList);
+      const target = await resolveCamera(updatedList).catch(() => null);
+      if (mounted && target && target !== liveCameraId()) await connectCamera(target);
     };
     void bootstrap();
     return () => { mounted = false; };
   }, []); // eslint-disable-line react-hooks/exhaustive-deps
 
-  // Re-connect when user changes camera in settings. The old stream is
-  // swapped out inside connectCamera only after its replacement is live.
+  // Re-connect when user changes camera or fallbacks in settings, if that
+  // changes the camera to use. The old stream is swapped out inside
+  // connectCamera only after its replacement is live.
+  const priorityKey = settings.cameraPriority.join("\n");
   useEffect(() => {
-    if (!settings.selectedCameraId) return;
-    void connectCamera(settings.selectedCameraId);
-  }, [settings.selectedCameraId, connectCamera]);
+    if (!settings.selectedCameraId && !priorityKey) return;
+    void (async () => {
+      const target = await resolveCamera(devicesRef.current).catch(() => null);
+      if (target && target === liveCameraId()) return;
+      await connectCamera(target ?? undefined);
+    })();
+  }, [settings.selectedCameraId, priorityKey, connectCamera]);
 
   // Stop the stream only on unmount (and drop any pending mute-recovery timer).
   useEffect(() => () => {
@@ -185,9 +212,12 @@ function MainCameraContent() {
     stopStream(streamRef.current);
   }, []);
 
-  // Handle hot-plug. Debounced and gated on the *video* device set actually
-  // changing — audio-only changes (e.g. getDisplayMedia grabbing the mic when
-  // a screen recording starts) must not bounce the camera.
+  // Handle hot-plug, from the webview's `devicechange` or Rust's device
+  // watcher (WebKitGTK does not fire the former). Debounced and gated on
+  // the *video* device set actually changing — audio-only changes (e.g.
+  // getDisplayMedia grabbing the mic when a screen recording starts) must
+  // not bounce the camera. Switching cameras is Rust's call: reporting the
+  // new list gets a `CAMERA_REACQUIRE` if the camera to use changed.
   useEffect(() => {
     if (!navigator.mediaDevices) return;
     let timer: ReturnType<typeof setTimeout> | null = null;
@@ -198,25 +228,33 @@ function MainCameraContent() {
         const prevIds = devicesRef.current.map((d) => d.deviceId).sort().join("|");
         const nextIds = cameraList.map((d) => d.deviceId).sort().join("|");
         if (nextIds === prevIds) return;
+        devicesRef.current = cameraList;
         setDevices(cameraList);
-        await connectCamera();
       }, 300);
     };
     navigator.mediaDevices.addEventListener("devicechange", onDeviceChanged);
+    const unlisten = [EVT.CAMERA_ADDED, EVT.CAMERA_REMOVED].map((name) => listen(name, onDeviceChanged));
     return () => {
       if (timer) clearTimeout(timer);
       navigator.mediaDevices.removeEventListener("devicechange", onDeviceChanged);
+      for (const promise of unlisten) void promise.then((off) => off());
     };
   }, [connectCamera]);
 
+  // The tray's camera submenu lists what this window can see.
+  useEffect(() => {
+    void reportCameras(devices).catch(() => undefined);
+  }, [devices]);
+
   useEffect(() => {
-    const unlistenPromise = listen<AppSettings>(EVT.SETTINGS_UPDATED, (event) => {
-      setSettings(event.payload);
+    const unlistenPromise = listen<SettingsUpdatedPayload>(EVT.SETTINGS_UPDATED, (event) => {
+      setSettings(event.payload.settings);
     });
     return () => { void unlistenPromise.then((unlisten) => unlisten()); };
   }, []);
 
-  // Re-acquire camera when settings window releases its temp stream
+  // Re-acquire camera when settings window releases its temp stream, or
+  // when the selected camera is plugged back in
   useEffect(() => {
     const unlistenPromise = listen(EVT.CAMERA_REACQUIRE, () => {
       void connectCamera();
@@ -224,35 +262,6 @@ function MainCameraContent() {
     return () => { void unlistenPromise.then((unlisten) => unlisten()); };
   }, [connectCamera]);
 
-
-  useEffect(() => {
-    const hotkeys = async () => {
-      try {
-        await unregisterAll();
-      } catch { /* ignore */ }
-      try {
-        await register("CommandOrControl+Shift+V", async () => {
-          await toggleMainWindowVisibility();
-        });
-        await register("CommandOrControl+Shift+L", async () => {
-          const next = { ...settings, locked: !settings.locked };
-          await syncSettings(next);
-          await emit(EVT.HOTKEY_TRIGGERED, { action: "toggle_lock" });
-        });
-        await register("CommandOrControl+Shift+,", async () => {
-          await openSettingsWindow();
-        });
-        await register("CommandOrControl+Shift+R", async () => {
-          // The recording control bar listens for this action and toggles
-          // start/stop. If the bar isn't open yet, the action is inert.
-          await emit(EVT.HOTKEY_TRIGGERED, { action: "toggle_recording" });
-        });
-      } catch { /* hotkey registration may fail if already taken */ }
-    };
-    void hotkeys();
-    return () => { void unregisterAll(); };
-  }, [settings, syncSettings]);
-
   // Close context menu on click anywhere, blur, or Escape
   useEffect(() => {
     if (!ctxMenu) return;
@@ -288,19 +297,31 @@ function MainCameraContent() {
 
   const shapeClass = useMemo(() => `shape-${settings.shape}`, [settings.shape]);
 
+  const liveDevice = devices.find((d) => d.deviceId === liveId);
+  // Settings as they apply to the camera on screen.
+  const view = useMemo(() => applyCameraOverride(settings, liveDevice), [settings, liveDevice]);
+
   const videoStyle = useMemo(() => {
-    const transform = settings.mirror ? "scaleX(-1)" : "scaleX(1)";
-    if (!settings.beauty) return { transform };
-    const smoothness = settings.beautySmoothness / 100;
-    const brightness = 1 + (settings.beautyBrightness - 50) / 200;
-    const blur = smoothness * 1.2;
-    const contrast = 1 - smoothness * 0.06;
-    const saturate = 1 + smoothness * 0.12;
-    return {
-      transform,
-      filter: `blur(${blur}px) brightness(${brightness}) contrast(${contrast}) saturate(${saturate})`,
-    };
-  }, [settings.mirror, settings.beauty, settings.beautySmoothness, settings.beautyBrightness]);
+    const transform = view.mirror ? "scaleX(-1)" : "scaleX(1)";
+    const filter = beautyFilter(view);
+    return filter ? { transform, filter } : { transform };
+  }, [view]);
+
+  // The virtual camera renders the same picture off screen; it reads the
+  // latest view on every frame, so only its own settings restart it.
+  const viewRef = useRef(view);
+  viewRef.current = view;
+  const { virtualCameraEnabled, virtualCameraDevice, virtualCameraWidth, virtualCameraHeight, virtualCameraFps } = settings;
+  useEffect(() => {
+    if (!virtualCameraEnabled) return;
+    return startVirtualCamera(
+      () => videoRef.current,
+      () => viewRef.current,
+      virtualCameraWidth,
+      virtualCameraHeight,
+      virtualCameraFps,
+    );
+  }, [virtualCameraEnabled, virtualCameraDevice, virtualCameraWidth, virtualCameraHeight, virtualCameraFps]);
 
   return (
     <main className="camera-app" onContextMenu={onContextMenu}>
@@ -343,29 +364,26 @@ function MainCameraContent() {
           onPointerDown={(e) => e.stopPropagation()}
         >
           <button type="button" onClick={() => {
-            const next = { ...settings, locked: !settings.locked };
-            void syncSettings(next);
+            void runAction({ action: "toggleLock" });
             setCtxMenu(null);
           }}>
             {settings.locked ? <><LockOpen size={14} /> {t.unlock_drag}</> : <><Lock size={14} /> {t.lock_drag}</>}
           </button>
           <button type="button" onClick={() => {
-            const next = { ...settings, mirror: !settings.mirror };
+            const next = withCameraSetting(settings, liveDevice, "mirror", !view.mirror);
             void syncSettings(next);
             setCtxMenu(null);
           }}>
-            <FlipHorizontal2 size={14} /> {settings.mirror ? t.mirror_off : t.mirror_on}
+            <FlipHorizontal2 size={14} /> {view.mirror ? t.mirror_off : t.mirror_on}
           </button>
           <button type="button" onClick={() => {
-            void openSettingsWindow();
+            void runAction({ action: "openSettings" });
             setCtxMenu(null);
           }}>
             <Settings size={14} /> {t.open_settings}
           </button>
-          <button type="button" onClick={async () => {
-            const next = { ...settings, keyboardDisplayEnabled: !settings.keyboardDisplayEnabled };
-            await toggleKeyboardWindow(next.keyboardDisplayEnabled);
-            void syncSettings(next);
+          <button type="button" onClick={() => {
+            void runAction({ action: "toggleKeyboard" });
             setCtxMenu(null);
           }}>
             <Keyboard size={14} /> {settings.keyboardDisplayEnabled ? t.keyboard_hide : t.keyboard_show}
@@ -398,9 +416,9 @@ export function MainCameraWindow() {
   }, []);
 
   useEffect(() => {
-    const unlistenPromise = listen<AppSettings>(EVT.SETTINGS_UPDATED, (event) => {
-      if (event.payload.locale) {
-        setLocale(event.payload.locale as Locale);
+    const unlistenPromise = listen<SettingsUpdatedPayload>(EVT.SETTINGS_UPDATED, (event) => {
+      if (event.payload.settings.locale) {
+        setLocale(event.payload.settings.locale as Locale);
       }
     });
     return () => { void unlistenPromise.then((unlisten) => unlisten()); };
@@ -5,8 +5,7 @@ import { Pause, Play, Square, Crop, Monitor } from "lucide-react";
 import { getAppSettings, resetRecordingRegion, startRegionSelect } from "../lib/tauri";
 import { useRecordingRemote } from "../hooks/useRecordingRemote";
 import { I18nProvider, getMessages, useI18n, detectLocale, type Locale } from "../i18n";
-import { EVT } from "../lib/events";
-import type { AppSettings } from "../types/app";
+import { EVT, type SettingsUpdatedPayload } from "../lib/events";
 
 /**
  * The floating recording control bar — a REMOTE CONTROL for the pipeline,
@@ -137,9 +136,9 @@ export default function RecordingControlWindow() {
   }, []);
 
   useEffect(() => {
-    const unlistenPromise = listen<AppSettings>(EVT.SETTINGS_UPDATED, (event) => {
-      if (event.payload.locale) {
-        setLocale(event.payload.locale as Locale);
+    const unlistenPromise = listen<SettingsUpdatedPayload>(EVT.SETTINGS_UPDATED, (event) => {
+      if (event.payload.settings.locale) {
+        setLocale(event.payload.settings.locale as Locale);
       }
     });
     return () => { void unlistenPromise.then((unlisten) => unlisten()); };
@@ -3,9 +3,9 @@ import { listen } from "@tauri-apps/api/event";
 import { getCurrentWindow } from "@tauri-apps/api/window";
 import { confirmRegion, cancelRegionSelect, getAppSettings } from "../lib/tauri";
 import { cssToPhysical } from "../lib/coords";
-import type { AppSettings, RecordingRegion } from "../types/app";
+import type { RecordingRegion } from "../types/app";
 import { I18nProvider, getMessages, useI18n, detectLocale, type Locale } from "../i18n";
-import { EVT } from "../lib/events";
+import { EVT, type SettingsUpdatedPayload } from "../lib/events";
 
 interface Rect {
   x: number;
@@ -36,9 +36,9 @@ export default function RegionSelectWindow() {
   }, []);
 
   useEffect(() => {
-    const unlistenPromise = listen<AppSettings>(EVT.SETTINGS_UPDATED, (event) => {
-      if (event.payload.locale) {
-        setLocale(event.payload.locale as Locale);
+    const unlistenPromise = listen<SettingsUpdatedPayload>(EVT.SETTINGS_UPDATED, (event) => {
+      if (event.payload.settings.locale) {
+        setLocale(event.payload.settings.locale as Locale);
       }
     });
     return () => { void unlistenPromise.then((unlisten) => unlisten()); };
@@ -1,7 +1,7 @@
 import { emit, listen } from "@tauri-apps/api/event";
-import { EVT } from "../lib/events";
+import { EVT, type HotkeyError, type SettingsUpdatedPayload, type SettingsWarningPayload } from "../lib/events";
 import { gsap } from "gsap";
-import { Camera, Lock, Palette, Pin, Sparkles, Video } from "lucide-react";
+import { Camera, FileJson, Layers, Lock, Palette, Pin, Radio, Sparkles, Video, Webcam } from "lucide-react";
 import { useEffect, useMemo, useRef, useState } from "react";
 import { Button } from "../components/ui/button";
 import { Card } from "../components/ui/card";
@@ -9,30 +9,100 @@ import { Label } from "../components/ui/label";
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from "../components/ui/select";
 import { Slider } from "../components/ui/slider";
 import { Switch } from "../components/ui/switch";
-import { listBrowserCameras } from "../lib/camera";
+import { applyCameraOverride, bestMode, cameraOverride, findNativeCamera, listBrowserCameras, withCameraSetting } from "../lib/camera";
+import { settingsPatch } from "../lib/settingsPatch";
+import { HOTKEY_FIELDS, acceleratorFromEvent, formatAccelerator, isMacPlatform, type HotkeyField } from "../lib/hotkeys";
 import {
-  applyWindowShape,
+  activateSettingsProfile,
+  applySettingsImport,
+  cancelSettingsImport,
+  createSettingsProfile,
+  deleteSettingsProfile,
+  duplicateSettingsProfile,
+  exportSettingsFile,
   getAppSettings,
+  getAppSettingsRevisioned,
+  getControlApiInfo,
+  getVirtualCameraInfo,
+  getHotkeyErrors,
   getRecordingRegion,
+  listCameras,
+  listSettingsProfiles,
+  moveDraftsDir,
   openSettingsWindow,
+  patchAppSettings,
+  pickDraftsDir,
   pickRecordingOutputDir,
+  previewSettingsImport,
+  regenerateControlApiToken,
+  renameSettingsProfile,
   resetRecordingRegion,
-  saveAppSettings,
-  setAlwaysOnTop,
-  setClickThrough,
   startRegionSelect,
   toggleKeyboardWindow,
   toggleRecordingWindow,
 } from "../lib/tauri";
-import { defaultSettings, type AppSettings, type CameraDevice, type CursorEffectStyle, type KeyboardDisplayStyle, type RecordingRegion, type ShapePreset } from "../types/app";
-import { I18nProvider, getMessages, useI18n, detectLocale, type Locale } from "../i18n";
+import { defaultSettings, type AppLocale, type AppSettings, type CameraDevice, type ControlApiInfo, type CursorEffectStyle, type KeyboardDisplayStyle, type ProfilesSummary, type RecordingRegion, type SettingsError, type SettingsImportPreview, type ShapePreset, type VirtualCameraInfo } from "../types/app";
+import { I18nProvider, LOCALES, getMessages, useI18n, detectLocale, type Locale } from "../i18n";
+
+/** Click, then press the new shortcut. Esc cancels; Backspace or Delete
+ *  unbinds. */
+function HotkeyInput({ value, onChange }: { value: string; onChange: (next: string) => void }) {
+  const t = useI18n();
+  const mac = isMacPlatform();
+  const [recording, setRecording] = useState(false);
+  return (
+    <button
+      type="button"
+      className={`settings-input hotkey-input${recording ? " recording" : ""}`}
+      onClick={() => setRecording(true)}
+      onBlur={() => setRecording(false)}
+      onKeyDown={(e) => {
+        if (!recording) return;
+        e.preventDefault();
+        const plain = !e.ctrlKey && !e.metaKey && !e.altKey && !e.shiftKey;
+        if (plain && e.code === "Escape") {
+          setRecording(false);
+        } else if (plain && (e.code === "Backspace" || e.code === "Delete")) {
+          setRecording(false);
+          onChange("");
+        } else {
+          const accelerator = acceleratorFromEvent(e.nativeEvent, mac);
+          if (accelerator) {
+            setRecording(false);
+            onChange(accelerator);
+          }
+        }
+      }}
+    >
+      {recording ? t.hotkey_recording : value ? <kbd>{formatAccelerator(value, mac)}</kbd> : t.hotkey_unbound}
+    </button>
+  );
+}
 
 function SettingsContent() {
   const t = useI18n();
   const [settings, setSettings] = useState<AppSettings>(defaultSettings);
   const [devices, setDevices] = useState<CameraDevice[]>([]);
+  const [nativeCameras, setNativeCameras] = useState<CameraDevice[]>([]);
   const [saving, setSaving] = useState(false);
   const [region, setRegion] = useState<RecordingRegion | null>(null);
+  const [draftsInfo, setDraftsInfo] = useState("");
+  const [resetFields, setResetFields] = useState<string[]>([]);
+  const [saveError, setSaveError] = useState("");
+  const [profiles, setProfiles] = useState<ProfilesSummary>({ active: null, profiles: [] });
+  const [profileName, setProfileName] = useState("");
+  const [profileError, setProfileError] = useState("");
+  // Last settings known to be stored and their revision; edits are sent as
+  // a patch against these so concurrent writes from other windows survive.
+  const storedRef = useRef<AppSettings>(defaultSettings);
+  const revisionRef = useRef<number | undefined>(undefined);
+  const [importPreview, setImportPreview] = useState<SettingsImportPreview | null>(null);
+  const [fileInfo, setFileInfo] = useState("");
+  const [controlInfo, setControlInfo] = useState<ControlApiInfo | null>(null);
+  const [portDraft, setPortDraft] = useState("");
+  const [virtualInfo, setVirtualInfo] = useState<VirtualCameraInfo | null>(null);
+  const [deviceDraft, setDeviceDraft] = useState("");
+  const [hotkeyErrors, setHotkeyErrors] = useState<HotkeyError[]>([]);
   const containerRef = useRef<HTMLDivElement | null>(null);
 
   const shapeOptions: Array<{ value: ShapePreset; label: string; desc: string }> = useMemo(() => [
@@ -41,6 +111,26 @@ function SettingsContent() {
     { value: "mickey", label: "Mickey", desc: t.shape_mickey_desc },
   ], [t]);
 
+  const selectedDevice = devices.find((d) => d.deviceId === settings.selectedCameraId);
+  // Mirror and beauty as they apply to the selected camera, and where
+  // edits to them go (see `withCameraSetting`).
+  const selectedOverride = cameraOverride(settings, selectedDevice);
+  const view = applyCameraOverride(settings, selectedDevice);
+
+  // What the driver says about the selected camera (Linux only for now).
+  const cameraHint = useMemo(() => {
+    const native = findNativeCamera(nativeCameras, selectedDevice?.label);
+    if (!native) return "";
+    if (native.busy) return t.camera_busy;
+    const mode = bestMode(native.modes);
+    return mode
+      ? t.camera_best_mode
+          .replace("{width}", String(mode.width))
+          .replace("{height}", String(mode.height))
+          .replace("{fps}", String(mode.frameRates[0] ?? "?"))
+      : "";
+  }, [nativeCameras, selectedDevice, t]);
+
   useEffect(() => {
     const load = async () => {
       // Request temporary camera access so enumerateDevices returns labels.
@@ -54,13 +144,18 @@ function SettingsContent() {
       } catch {
         // Permission denied — still try to enumerate
       }
-      const [nextSettings, cameraList, savedRegion] = await Promise.all([
-        getAppSettings(),
+      const [stored, cameraList, savedRegion, native] = await Promise.all([
+        getAppSettingsRevisioned(),
         listBrowserCameras(),
         getRecordingRegion(),
+        listCameras().catch(() => []),
       ]);
+      const nextSettings = stored.settings;
+      storedRef.current = nextSettings;
+      revisionRef.current = stored.revision;
       setSettings(nextSettings);
       setDevices(cameraList);
+      setNativeCameras(native);
       setRegion(savedRegion);
 
       // If no camera explicitly selected but devices are available,
@@ -77,10 +172,84 @@ function SettingsContent() {
   }, []);
 
   useEffect(() => {
-    const unlistenPromise = listen<AppSettings>(EVT.SETTINGS_UPDATED, (event) => {
-      setSettings(event.payload);
+    const unlistenPromise = listen<SettingsUpdatedPayload>(EVT.SETTINGS_UPDATED, (event) => {
+      storedRef.current = event.payload.settings;
+      revisionRef.current = event.payload.revision;
+      setSettings(event.payload.settings);
+      // The server (re)starts on the Rust side; show where it ended up.
+      if (event.payload.changed.some((key) => key.startsWith("controlApi"))) {
+        void getControlApiInfo().then(setControlInfo);
+      }
+      // The virtual camera was (re)opened, or failed to.
+      if (event.payload.changed.some((key) => key.startsWith("virtualCamera"))) {
+        void getVirtualCameraInfo().then(setVirtualInfo);
+      }
+      // Hotkeys were re-registered; some may now be free or taken.
+      if (event.payload.changed.some((key) => key.startsWith("hotkey"))) {
+        void getHotkeyErrors().then(setHotkeyErrors);
+      }
+    });
+
+    return () => {
+      void unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, []);
+
+  useEffect(() => {
+    void getControlApiInfo().then(setControlInfo).catch(() => setControlInfo(null));
+  }, []);
+
+  useEffect(() => {
+    setPortDraft(String(settings.controlApiPort));
+  }, [settings.controlApiPort]);
+
+  useEffect(() => {
+    void getVirtualCameraInfo().then(setVirtualInfo).catch(() => setVirtualInfo(null));
+  }, []);
+
+  useEffect(() => {
+    setDeviceDraft(settings.virtualCameraDevice ?? "");
+  }, [settings.virtualCameraDevice]);
+
+  useEffect(() => {
+    void getHotkeyErrors().then(setHotkeyErrors).catch(() => setHotkeyErrors([]));
+    const unlistenPromise = listen<HotkeyError>(EVT.HOTKEY_ERROR, () => {
+      void getHotkeyErrors().then(setHotkeyErrors);
+    });
+    return () => {
+      void unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, []);
+
+  // Profiles can also be switched from the tray or hotkey; keep the list
+  // and active marker current.
+  useEffect(() => {
+    void listSettingsProfiles().then(setProfiles).catch((err) => setProfileError(`${err}`));
+    const unlistenPromise = listen<ProfilesSummary>(EVT.PROFILES_UPDATED, (event) => {
+      setProfiles(event.payload);
     });
+    return () => {
+      void unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, []);
+
+  // An edit made to settings.json by hand (or a script) was invalid and
+  // ignored; valid ones simply arrive as SETTINGS_UPDATED.
+  useEffect(() => {
+    const unlistenPromise = listen<SettingsError>(EVT.SETTINGS_ERROR, (event) => {
+      setSaveError(`${t.settings_external_rejected}: ${event.payload.message}`);
+    });
+    return () => {
+      void unlistenPromise.then((unlisten) => unlisten());
+    };
+  }, [t]);
 
+  // Stored fields that failed to load were reset to defaults on the Rust
+  // side; name them so the user knows what to re-check.
+  useEffect(() => {
+    const unlistenPromise = listen<SettingsWarningPayload>(EVT.SETTINGS_WARNING, (event) => {
+      setResetFields(event.payload.warnings.map((w) => w.field || "*"));
+    });
     return () => {
       void unlistenPromise.then((unlisten) => unlisten());
     };
@@ -110,13 +279,106 @@ function SettingsContent() {
     );
   }, []);
 
+  const hotkeyLabels: Record<HotkeyField, string> = {
+    hotkeyToggleVisibility: t.hotkey_toggle_visibility,
+    hotkeyToggleLock: t.hotkey_toggle_lock,
+    hotkeyOpenSettings: t.hotkey_open_settings,
+    hotkeyToggleRecording: t.hotkey_toggle_recording,
+    hotkeyTogglePause: t.hotkey_toggle_pause,
+    hotkeyToggleKeyboard: t.hotkey_toggle_keyboard,
+    hotkeySelectRegion: t.hotkey_select_region,
+    hotkeyCycleShape: t.hotkey_cycle_shape,
+    hotkeyNextProfile: t.hotkey_next_profile,
+    hotkeyHoldToShow: t.hotkey_hold_to_show,
+    hotkeyHoldToRecord: t.hotkey_hold_to_record,
+  };
+
   const previewClass = useMemo(() => `preview-shape preview-${settings.shape}`, [settings.shape]);
 
   const commit = async (next: AppSettings) => {
     setSettings(next);
+    const patch = settingsPatch(storedRef.current, next);
+    if (Object.keys(patch).length === 0) return;
     setSaving(true);
-    await saveAppSettings(next);
-    setSaving(false);
+    try {
+      const updated = await patchAppSettings(patch, revisionRef.current);
+      storedRef.current = updated.settings;
+      revisionRef.current = updated.revision;
+      setSaveError("");
+    } catch (err) {
+      // Nothing was stored; show which fields were rejected (or that
+      // another window saved first) and fall back to what is persisted.
+      const e = err as Partial<SettingsError>;
+      setSaveError(
+        e.currentRevision != null
+          ? t.settings_changed_elsewhere
+          : e.fields?.length
+            ? e.fields.map((f) => `${f.field}: ${f.message}`).join("; ")
+            : `${e.message ?? err}`,
+      );
+      const stored = await getAppSettingsRevisioned();
+      storedRef.current = stored.settings;
+      revisionRef.current = stored.revision;
+      setSettings(stored.settings);
+    } finally {
+      setSaving(false);
+    }
+  };
+
+  // Every profile command resolves with the updated list; the settings
+  // themselves arrive through SETTINGS_UPDATED when the active one changes.
+  const runProfileAction = async (action: () => Promise<ProfilesSummary>) => {
+    try {
+      setProfiles(await action());
+      setProfileError("");
+      setProfileName("");
+    } catch (err) {
+      setProfileError(`${err}`);
+    }
+  };
+  const activeProfile = profiles.active ?? "";
+
+  // Importing is two-step: the Rust side decodes the file and holds it
+  // while the user reviews the changes, then applies or drops it.
+  const runFileAction = async (action: () => Promise<void>) => {
+    try {
+      await action();
+    } catch (err) {
+      setFileInfo(`${err}`);
+    }
+  };
+  const exportSettings = () =>
+    runFileAction(async () => {
+      const path = await exportSettingsFile();
+      setFileInfo(path ? `${t.settings_exported} ${path}` : "");
+    });
+  const previewImport = () =>
+    runFileAction(async () => {
+      setImportPreview(await previewSettingsImport(devices.map((d) => d.deviceId)));
+      setFileInfo("");
+    });
+  const finishImport = (apply: boolean) =>
+    runFileAction(async () => {
+      setImportPreview(null);
+      await (apply ? applySettingsImport() : cancelSettingsImport());
+    });
+  const formatValue = (value: unknown) => (value === null || value === undefined ? "—" : JSON.stringify(value));
+
+  // Moving drafts persists the setting on the Rust side (and broadcasts
+  // SETTINGS_UPDATED), so this does not go through `commit`.
+  const relocateDrafts = async (target: string | null) => {
+    setSaving(true);
+    try {
+      const report = await moveDraftsDir(target);
+      setDraftsInfo(
+        `${t.drafts_dir_moved}: ${report.moved}` +
+          (report.skipped.length ? ` · ${t.drafts_dir_skipped}: ${report.skipped.length}` : ""),
+      );
+    } catch (err) {
+      setDraftsInfo(`${err}`);
+    } finally {
+      setSaving(false);
+    }
   };
 
   return (
@@ -127,22 +389,254 @@ function SettingsContent() {
           <Select
             value={settings.locale || detectLocale()}
             onValueChange={(value) => {
-              void commit({ ...settings, locale: value });
+              void commit({ ...settings, locale: value as AppLocale });
             }}
           >
             <SelectTrigger style={{ width: 90, height: 26, fontSize: 12, padding: "0 8px" }}>
               <SelectValue />
             </SelectTrigger>
             <SelectContent>
-              <SelectItem value="zh-CN">中文</SelectItem>
-              <SelectItem value="en">English</SelectItem>
+              {LOCALES.map((tag) => (
+                <SelectItem key={tag} value={tag}>{getMessages(tag).language_name}</SelectItem>
+              ))}
             </SelectContent>
           </Select>
         </div>
         <h1>Cam Window Styling</h1>
         <p>{t.settings_subtitle}</p>
+        {resetFields.length > 0 && (
+          <p className="settings-warning">
+            {t.settings_fields_reset}: {resetFields.join(", ")}
+          </p>
+        )}
+        {saveError && (
+          <p className="settings-warning">
+            {t.settings_save_failed}: {saveError}
+          </p>
+        )}
       </header>
 
+      <Card className="settings-section">
+        <div className="section-title">
+          <Layers size={16} />
+          <h2>{t.profiles}</h2>
+        </div>
+        <Label htmlFor="profile-select">{t.profile_active}</Label>
+        <Select
+          value={activeProfile}
+          onValueChange={(id) => {
+            if (id && id !== activeProfile) {
+              void runProfileAction(() => activateSettingsProfile(id));
+            }
+          }}
+        >
+          <SelectTrigger id="profile-select">
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {profiles.profiles.map((p) => (
+              <SelectItem key={p.id} value={p.id}>
+                {p.name}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+        <p className="hint">{t.profile_hint}</p>
+        <div className="setting-row">
+          <input
+            className="settings-input"
+            value={profileName}
+            placeholder={t.profile_name_placeholder}
+            onChange={(e) => setProfileName(e.target.value)}
+          />
+          <div style={{ display: "flex", gap: 6 }}>
+            <Button
+              variant="secondary"
+              disabled={!profileName.trim()}
+              onClick={() => void runProfileAction(() => createSettingsProfile(profileName))}
+              style={{ height: 28, fontSize: 12, padding: "0 10px" }}
+            >
+              {t.profile_create}
+            </Button>
+            <Button
+              variant="secondary"
+              disabled={!profileName.trim() || !activeProfile}
+              onClick={() => void runProfileAction(() => duplicateSettingsProfile(activeProfile, profileName))}
+              style={{ height: 28, fontSize: 12, padding: "0 10px" }}
+            >
+              {t.profile_duplicate}
+            </Button>
+            <Button
+              variant="secondary"
+              disabled={!profileName.trim() || !activeProfile}
+              onClick={() => void runProfileAction(() => renameSettingsProfile(activeProfile, profileName))}
+              style={{ height: 28, fontSize: 12, padding: "0 10px" }}
+            >
+              {t.profile_rename}
+            </Button>
+          </div>
+        </div>
+        {profiles.profiles.length > 1 && (
+          <div className="setting-row">
+            <p className="hint">{t.profile_delete_hint}</p>
+            <div style={{ display: "flex", gap: 6, flexWrap: "wrap" }}>
+              {profiles.profiles
+                .filter((p) => p.id !== activeProfile)
+                .map((p) => (
+                  <Button
+                    key={p.id}
+                    variant="secondary"
+                    onClick={() => void runProfileAction(() => deleteSettingsProfile(p.id))}
+                    style={{ height: 28, fontSize: 12, padding: "0 10px" }}
+                  >
+                    {t.profile_delete} {p.name}
+                  </Button>
+                ))}
+            </div>
+          </div>
+        )}
+        {profileError && <p className="settings-warning">{profileError}</p>}
+      </Card>
+
+      <Card className="settings-section">
+        <div className="section-title">
+          <FileJson size={16} />
+          <h2>{t.settings_file}</h2>
+        </div>
+        <p className="hint">{t.settings_file_hint}</p>
+        <div className="setting-row" style={{ display: "flex", gap: 6 }}>
+          <Button variant="secondary" onClick={() => void exportSettings()} style={{ height: 28, fontSize: 12, padding: "0 10px" }}>
+            {t.settings_export}
+          </Button>
+          <Button variant="secondary" onClick={() => void previewImport()} style={{ height: 28, fontSize: 12, padding: "0 10px" }}>
+            {t.settings_import}
+          </Button>
+        </div>
+        {importPreview && (
+          <div className="setting-row">
+            {importPreview.changes.length > 0 ? (
+              <>
+                <p className="hint">
+                  {t.settings_import_changes} {importPreview.path}
+                </p>
+                <ul className="import-changes">
+                  {importPreview.changes.map((c) => (
+                    <li key={c.key}>
+                      <code>{c.key}</code>: {formatValue(c.from)} → {formatValue(c.to)}
+                    </li>
+                  ))}
+                </ul>
+              </>
+            ) : (
+              <p className="hint">{t.settings_import_no_changes}</p>
+            )}
+            {importPreview.warnings.length > 0 && (
+              <p className="settings-warning">
+                {t.settings_import_warnings}:{" "}
+                {importPreview.warnings.map((w) => `${w.field || "*"}: ${w.message}`).join("; ")}
+              </p>
+            )}
+            <div style={{ display: "flex", gap: 6 }}>
+              <Button
+                disabled={importPreview.changes.length === 0}
+                onClick={() => void finishImport(true)}
+                style={{ height: 28, fontSize: 12, padding: "0 10px" }}
+              >
+                {t.settings_import_apply}
+              </Button>
+              <Button variant="secondary" onClick={() => void finishImport(false)} style={{ height: 28, fontSize: 12, padding: "0 10px" }}>
+                {t.settings_import_cancel}
+              </Button>
+            </div>
+          </div>
+        )}
+        {fileInfo && <p className="hint">{fileInfo}</p>}
+      </Card>
+
+      <Card className="settings-section">
+        <div className="section-title">
+          <Radio size={16} />
+          <h2>{t.control_api}</h2>
+        </div>
+        <p className="hint">{t.control_api_hint}</p>
+        <div className="setting-row">
+          <div>
+            <Label>{t.control_api_enable}</Label>
+            <p className="hint">
+              {!settings.controlApiEnabled
+                ? ""
+                : controlInfo?.port != null
+                  ? `${t.control_api_listening} 127.0.0.1:${controlInfo.port}`
+                  : `${t.control_api_failed}: ${controlInfo?.error ?? ""}`}
+            </p>
+          </div>
+          <Switch
+            checked={settings.controlApiEnabled}
+            onCheckedChange={(checked) => {
+              void commit({ ...settings, controlApiEnabled: checked });
+            }}
+          />
+        </div>
+        {settings.controlApiEnabled && (
+          <>
+            <div className="setting-row">
+              <Label htmlFor="control-api-port">{t.control_api_port}</Label>
+              <input
+                id="control-api-port"
+                className="settings-input"
+                inputMode="numeric"
+                value={portDraft}
+                onChange={(e) => setPortDraft(e.target.value)}
+                onBlur={() => {
+                  const port = Number(portDraft);
+                  if (portDraft.trim() && port !== settings.controlApiPort) {
+                    void commit({ ...settings, controlApiPort: port });
+                  }
+                }}
+                style={{ width: 90 }}
+              />
+            </div>
+            <div className="setting-row">
+              <div>
+                <Label>{t.control_api_obs}</Label>
+                <p className="hint">{t.control_api_obs_hint}</p>
+              </div>
+              <Switch
+                checked={settings.controlApiObsCompat}
+                onCheckedChange={(checked) => {
+                  void commit({ ...settings, controlApiObsCompat: checked });
+                }}
+              />
+            </div>
+            <div className="setting-row">
+              <div>
+                <Label>{t.control_api_token}</Label>
+                <p className="hint" style={{ wordBreak: "break-all" }}>
+                  <code>{controlInfo?.token}</code>
+                </p>
+              </div>
+              <div style={{ display: "flex", gap: 6 }}>
+                <Button
+                  variant="secondary"
+                  disabled={!controlInfo}
+                  onClick={() => void navigator.clipboard.writeText(controlInfo?.token ?? "")}
+                  style={{ height: 28, fontSize: 12, padding: "0 10px" }}
+                >
+                  {t.control_api_copy}
+                </Button>
+                <Button
+                  variant="secondary"
+                  onClick={() => void regenerateControlApiToken().then(setControlInfo)}
+                  style={{ height: 28, fontSize: 12, padding: "0 10px" }}
+                >
+                  {t.control_api_regenerate}
+                </Button>
+              </div>
+            </div>
+          </>
+        )}
+      </Card>
+
       <Card className="settings-section">
         <div className="section-title">
           <Camera size={16} />
@@ -169,6 +663,86 @@ function SettingsContent() {
               ))}
           </SelectContent>
         </Select>
+        {cameraHint && <p className="hint">{cameraHint}</p>}
+        <div className="setting-row">
+          <p className="hint">{t.camera_priority_hint}</p>
+          <div style={{ display: "flex", gap: 6, flexWrap: "wrap" }}>
+            {settings.cameraPriority.map((entry) => (
+              <Button
+                key={entry}
+                variant="secondary"
+                title={t.camera_priority_remove}
+                onClick={() => void commit({ ...settings, cameraPriority: settings.cameraPriority.filter((e) => e !== entry) })}
+                style={{ height: 28, fontSize: 12, padding: "0 10px" }}
+              >
+                {devices.find((d) => d.deviceId === entry)?.label ?? entry} ×
+              </Button>
+            ))}
+          </div>
+        </div>
+        {/* Stored by label, which survives the ids changing across docks and machines. */}
+        <Select
+          value=""
+          onValueChange={(label) => void commit({ ...settings, cameraPriority: [...settings.cameraPriority, label] })}
+        >
+          <SelectTrigger>
+            <SelectValue placeholder={t.camera_priority_add} />
+          </SelectTrigger>
+          <SelectContent>
+            {devices
+              .filter((device) => device.label && !settings.cameraPriority.includes(device.label))
+              .map((device) => (
+                <SelectItem key={device.deviceId} value={device.label}>
+                  {device.label}
+                </SelectItem>
+              ))}
+          </SelectContent>
+        </Select>
+        {selectedDevice && (
+          <div className="setting-row">
+            <div>
+              <Label>{t.camera_override}</Label>
+              <p className="hint">{t.camera_override_hint.replace("{camera}", selectedOverride?.match ?? selectedDevice.label)}</p>
+            </div>
+            <Switch
+              checked={!!selectedOverride}
+              onCheckedChange={(checked) => {
+                const cameraOverrides = checked
+                  ? [
+                      // Starts from what the camera shows now; label, not id, so
+                      // it still applies when the camera is plugged in elsewhere.
+                      { match: selectedDevice.label, mirror: view.mirror, beauty: view.beauty, beautySmoothness: view.beautySmoothness, beautyBrightness: view.beautyBrightness },
+                      ...settings.cameraOverrides,
+                    ]
+                  : settings.cameraOverrides.filter((o) => o !== selectedOverride);
+                void commit({ ...settings, cameraOverrides });
+              }}
+            />
+          </div>
+        )}
+        {selectedOverride && (
+          <div className="setting-row">
+            <Label>{t.camera_max_resolution}</Label>
+            <Select
+              value={String(selectedOverride.maxHeight ?? "auto")}
+              onValueChange={(value) => {
+                const maxHeight = value === "auto" ? undefined : Number(value);
+                const cameraOverrides = settings.cameraOverrides.map((o) => (o === selectedOverride ? { ...o, maxHeight } : o));
+                void commit({ ...settings, cameraOverrides });
+              }}
+            >
+              <SelectTrigger style={{ width: 140, height: 28, fontSize: 12, padding: "0 8px" }}>
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="auto">{t.camera_max_resolution_auto}</SelectItem>
+                {[1080, 720, 480].map((height) => (
+                  <SelectItem key={height} value={String(height)}>{height}p</SelectItem>
+                ))}
+              </SelectContent>
+            </Select>
+          </div>
+        )}
       </Card>
 
       <Card className="settings-section">
@@ -207,7 +781,6 @@ function SettingsContent() {
               className={`shape-card ${settings.shape === shape.value ? "active" : ""}`}
               onClick={() => {
                 const next = { ...settings, shape: shape.value };
-                void applyWindowShape(shape.value);
                 void commit(next);
                 gsap.fromTo(
                   ".preview-shape",
@@ -254,9 +827,9 @@ function SettingsContent() {
             <p className="hint">{t.mirror_hint}</p>
           </div>
           <Switch
-            checked={settings.mirror}
+            checked={view.mirror}
             onCheckedChange={(checked) => {
-              void commit({ ...settings, mirror: checked });
+              void commit(withCameraSetting(settings, selectedDevice, "mirror", checked));
             }}
           />
         </div>
@@ -269,7 +842,6 @@ function SettingsContent() {
           <Switch
             checked={settings.alwaysOnTop}
             onCheckedChange={(checked) => {
-              void setAlwaysOnTop(checked);
               void commit({ ...settings, alwaysOnTop: checked });
             }}
           />
@@ -283,7 +855,6 @@ function SettingsContent() {
           <Switch
             checked={settings.clickThrough}
             onCheckedChange={(checked) => {
-              void setClickThrough(checked);
               void commit({ ...settings, clickThrough: checked });
             }}
           />
@@ -315,41 +886,128 @@ function SettingsContent() {
             <p className="hint">{t.beauty_hint}</p>
           </div>
           <Switch
-            checked={settings.beauty}
+            checked={view.beauty}
             onCheckedChange={(checked) => {
-              void commit({ ...settings, beauty: checked });
+              void commit(withCameraSetting(settings, selectedDevice, "beauty", checked));
             }}
           />
         </div>
 
-        {settings.beauty && (
+        {view.beauty && (
           <>
             <div className="space-y-2" style={{ padding: "10px 0" }}>
               <Label>{t.smoothness}</Label>
               <Slider
-                value={[settings.beautySmoothness]}
+                value={[view.beautySmoothness]}
                 min={0}
                 max={100}
                 step={1}
                 onValueChange={(value) => {
-                  void commit({ ...settings, beautySmoothness: value[0] });
+                  void commit(withCameraSetting(settings, selectedDevice, "beautySmoothness", value[0]));
                 }}
               />
-              <p className="hint">{t.current_prefix} {settings.beautySmoothness}%</p>
+              <p className="hint">{t.current_prefix} {view.beautySmoothness}%</p>
             </div>
 
             <div className="space-y-2" style={{ padding: "10px 0" }}>
               <Label>{t.brightness}</Label>
               <Slider
-                value={[settings.beautyBrightness]}
+                value={[view.beautyBrightness]}
                 min={0}
                 max={100}
                 step={1}
                 onValueChange={(value) => {
-                  void commit({ ...settings, beautyBrightness: value[0] });
+                  void commit(withCameraSetting(settings, selectedDevice, "beautyBrightness", value[0]));
+                }}
+              />
+              <p className="hint">{t.current_prefix} {view.beautyBrightness}%{t.brightness_original_hint}</p>
+            </div>
+          </>
+        )}
+      </Card>
+
+      <Card className="settings-section">
+        <div className="section-title">
+          <Webcam size={16} />
+          <h2>{t.virtual_camera}</h2>
+        </div>
+        <p className="hint">{t.virtual_camera_hint}</p>
+        <div className="setting-row">
+          <div>
+            <Label>{t.virtual_camera_enable}</Label>
+            <p className="hint">
+              {!settings.virtualCameraEnabled
+                ? ""
+                : virtualInfo?.device
+                  ? `${t.virtual_camera_publishing} ${virtualInfo.device}`
+                  : `${t.virtual_camera_failed}: ${virtualInfo?.error ?? ""}`}
+            </p>
+          </div>
+          <Switch
+            checked={settings.virtualCameraEnabled}
+            onCheckedChange={(checked) => {
+              void commit({ ...settings, virtualCameraEnabled: checked });
+            }}
+          />
+        </div>
+        {settings.virtualCameraEnabled && (
+          <>
+            <div className="setting-row">
+              <Label>{t.virtual_camera_resolution}</Label>
+              <Select
+                value={`${settings.virtualCameraWidth}x${settings.virtualCameraHeight}`}
+                onValueChange={(value) => {
+                  const [width, height] = value.split("x").map(Number);
+                  void commit({ ...settings, virtualCameraWidth: width, virtualCameraHeight: height });
+                }}
+              >
+                <SelectTrigger style={{ width: 140, height: 28, fontSize: 12, padding: "0 8px" }}>
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  {["640x360", "1280x720", "1920x1080"].map((size) => (
+                    <SelectItem key={size} value={size}>{size.replace("x", "×")}</SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+            </div>
+            <div className="setting-row">
+              <Label>{t.virtual_camera_fps}</Label>
+              <Select
+                value={String(settings.virtualCameraFps)}
+                onValueChange={(value) => {
+                  void commit({ ...settings, virtualCameraFps: Number(value) });
                 }}
+              >
+                <SelectTrigger style={{ width: 140, height: 28, fontSize: 12, padding: "0 8px" }}>
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  {[15, 24, 30].map((fps) => (
+                    <SelectItem key={fps} value={String(fps)}>{fps} fps</SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+            </div>
+            <div className="setting-row">
+              <div>
+                <Label htmlFor="virtual-camera-device">{t.virtual_camera_device}</Label>
+                <p className="hint">{t.virtual_camera_device_hint}</p>
+              </div>
+              <input
+                id="virtual-camera-device"
+                className="settings-input"
+                value={deviceDraft}
+                placeholder="/dev/video10"
+                onChange={(e) => setDeviceDraft(e.target.value)}
+                onBlur={() => {
+                  const device = deviceDraft.trim() || undefined;
+                  if (device !== settings.virtualCameraDevice) {
+                    void commit({ ...settings, virtualCameraDevice: device });
+                  }
+                }}
+                style={{ width: 140 }}
               />
-              <p className="hint">{t.current_prefix} {settings.beautyBrightness}%{t.brightness_original_hint}</p>
             </div>
           </>
         )}
@@ -522,6 +1180,40 @@ function SettingsContent() {
           </div>
         </div>
 
+        <div className="setting-row">
+          <div>
+            <Label>{t.drafts_dir}</Label>
+            <p className="hint" style={{ wordBreak: "break-all" }}>
+              {settings.draftsDir || t.drafts_dir_default}
+              {draftsInfo ? ` — ${draftsInfo}` : ""}
+            </p>
+          </div>
+          <div style={{ display: "flex", gap: 6 }}>
+            <Button
+              variant="secondary"
+              onClick={async () => {
+                const dir = await pickDraftsDir();
+                if (dir) {
+                  void relocateDrafts(dir);
+                }
+              }}
+              style={{ height: 28, fontSize: 12, padding: "0 10px" }}
+            >
+              {t.recording_output_dir_pick}
+            </Button>
+            <Button
+              variant="secondary"
+              disabled={!settings.draftsDir}
+              onClick={() => {
+                void relocateDrafts(null);
+              }}
+              style={{ height: 28, fontSize: 12, padding: "0 10px" }}
+            >
+              {t.recording_output_dir_reset}
+            </Button>
+          </div>
+        </div>
+
         <div className="space-y-2" style={{ padding: "10px 0" }}>
           <Label>{t.recording_fps}</Label>
           <Slider
@@ -611,15 +1303,33 @@ function SettingsContent() {
         )}
       </Card>
 
-      <Card className="settings-section hotkey-card">
+      <Card className="settings-section">
         <div className="section-title">
           <Sparkles size={16} />
           <h2>{t.hotkeys}</h2>
         </div>
-        <p><kbd>Cmd/Ctrl + Shift + V</kbd> {t.hotkey_toggle_visibility}</p>
-        <p><kbd>Cmd/Ctrl + Shift + L</kbd> {t.hotkey_toggle_lock}</p>
-        <p><kbd>Cmd/Ctrl + Shift + ,</kbd> {t.hotkey_open_settings}</p>
-        <p><kbd>Cmd/Ctrl + Shift + R</kbd> {t.hotkey_toggle_recording}</p>
+        <p className="hint">{t.hotkeys_hint}</p>
+        {HOTKEY_FIELDS.map((field) => {
+          const refused = hotkeyErrors.find((e) => e.field === field);
+          return (
+            <div className="setting-row" key={field}>
+              <div>
+                <Label>{hotkeyLabels[field]}</Label>
+                {refused && (
+                  <p className="settings-warning" title={refused.message}>
+                    {t.hotkey_refused}
+                  </p>
+                )}
+              </div>
+              <HotkeyInput
+                value={settings[field]}
+                onChange={(next) => {
+                  void commit({ ...settings, [field]: next });
+                }}
+              />
+            </div>
+          );
+        })}
       </Card>
 
       <footer className="settings-footer settings-section">
@@ -647,9 +1357,9 @@ export function SettingsWindow() {
   }, []);
 
   useEffect(() => {
-    const unlistenPromise = listen<AppSettings>(EVT.SETTINGS_UPDATED, (event) => {
-      if (event.payload.locale) {
-        setLocale(event.payload.locale as Locale);
+    const unlistenPromise = listen<SettingsUpdatedPayload>(EVT.SETTINGS_UPDATED, (event) => {
+      if (event.payload.settings.locale) {
+        setLocale(event.payload.settings.locale as Locale);
       }
     });
     return () => { void unlistenPromise.then((unlisten) => unlisten()); };